@@ -0,0 +1,83 @@
+use crate::utils::range::Range;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AliasKind {
+  Column, // SELECT price AS p, AGGREGATE SUM(x) AS total
+  Table,  // FROM users AS u, JOIN accounts AS a
+}
+
+#[derive(Debug, Clone)]
+pub struct AliasBinding {
+  pub name: String,
+  pub kind: AliasKind,
+  pub range: Range,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+  pub qualifier: Option<String>,
+  pub name: String,
+  pub range: Range,
+}
+
+// Aliases bound and referenced in one query
+pub struct AliasMap {
+  bindings: Vec<AliasBinding>,
+  references: Vec<Reference>,
+  projections: Vec<Range>, // SELECT and AGGREGATE stages, which drop unreferenced columns
+}
+
+impl AliasMap {
+  pub fn new() -> Self {
+    Self { bindings: vec![], references: vec![], projections: vec![] }
+  }
+
+  pub fn clear(&mut self) {
+    self.bindings.clear();
+    self.references.clear();
+    self.projections.clear();
+  }
+
+  // returns the earlier binding when the alias is already bound in this scope
+  pub fn bind(&mut self, name: &str, kind: AliasKind, range: Range) -> Option<AliasBinding> {
+    let previous = self.bindings.iter().find(|binding| binding.kind == kind && binding.name == name).cloned();
+    self.bindings.push(AliasBinding { name: name.to_string(), kind, range });
+    previous
+  }
+
+  pub fn reference(&mut self, qualifier: Option<&str>, name: &str, range: Range) {
+    let qualifier = qualifier.map(|qualifier| qualifier.to_string());
+    self.references.push(Reference { qualifier, name: name.to_string(), range });
+  }
+
+  pub fn projection(&mut self, range: Range) {
+    self.projections.push(range);
+  }
+
+  pub fn references(&self) -> &[Reference] {
+    &self.references
+  }
+
+  pub fn is_column_alias(&self, name: &str) -> bool {
+    self.bindings.iter().any(|binding| binding.kind == AliasKind::Column && binding.name == name)
+  }
+
+  pub fn unused(&self) -> Vec<AliasBinding> {
+    self.bindings.iter().filter(|binding| !self.is_used(binding)).cloned().collect()
+  }
+
+  fn is_used(&self, binding: &AliasBinding) -> bool {
+    match binding.kind {
+      // `SELECT u.name FROM users AS u` references the alias before binding it
+      AliasKind::Table => self.references.iter().any(|reference| reference.qualifier.as_ref() == Some(&binding.name)),
+      // a column alias only goes unused when a later stage projects it away
+      AliasKind::Column => {
+        let is_downstream = |range: &Range| range.start >= binding.range.end;
+        let referenced = self.references.iter().any(|reference| {
+          reference.qualifier.is_none() && reference.name == binding.name && is_downstream(&reference.range)
+        });
+        referenced || !self.projections.iter().any(is_downstream)
+      }
+    }
+  }
+}
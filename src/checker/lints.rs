@@ -0,0 +1,37 @@
+use crate::diagnostics::codes::rule_code;
+
+// how many distinct tables a query may join before `wide-join` warns
+pub const MAX_JOINED_TABLES: usize = 10;
+
+// Lints that can be turned on and off individually, by the names `spipe:allow` comments use
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LintConfig {
+  disabled: Vec<&'static str>, // codes of the rules turned off
+  pub max_joined_tables: usize,
+}
+
+impl LintConfig {
+  pub fn new() -> Self {
+    Self { disabled: vec![], max_joined_tables: MAX_JOINED_TABLES }
+  }
+
+  pub fn with_max_joined_tables(mut self, max_joined_tables: usize) -> Self {
+    self.max_joined_tables = max_joined_tables;
+    self
+  }
+
+  pub fn set(&mut self, name: &str, enabled: bool) -> bool {
+    let Some(code) = rule_code(name) else {
+      return false;
+    };
+    self.disabled.retain(|disabled| *disabled != code);
+    if !enabled {
+      self.disabled.push(code);
+    }
+    true
+  }
+
+  pub fn enabled(&self, code: &str) -> bool {
+    !self.disabled.contains(&code)
+  }
+}
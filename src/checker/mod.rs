@@ -1,26 +1,185 @@
 #![allow(dead_code)]
-use crate::diagnostics::maneger::{DiagnosticsManager, TypeError};
+mod aliases;
+mod lints;
+mod schema;
+mod suppressions;
+
+use crate::diagnostics::maneger::{Diagnostic, DiagnosticsManager, Replacement, ReportFilter, TypeError};
+use crate::emiter::{Dialect, EmitOptions, ToSql};
+use crate::lexer::Token;
 use crate::parser::ast::*;
+use crate::utils::range::Range;
 use crate::utils::source::Source;
+use crate::utils::ColorMode;
+use aliases::{AliasKind, AliasMap};
+pub use lints::LintConfig;
+pub use schema::Schema;
+
+// names seen in the current scope, with where each was first seen
+type Seen = Vec<(String, Range)>;
+
+// what a string literal is searched for, lowercased with its whitespace collapsed, and how it is described
+const INJECTION_PATTERNS: [(&str, &str); 8] = [
+  ("' or ", "`' OR`"),
+  ("' and ", "`' AND`"),
+  ("'='", "`'='`"),
+  ("';", "`';`"),
+  ("; drop ", "`; DROP`"),
+  ("; delete ", "`; DELETE`"),
+  ("--", "`--`"),
+  ("/*", "`/*`"),
+];
+
+// what the last projecting stage of a query left in scope, for ORDER BY to resolve its keys against
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Projection {
+  Source,               // nothing projected yet, the tables' columns are all there is
+  Columns(usize),       // a SELECT, the tables' columns stay in scope next to the selected ones
+  Grouped(Vec<String>), // an AGGREGATE, only its alias and the GROUP BY columns remain
+  Reshaped,             // PIVOT and UNPIVOT, whose columns come from the data
+}
 
 pub struct Checker {
   diagnostics: DiagnosticsManager,
-  tables: Vec<String>,
-  columns: Vec<String>,
+  tables: Seen,
+  joined: Seen,              // tables the current scope joins
+  scopes: Vec<(Seen, Seen)>, // tables and joined tables saved by `push_scope`
+  aliases: AliasMap,
+  lints: LintConfig,
+  schema: Option<Schema>,
+  dialect: Dialect,
+  keep_qualify: bool, // QUALIFY is emitted as written, so it needs a dialect that has it
+  sources: Vec<(String, Option<String>)>, // tables read by the query, with their alias
+  windows: Seen,      // names given by the WINDOW stages of the current chain
+  casts: Seen,        // the type each cast converts to, for type inference to build on
+  projection: Projection,
+  transaction: bool, // a BEGIN is open, COMMIT or ROLLBACK closes it
+  ctes: Seen,        // names the WITH of the current query defines, read like tables
+  included: Seen,    // names the included files define or read, known to every query
+  strict: bool,      // every diagnostic fails the check, warnings and infos too
 }
 
 impl Checker {
   pub fn new() -> Self {
-    Self { diagnostics: DiagnosticsManager::new(), tables: vec![], columns: vec![] }
+    let lints = LintConfig::new();
+    let aliases = AliasMap::new();
+    let diagnostics = DiagnosticsManager::new();
+    Self {
+      diagnostics,
+      tables: vec![],
+      joined: vec![],
+      scopes: vec![],
+      aliases,
+      lints,
+      schema: None,
+      dialect: Dialect::default(),
+      keep_qualify: false,
+      sources: vec![],
+      windows: vec![],
+      casts: vec![],
+      projection: Projection::Source,
+      transaction: false,
+      ctes: vec![],
+      included: vec![],
+      strict: false,
+    }
+  }
+
+  pub fn with_strict_mode(mut self) -> Self {
+    self.strict = true;
+    self
+  }
+
+  pub fn with_lints(&mut self, lints: LintConfig) {
+    self.lints = lints;
+  }
+
+  pub fn with_schema(&mut self, schema: Schema) {
+    self.schema = Some(schema);
+  }
+
+  pub fn with_target(&mut self, dialect: Dialect, keep_qualify: bool) {
+    self.dialect = dialect;
+    self.keep_qualify = keep_qualify;
+  }
+
+  // a program parsed before the checked one, its ctes and the tables it reads are known by name;
+  // it is not checked itself
+  pub fn with_included(&mut self, program: &Program) {
+    program.statements.iter().for_each(|statement| self.include_statement(statement));
+  }
+
+  fn include_statement(&mut self, stmt: &Statement) {
+    if let Statement::With(with) = stmt {
+      for cte in &with.ctes {
+        self.included.push((cte.name().to_string(), cte.get_range()));
+        cte.branches().for_each(|query| self.include_statement(query));
+      }
+      return self.include_statement(&with.body);
+    }
+    for clause in stmt.clauses() {
+      let table = match clause {
+        Statement::From(from) => &from.table,
+        Statement::Join(join) => &join.table,
+        Statement::Select(SelectStatement { from: Some(from), .. }) => &from.table,
+        _ => continue,
+      };
+      self.included.push((table.lexeme.clone().unwrap_or_default(), table.range.clone()));
+    }
   }
 
   pub fn check(&mut self, program: &Program) {
-    for stmt in &program.statements {
-      self.check_statement(stmt);
+    self.transaction = false;
+    for query in program.queries() {
+      // every `;`-separated query has its own aliases, `check_statement` scopes tables
+      self.aliases.clear();
+      self.sources.clear();
+      self.ctes.clear();
+      self.projection = Projection::Source;
+      for stmt in query {
+        self.check_statement(stmt);
+      }
+      self.check_unused_aliases();
+      self.check_unknown_columns();
+      self.check_ungrouped_columns(query);
+      self.check_percentiles(&query.iter().flat_map(Statement::clauses).collect::<Vec<_>>());
     }
+    // rules turned off are dropped before suppressions, so they do not count as suppressed
+    self.diagnostics.diagnostics.retain(|diagnostic| self.lints.enabled(diagnostic.code));
+    self.apply_suppressions(program);
+  }
+
+  // drops what `spipe:allow` comments silence, the count is kept for `--report-suppressed`
+  fn apply_suppressions(&mut self, program: &Program) {
+    let (suppressions, unknown) = suppressions::collect(program);
+    let found = self.diagnostics.diagnostics.len();
+    self.diagnostics.diagnostics.retain(|diagnostic| !suppressions.iter().any(|s| s.covers(diagnostic)));
+    self.diagnostics.suppressed += found - self.diagnostics.diagnostics.len();
+    unknown.into_iter().for_each(|diagnostic| self.diagnostics.add(diagnostic));
   }
 
+  // each top-level statement is its own pipe chain, even without a `;` between them
   pub fn check_statement(&mut self, stmt: &Statement) {
+    // a WINDOW stage may come after the stages using its names
+    let definitions = stmt.window_definitions().into_iter();
+    self.windows = definitions.map(|definition| (definition.name().to_string(), definition.range.clone())).collect();
+    self.push_scope();
+    self.check_clause(stmt);
+    self.pop_scope();
+  }
+
+  pub fn push_scope(&mut self) {
+    self.scopes.push((std::mem::take(&mut self.tables), std::mem::take(&mut self.joined)));
+  }
+
+  pub fn pop_scope(&mut self) {
+    if let Some((tables, joined)) = self.scopes.pop() {
+      self.tables = tables;
+      self.joined = joined;
+    }
+  }
+
+  fn check_clause(&mut self, stmt: &Statement) {
     match stmt {
       Statement::Select(select) => self.check_select(select),
       Statement::From(from) => self.check_from(from),
@@ -28,27 +187,64 @@ impl Checker {
       Statement::Where(where_clause) => self.check_where(where_clause),
       Statement::GroupBy(group_by) => self.check_group_by(group_by),
       Statement::Aggregate(agg) => self.check_aggregate(agg),
+      Statement::Pivot(pivot) => self.check_pivot(pivot),
+      Statement::Unpivot(unpivot) => self.check_unpivot(unpivot),
+      Statement::Qualify(qualify) => self.check_qualify(qualify),
+      Statement::Window(window) => self.reference_windows(window),
+      Statement::Order(order) => self.check_order(order),
       Statement::Pipe(pipe) => self.check_pipe(pipe),
+      Statement::Limit(limit) => self.check_limit(limit),
+      Statement::Copy(copy) => self.check_copy(copy),
+      Statement::Insert(insert) => self.check_insert(insert),
+      Statement::Truncate(truncate) => self.check_truncate(truncate),
+      Statement::AlterTable(alter) => self.check_alter_table(alter),
+      Statement::CreateIndex(index) => self.check_create_index(index),
+      Statement::DropIndex(index) => self.check_drop_index(index),
+      Statement::CommentOn(comment) => self.check_comment_on(comment),
+      Statement::Pragma(pragma) => self.check_pragma(pragma),
+      Statement::Grant(grant) => self.check_access_control("GRANT", &grant.object, grant.get_range()),
+      Statement::Revoke(revoke) => self.check_access_control("REVOKE", &revoke.object, revoke.get_range()),
+      Statement::Transaction(transaction) => self.check_transaction(transaction),
+      Statement::With(with) => self.check_with(with),
       _ => {}
     }
   }
 
   fn check_from(&mut self, from: &FromClause) {
+    if let Some(alias) = &from.alias {
+      self.bind_alias(alias, AliasKind::Table);
+    }
+    self.use_table(&from.table, from.alias.as_ref());
     let table_name = from.table.lexeme.as_ref().unwrap().clone();
-    if self.tables.contains(&table_name) {
-      self.diagnostics.add(
-        TypeError::DuplicateColumn {
-          range: from.get_range(),
-          // hint: Some("check for duplicate table usage".to_string()),
-        }
-        .into(),
-      );
+    if let Some((_, first)) = self.tables.iter().find(|(name, _)| *name == table_name) {
+      self.diagnostics.add(TypeError::DuplicateTable { range: from.get_range(), first: first.clone() }.into());
     } else {
-      self.tables.push(table_name);
+      self.tables.push((table_name, from.get_range()));
+    }
+    if let Some(sample) = &from.sample {
+      if !self.dialect.has_tablesample() {
+        let dialect = self.dialect.name().to_string();
+        self.diagnostics.add(TypeError::ApproximatedSample { dialect, range: sample.get_range() }.into());
+      }
     }
   }
 
   fn check_select(&mut self, select: &SelectStatement) {
+    self.aliases.projection(select.get_range());
+    self.projection = Projection::Columns(select.expressions.len());
+    if let Some(from) = &select.from {
+      if let Some(alias) = &from.alias {
+        self.bind_alias(alias, AliasKind::Table);
+      }
+      self.use_table(&from.table, from.alias.as_ref());
+    }
+    if let Distinct::On(expressions) = &select.distinct {
+      expressions.iter().for_each(|expression| self.reference_expression(expression));
+      if self.dialect != Dialect::Postgres {
+        let dialect = self.dialect.name().to_string();
+        self.diagnostics.add(TypeError::PostgresOnlyDistinctOn { dialect, range: select.get_range() }.into());
+      }
+    }
     if select.expressions.is_empty() {
       self.diagnostics.add(
         TypeError::MissingSelectClause {
@@ -58,27 +254,34 @@ impl Checker {
         .into(),
       );
     }
+    // duplicates are only looked for within this projection, a later SELECT may pick the same columns again
+    let mut columns: Seen = vec![];
     for expr in &select.expressions {
+      self.reference_expression(&expr.expression);
+      if let Some(alias) = &expr.alias {
+        self.bind_alias(alias, AliasKind::Column);
+      }
       if let Expression::Column(col) = &expr.expression {
         let column_name = col.column.lexeme.as_ref().unwrap().clone();
-        if !self.columns.contains(&column_name) {
-          self.columns.push(column_name);
+        if let Some((_, first)) = columns.iter().find(|(name, _)| *name == column_name) {
+          self.diagnostics.add(TypeError::DuplicateColumn { range: col.get_range(), first: first.clone() }.into());
         } else {
-          self.diagnostics.add(
-            TypeError::DuplicateColumn {
-              range: col.get_range(),
-              // hint: Some("column is used multiple times".to_string()),
-            }
-            .into(),
-          );
+          columns.push((column_name, col.get_range()));
         }
       }
     }
   }
 
   fn check_join(&mut self, join: &JoinClause) {
+    if let Some(alias) = &join.alias {
+      self.bind_alias(alias, AliasKind::Table);
+    }
+    self.use_table(&join.table, join.alias.as_ref());
+    self.reference_expression(&join.on.left);
+    self.reference_expression(&join.on.right);
     let table_name = join.table.lexeme.as_ref().unwrap().clone();
-    if !self.tables.contains(&table_name) {
+    self.check_joined_tables(&table_name, join.get_range());
+    if !self.tables.iter().any(|(name, _)| *name == table_name) {
       self.diagnostics.add(
         TypeError::MissingIndexOnJoin {
           range: join.get_range(),
@@ -102,21 +305,70 @@ impl Checker {
     }
   }
 
+  // warns once, on the join that takes the scope past `max_joined_tables` distinct tables
+  fn check_joined_tables(&mut self, table_name: &str, range: Range) {
+    let seen = |tables: &Seen| tables.iter().any(|(name, _)| name == table_name);
+    if seen(&self.tables) || seen(&self.joined) {
+      return;
+    }
+    self.joined.push((table_name.to_string(), range.clone()));
+    let mut names = self.tables.iter().chain(&self.joined).map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    let limit = self.lints.max_joined_tables;
+    if names.len() == limit + 1 {
+      self.diagnostics.add(TypeError::TooManyJoinedTables { count: names.len(), limit, range }.into());
+    }
+  }
+
   fn check_where(&mut self, where_clause: &WhereClause) {
-    // Check for potential performance issues with OR conditions
-    if where_clause.condition.emit().contains(" OR ") {
-      self.diagnostics.add(
-        TypeError::UnsupportedOperator {
-          range: where_clause.get_range(),
-          // hint: Some("use AND instead of OR for better indexing".to_string()),
-        }
-        .into(),
-      );
+    self.reference_expression(&where_clause.condition);
+    self.check_condition_for_tautology(&where_clause.condition);
+    // an OR can keep the database from using an index on either side of it
+    if has_or(&where_clause.condition) {
+      self.diagnostics.add(TypeError::OrInWhere { range: where_clause.get_range() }.into());
+    }
+  }
+
+  // `price = price` or `1 = 1` keeps every row, most likely a typo or a placeholder never filled in
+  fn check_condition_for_tautology(&mut self, condition: &Expression) {
+    let Expression::Condition(cond) = condition else { return };
+    match cond.operator {
+      Operator::And | Operator::Or => {
+        self.check_condition_for_tautology(&cond.left);
+        self.check_condition_for_tautology(&cond.right);
+      }
+      Operator::Equal if is_same_operand(&cond.left, &cond.right) => {
+        let condition_sql = condition.to_sql(Dialect::default(), &EmitOptions::default());
+        let range = condition.get_range();
+        self.diagnostics.add(TypeError::TautologicalCondition { condition: condition_sql, range }.into());
+      }
+      _ => {}
     }
   }
 
   fn check_group_by(&mut self, group_by: &GroupByClause) {
-    if group_by.columns.is_empty() {
+    group_by.expressions().into_iter().for_each(|column| self.reference_expression(column));
+    if let Projection::Grouped(names) = &mut self.projection {
+      let columns = group_by.expressions().into_iter().filter_map(|expression| match expression {
+        Expression::Column(column) => Some(column.name().to_string()),
+        _ => None,
+      });
+      names.extend(columns);
+    }
+    let grouping = match &group_by.modifier {
+      GroupByModifier::Rollup if self.dialect == Dialect::MySql => None,
+      GroupByModifier::Rollup => Some("ROLLUP"),
+      GroupByModifier::Cube => Some("CUBE"),
+      GroupByModifier::GroupingSets(_) => Some("GROUPING SETS"),
+      GroupByModifier::None => None,
+    };
+    if let (Some(grouping), false) = (grouping, self.dialect.has_grouping_sets()) {
+      let dialect = self.dialect.name().to_string();
+      let range = group_by.get_range();
+      self.diagnostics.add(TypeError::UnsupportedGrouping { grouping: grouping.to_string(), dialect, range }.into());
+    }
+    if group_by.columns.is_empty() && group_by.modifier == GroupByModifier::None {
       self.diagnostics.add(
         TypeError::MissingGroupBy {
           range: group_by.get_range(),
@@ -127,7 +379,192 @@ impl Checker {
     }
   }
 
+  // both reshape the rows, so like an aggregate they start a new projection
+  fn check_pivot(&mut self, pivot: &PivotClause) {
+    self.aliases.projection(pivot.get_range());
+    self.projection = Projection::Reshaped;
+    self.reference_expression(&pivot.aggregate.argument);
+    self.reference_token(&pivot.for_col);
+  }
+
+  fn check_unpivot(&mut self, unpivot: &UnpivotClause) {
+    self.aliases.projection(unpivot.get_range());
+    self.projection = Projection::Reshaped;
+    unpivot.in_columns.iter().for_each(|column| self.reference_token(column));
+  }
+
+  fn check_qualify(&mut self, qualify: &QualifyClause) {
+    self.reference_expression(&qualify.condition);
+    if self.dialect.has_qualify() {
+      return;
+    }
+    let dialect = self.dialect.name().to_string();
+    if self.keep_qualify {
+      self.diagnostics.add(TypeError::UnsupportedQualify { dialect, range: qualify.get_range() }.into());
+    } else {
+      let rewrite = "QUALIFY becomes a filtered subquery".to_string();
+      self.diagnostics.add(TypeError::DialectRewrite { rewrite, dialect, range: qualify.get_range() }.into());
+    }
+  }
+
+  fn check_copy(&mut self, copy: &CopyStatement) {
+    self.use_table(&copy.table, None);
+    if self.dialect != Dialect::Postgres {
+      let dialect = self.dialect.name().to_string();
+      self.diagnostics.add(TypeError::PostgresOnlyCopy { dialect, range: copy.get_range() }.into());
+    }
+  }
+
+  // the target is looked up in the schema, but its columns are not in scope for the rows
+  fn check_insert(&mut self, insert: &InsertStatement) {
+    self.check_table_exists(&insert.table);
+    match &insert.source {
+      InsertSource::Values(rows) => rows.iter().flatten().for_each(|value| self.reference_expression(value)),
+      InsertSource::Select(query) => self.check_clause(query),
+    }
+    if insert.columns.is_empty() {
+      return;
+    }
+    let expected = insert.columns.len();
+    for (found, range) in insert.source.row_widths() {
+      if found != expected {
+        self.diagnostics.add(TypeError::InsertColumnCount { expected, found, range }.into());
+      }
+    }
+  }
+
+  // the columns dropped or renamed must already be there, an added one is new
+  fn check_alter_table(&mut self, alter: &AlterTableStatement) {
+    self.use_table(&alter.table, None);
+    match &alter.action {
+      AlterAction::DropColumn(column) | AlterAction::RenameColumn { from: column, .. } => self.reference_token(column),
+      AlterAction::AddColumn(_) | AlterAction::RenameTable(_) => {}
+    }
+  }
+
+  fn check_create_index(&mut self, index: &CreateIndexStatement) {
+    self.use_table(&index.table, None);
+    index.columns.iter().for_each(|column| self.reference_token(column));
+  }
+
+  fn check_drop_index(&mut self, index: &DropIndexStatement) {
+    match &index.table {
+      Some(table) => self.check_table_exists(table),
+      None if self.dialect == Dialect::MySql => {
+        let name = index.name.lexeme.clone().unwrap_or_default();
+        self.diagnostics.add(TypeError::DropIndexWithoutTable { name, range: index.get_range() }.into());
+      }
+      None => {}
+    }
+  }
+
+  // the index is not in the schema, so only the tables and columns are looked up
+  fn check_comment_on(&mut self, comment: &CommentOnStatement) {
+    match (comment.object_type, &comment.table) {
+      (CommentObjectType::Table, _) => self.check_table_exists(&comment.name),
+      (CommentObjectType::Column, Some(table)) => {
+        self.use_table(table, None);
+        self.reference_token(&comment.name);
+      }
+      _ => {}
+    }
+    if self.dialect != Dialect::Postgres {
+      let dialect = self.dialect.name().to_string();
+      self.diagnostics.add(TypeError::PostgresOnlyComment { dialect, range: comment.get_range() }.into());
+    }
+  }
+
+  fn check_pragma(&mut self, pragma: &PragmaStatement) {
+    if let Some(table) = pragma.table() {
+      self.check_table_exists(table);
+    }
+    if self.dialect != Dialect::Sqlite {
+      let dialect = self.dialect.name().to_string();
+      self.diagnostics.add(TypeError::SqliteOnlyPragma { dialect, range: pragma.get_range() }.into());
+    }
+  }
+
+  fn check_access_control(&mut self, statement: &str, object: &Token, range: Range) {
+    self.check_table_exists(object);
+    let table = object.lexeme.clone().unwrap_or_default();
+    self.diagnostics.add(TypeError::AccessControl { statement: statement.to_string(), table, range }.into());
+  }
+
+  // each cte is checked as its own query, it can read the ctes before it and, when recursive, itself
+  fn check_with(&mut self, with: &WithStatement) {
+    for cte in &with.ctes {
+      self.ctes.push((cte.name().to_string(), cte.get_range()));
+      if with.recursive {
+        self.check_recursive_cte(cte);
+      }
+      cte.branches().for_each(|query| self.check_statement(query));
+    }
+    self.check_statement(&with.body);
+  }
+
+  // a cte that reads itself needs a base query that does not, then one UNION ALL query that does
+  fn check_recursive_cte(&mut self, cte: &CommonTableExpression) {
+    let name = cte.name();
+    if !cte.branches().any(|query| query.reads_table(name)) {
+      return;
+    }
+    let step = match cte.unions.as_slice() {
+      [step] if step.all && step.query.reads_table(name) && !cte.query.reads_table(name) => &step.query,
+      _ => {
+        let error = TypeError::MalformedRecursiveCte { name: name.to_string(), range: cte.get_range() };
+        self.diagnostics.add(error.into());
+        return;
+      }
+    };
+    if !step.has_where() {
+      let error = TypeError::UnboundedRecursion { name: name.to_string(), range: step.get_range() };
+      self.diagnostics.add(error.into());
+    }
+  }
+
+  // statements run in file order, so a COMMIT before any BEGIN commits nothing this file started
+  fn check_transaction(&mut self, transaction: &TransactionStatement) {
+    match transaction.kind {
+      TransactionKind::Begin => self.transaction = true,
+      TransactionKind::Commit if !self.transaction => {
+        self.diagnostics.add(TypeError::CommitWithoutBegin { range: transaction.get_range() }.into());
+      }
+      TransactionKind::Commit | TransactionKind::Rollback => self.transaction = false,
+      _ => {}
+    }
+  }
+
+  fn check_truncate(&mut self, truncate: &TruncateStatement) {
+    self.check_table_exists(&truncate.table);
+    let table = truncate.table.lexeme.clone().unwrap_or_default();
+    self.diagnostics.add(TypeError::DestructiveOperation { table, range: truncate.get_range() }.into());
+    if !self.dialect.has_truncate() {
+      let (rewrite, dialect) = ("TRUNCATE becomes DELETE FROM".to_string(), self.dialect.name().to_string());
+      self.diagnostics.add(TypeError::DialectRewrite { rewrite, dialect, range: truncate.get_range() }.into());
+    }
+  }
+
+  fn reference_windows(&mut self, window: &WindowClause) {
+    for definition in &window.definitions {
+      definition.spec.partition_by.iter().for_each(|expr| self.reference_expression(expr));
+      definition.spec.order_by.iter().for_each(|order| self.reference_expression(&order.column));
+    }
+  }
+
   fn check_aggregate(&mut self, agg: &AggregateClause) {
+    self.aliases.projection(agg.get_range());
+    self.projection = Projection::Grouped(agg.alias.iter().filter_map(|alias| alias.lexeme.clone()).collect());
+    self.reference_expression(&agg.argument);
+    if let Some(filter) = &agg.filter {
+      self.reference_expression(filter);
+    }
+    if let Some(within_group) = &agg.within_group {
+      within_group.order.columns.iter().for_each(|key| self.reference_expression(&key.column));
+    }
+    if let Some(alias) = &agg.alias {
+      self.bind_alias(alias, AliasKind::Column);
+    }
+
     // Check if the aggregate is properly associated with a table
     // if !self.tables.iter().any(|t| agg.argument.emit().contains(t)) {
     //   self.diagnostics.add(
@@ -151,9 +588,119 @@ impl Checker {
     }
   }
 
+  // without percentiles the value is looked up by a subquery over every row the query reads, which
+  // cannot give each group its own
+  fn check_percentiles(&mut self, clauses: &[&Statement]) {
+    if self.dialect.has_percentile() {
+      return;
+    }
+    let grouped = clauses.iter().any(|clause| matches!(clause, Statement::GroupBy(_)));
+    for clause in clauses {
+      let agg = match clause {
+        Statement::With(with) => {
+          with.queries().for_each(|query| self.check_percentiles(&query.clauses()));
+          continue;
+        }
+        Statement::Aggregate(agg) if agg.within_group.is_some() => agg,
+        _ => continue,
+      };
+      let (function, dialect, range) =
+        (agg.function.emit().to_string(), self.dialect.name().to_string(), agg.get_range());
+      let error = match agg.function {
+        _ if grouped => TypeError::GroupedPercentile { function, dialect, range },
+        AggregateFn::PercentileCont => TypeError::ApproximatedPercentile { dialect, range },
+        _ => {
+          let rewrite = format!("{} becomes a subquery ranking the values", function);
+          TypeError::DialectRewrite { rewrite, dialect, range }
+        }
+      };
+      self.diagnostics.add(error.into());
+    }
+  }
+
+  fn check_limit(&mut self, limit: &LimitClause) {
+    let Some(offset) = limit.offset.as_ref().filter(|_| limit.comma_offset) else { return };
+    let fixed = format!("LIMIT {} OFFSET {}", limit.count.raw, offset.raw);
+    let diagnostic: Diagnostic = TypeError::CommaOffset { range: limit.get_range() }.into();
+    self.diagnostics.add(diagnostic.with_suggestion(Replacement::new(limit.get_range(), fixed)));
+  }
+
+  fn check_order(&mut self, order: &OrderClause) {
+    order.columns.iter().for_each(|order| self.reference_expression(&order.column));
+    order.columns.iter().for_each(|order| self.check_order_key(&order.column));
+  }
+
+  // a key is a selected position, an alias, or a column of the tables still in scope;
+  // unqualified names are left to the schema unless an AGGREGATE narrowed the columns
+  fn check_order_key(&mut self, key: &Expression) {
+    match key {
+      Expression::Literal(Literal::Number(number)) => {
+        let width = match &self.projection {
+          Projection::Columns(width) => *width,
+          Projection::Grouped(_) => 1,
+          Projection::Source | Projection::Reshaped => return,
+        };
+        if !number.raw.parse::<usize>().is_ok_and(|position| (1..=width).contains(&position)) {
+          let error =
+            TypeError::OrderPositionOutOfRange { position: number.raw.clone(), width, range: number.range.clone() };
+          self.diagnostics.add(error.into());
+        }
+      }
+      Expression::Column(column) => {
+        let in_scope = match (column.qualifier(), &self.projection) {
+          (Some(qualifier), _) => {
+            self.sources.iter().any(|(table, alias)| table == qualifier || alias.as_deref() == Some(qualifier))
+          }
+          (None, Projection::Grouped(names)) => {
+            names.iter().any(|name| name == column.name()) || self.aliases.is_column_alias(column.name())
+          }
+          (None, _) => true,
+        };
+        if !in_scope {
+          let key = key.to_sql(Dialect::default(), &EmitOptions::default());
+          self.diagnostics.add(TypeError::UnknownOrderKey { key, range: column.get_range() }.into());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  // `SELECT name, COUNT(id)` is only valid when the query groups its rows
+  fn check_ungrouped_columns(&mut self, query: &[Statement]) {
+    let clauses = query.iter().flat_map(|stmt| stmt.clauses()).collect::<Vec<_>>();
+    if clauses.iter().any(|clause| matches!(clause, Statement::GroupBy(_))) {
+      return;
+    }
+    for clause in clauses {
+      let Statement::Select(select) = clause else { continue };
+      if !select.expressions.iter().any(|expr| expr.expression.contains_aggregate()) {
+        continue;
+      }
+      let columns = select.expressions.iter().flat_map(|expr| expr.expression.plain_columns()).collect::<Vec<_>>();
+      // one fix groups by every plain column, so only the first diagnostic carries it
+      let mut names: Vec<String> = vec![];
+      let opts = EmitOptions::default();
+      for name in columns.iter().map(|column| column.to_sql(Dialect::default(), &opts)) {
+        if !names.contains(&name) {
+          names.push(name);
+        }
+      }
+      let names = names.join(", ");
+      let end = select.get_range().end;
+      let mut suggestion = Some(Replacement::new(Range { start: end, end }, format!("\n|> GROUP BY {}", names)));
+      for column in columns {
+        let diagnostic: Diagnostic = TypeError::MissingGroupBy { range: column.get_range() }.into();
+        match suggestion.take() {
+          Some(suggestion) => self.diagnostics.add(diagnostic.with_suggestion(suggestion)),
+          None => self.diagnostics.add(diagnostic),
+        }
+      }
+    }
+  }
+
   fn check_pipe(&mut self, pipe: &PipeStatement) {
-    self.check_statement(&pipe.left);
-    self.check_statement(&pipe.right);
+    self.check_clause(&pipe.left);
+    self.check_clause(&pipe.right);
 
     // Check if the aggregate function is properly piped
     if let Statement::Aggregate(_) = &*pipe.right {
@@ -170,16 +717,395 @@ impl Checker {
     }
   }
 
+  fn bind_alias(&mut self, alias: &Token, kind: AliasKind) {
+    let name = alias.lexeme.as_ref().unwrap();
+    let previous = self.aliases.bind(name, kind, alias.range.clone());
+    if let Some(previous) = previous {
+      self.diagnostics.add(TypeError::DuplicateAlias { range: alias.range.clone() }.into());
+      self.diagnostics.attach(Diagnostic::note(&format!("`{}` was first bound here", name), previous.range));
+    }
+  }
+
+  fn check_unused_aliases(&mut self) {
+    for binding in self.aliases.unused() {
+      self.diagnostics.add(TypeError::UnusedAlias { range: binding.range }.into());
+    }
+  }
+
+  fn use_table(&mut self, table: &Token, alias: Option<&Token>) {
+    self.check_table_exists(table);
+    let alias = alias.and_then(|alias| alias.lexeme.clone());
+    self.sources.push((table.lexeme.clone().unwrap(), alias));
+  }
+
+  fn check_table_exists(&mut self, table: &Token) {
+    let name = table.lexeme.as_deref().unwrap_or_default();
+    if self.ctes.iter().chain(&self.included).any(|(known, _)| known == name) {
+      return;
+    }
+    if self.schema.as_ref().is_some_and(|schema| !schema.has_table(name)) {
+      self.diagnostics.add(TypeError::UnknownTable { table: name.to_string(), range: table.range.clone() }.into());
+    }
+  }
+
+  // only reports columns whose tables are all described by the schema
+  fn check_unknown_columns(&mut self) {
+    let Some(schema) = &self.schema else { return };
+    let mut unknown = vec![];
+    for reference in self.aliases.references() {
+      let mut tables = match &reference.qualifier {
+        Some(qualifier) => {
+          self.sources.iter().filter(|(table, alias)| alias.as_ref() == Some(qualifier) || table == qualifier).collect()
+        }
+        None if self.aliases.is_column_alias(&reference.name) => continue,
+        None => self.sources.iter().collect::<Vec<_>>(),
+      }
+      .into_iter()
+      .map(|(table, _)| table.as_str())
+      .collect::<Vec<_>>();
+      tables.sort();
+      tables.dedup();
+      if tables.is_empty() || !tables.iter().all(|table| schema.has_table(table)) {
+        continue;
+      }
+      if !tables.iter().any(|table| schema.has_column(table, &reference.name)) {
+        let (table, column) = (tables.join(", "), reference.name.clone());
+        unknown.push(TypeError::UnknownColumn { table, column, range: reference.range.clone() });
+      }
+    }
+    unknown.into_iter().for_each(|error| self.diagnostics.add(error.into()));
+  }
+
+  // records every column reference and checks every literal, including those inside subqueries
+  fn reference_expression(&mut self, expression: &Expression) {
+    match expression {
+      Expression::Column(column) => self.aliases.reference(column.qualifier(), column.name(), column.get_range()),
+      Expression::Condition(condition) => {
+        self.reference_expression(&condition.left);
+        self.reference_expression(&condition.right);
+      }
+      Expression::FunctionCall(call) => call.arguments.iter().for_each(|argument| self.reference_expression(argument)),
+      Expression::Subquery(subquery) => self.reference_statement(&subquery.stmt),
+      Expression::In(in_) => {
+        self.reference_expression(&in_.expression);
+        in_.values.iter().for_each(|value| self.reference_expression(value));
+        self.check_row_values(in_);
+      }
+      Expression::Tuple(tuple) => tuple.items.iter().for_each(|item| self.reference_expression(item)),
+      Expression::Not(not) => self.reference_expression(&not.expression),
+      Expression::Cast(cast) => {
+        self.reference_expression(&cast.expression);
+        self.casts.push((cast.type_name().to_string(), cast.get_range()));
+      }
+      Expression::Window(window) => {
+        window.function.arguments.iter().for_each(|argument| self.reference_expression(argument));
+        window.spec.partition_by.iter().for_each(|expr| self.reference_expression(expr));
+        window.spec.order_by.iter().for_each(|order| self.reference_expression(&order.column));
+        if let Some(name) = &window.window_name {
+          self.check_window_name(name);
+        }
+      }
+      Expression::Literal(Literal::Interval(interval)) => self.check_interval(interval),
+      Expression::Literal(Literal::String(string)) => self.check_injection(string),
+      Expression::Literal(_) | Expression::CurrentTimestamp(_) => {}
+    }
+  }
+
+  // `(a, b) IN ((1, 2), (3, 4))`, every row as wide as the left side; a subquery is left to the database
+  fn check_row_values(&mut self, in_: &InExpression) {
+    if let [Expression::Subquery(_)] = in_.values.as_slice() {
+      return;
+    }
+    let width = |expression: &Expression| match expression {
+      Expression::Tuple(tuple) => tuple.items.len(),
+      _ => 1,
+    };
+    let expected = width(&in_.expression);
+    for value in &in_.values {
+      let found = width(value);
+      if found != expected {
+        self.diagnostics.add(TypeError::RowValueSize { expected, found, range: value.get_range() }.into());
+      }
+    }
+    if expected > 1 && !self.dialect.has_row_value_list() {
+      let (rewrite, dialect) = ("a row value IN becomes equalities joined by OR".to_string(), self.dialect.name());
+      let rewrite = TypeError::DialectRewrite { rewrite, dialect: dialect.to_string(), range: in_.get_range() };
+      self.diagnostics.add(rewrite.into());
+    }
+  }
+
+  fn check_window_name(&mut self, name: &Token) {
+    let text = name.lexeme.as_deref().unwrap_or_default();
+    if !self.windows.iter().any(|(window, _)| window == text) {
+      let error = TypeError::UnknownWindow { name: text.to_string(), range: name.range.clone() };
+      self.diagnostics.add(error.into());
+    }
+  }
+
+  // a bare column name, e.g. the FOR column of a PIVOT
+  fn reference_token(&mut self, column: &Token) {
+    self.reference_expression(&Expression::create_column(None, column.clone()));
+  }
+
+  // text that would end the string or the statement if pasted into sql unescaped
+  fn check_injection(&mut self, string: &StringLiteral) {
+    let value = string.value.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some((_, pattern)) = INJECTION_PATTERNS.iter().find(|(needle, _)| value.contains(needle)) {
+      let pattern = pattern.to_string();
+      self.diagnostics.add(TypeError::PotentialInjection { pattern, range: string.range.clone() }.into());
+    }
+  }
+
+  fn check_interval(&mut self, interval: &IntervalLiteral) {
+    if !interval.is_numeric() {
+      self.diagnostics.add(TypeError::InvalidInterval { range: interval.range.clone() }.into());
+    }
+  }
+
+  fn reference_statement(&mut self, stmt: &Statement) {
+    match stmt {
+      Statement::Select(select) => {
+        if let Some(from) = &select.from {
+          self.use_table(&from.table, from.alias.as_ref());
+        }
+        select.expressions.iter().for_each(|expr| self.reference_expression(&expr.expression))
+      }
+      Statement::Join(join) => {
+        self.use_table(&join.table, join.alias.as_ref());
+        self.reference_expression(&join.on.left);
+        self.reference_expression(&join.on.right);
+      }
+      Statement::Where(where_clause) => self.reference_expression(&where_clause.condition),
+      Statement::Qualify(qualify) => self.reference_expression(&qualify.condition),
+      Statement::GroupBy(group_by) => {
+        group_by.expressions().into_iter().for_each(|column| self.reference_expression(column))
+      }
+      Statement::Order(order) => order.columns.iter().for_each(|order| self.reference_expression(&order.column)),
+      Statement::Aggregate(agg) => {
+        self.reference_expression(&agg.argument);
+        if let Some(filter) = &agg.filter {
+          self.reference_expression(filter);
+        }
+        if let Some(within_group) = &agg.within_group {
+          within_group.order.columns.iter().for_each(|key| self.reference_expression(&key.column));
+        }
+      }
+      Statement::Pivot(pivot) => {
+        self.reference_expression(&pivot.aggregate.argument);
+        self.reference_token(&pivot.for_col);
+      }
+      Statement::Unpivot(unpivot) => unpivot.in_columns.iter().for_each(|column| self.reference_token(column)),
+      Statement::Window(window) => self.reference_windows(window),
+      Statement::Expression(expression) => self.reference_expression(expression),
+      Statement::Pipe(pipe) => {
+        self.reference_statement(&pipe.left);
+        self.reference_statement(&pipe.right);
+      }
+      Statement::From(from) => self.use_table(&from.table, from.alias.as_ref()),
+      Statement::Copy(copy) => self.use_table(&copy.table, None),
+      Statement::Insert(insert) => {
+        self.check_table_exists(&insert.table);
+        match &insert.source {
+          InsertSource::Values(rows) => rows.iter().flatten().for_each(|value| self.reference_expression(value)),
+          InsertSource::Select(query) => self.reference_statement(query),
+        }
+      }
+      Statement::Truncate(truncate) => self.check_table_exists(&truncate.table),
+      Statement::AlterTable(alter) => self.check_alter_table(alter),
+      Statement::CreateIndex(index) => self.check_create_index(index),
+      Statement::DropIndex(index) => self.check_drop_index(index),
+      Statement::CommentOn(comment) => self.check_comment_on(comment),
+      Statement::Pragma(pragma) => self.check_pragma(pragma),
+      Statement::Grant(grant) => self.check_access_control("GRANT", &grant.object, grant.get_range()),
+      Statement::Revoke(revoke) => self.check_access_control("REVOKE", &revoke.object, revoke.get_range()),
+      Statement::With(with) => {
+        with.ctes.iter().for_each(|cte| self.ctes.push((cte.name().to_string(), cte.get_range())));
+        with.queries().for_each(|query| self.reference_statement(query));
+      }
+      Statement::Limit(_) | Statement::Transaction(_) => {}
+    }
+  }
+
   fn check_condition_validity(&self, cond: &ConditionExpression) -> bool {
     // Add more complex condition checks if necessary
-    !cond.left.emit().is_empty() && !cond.right.emit().is_empty()
+    !cond.left.to_sql(Dialect::default(), &EmitOptions::default()).is_empty()
+      && !cond.right.to_sql(Dialect::default(), &EmitOptions::default()).is_empty()
   }
 
-  pub fn report(&self, source: &Source) {
-    self.diagnostics.report(source);
+  pub fn report(&self, source: &Source, color: ColorMode) {
+    self.diagnostics.report(source, color, &ReportFilter::default());
   }
 
   pub fn contains_error(&self) -> bool {
-    self.diagnostics.contains_error()
+    self.diagnostics.contains_error() || (self.strict && !self.diagnostics.diagnostics.is_empty())
+  }
+
+  pub fn into_diagnostics(mut self) -> DiagnosticsManager {
+    self.diagnostics.strict = self.strict;
+    self.diagnostics
+  }
+}
+
+// an OR anywhere in the condition, its subqueries are checked on their own
+fn has_or(condition: &Expression) -> bool {
+  match condition {
+    Expression::Condition(cond) => cond.operator == Operator::Or || has_or(&cond.left) || has_or(&cond.right),
+    Expression::Not(not) => has_or(&not.expression),
+    _ => false,
+  }
+}
+
+// the same column of the same table, or two literals written alike
+fn is_same_operand(left: &Expression, right: &Expression) -> bool {
+  match (left, right) {
+    (Expression::Column(left), Expression::Column(right)) => {
+      left.qualifier() == right.qualifier() && left.name() == right.name()
+    }
+    (Expression::Literal(_), Expression::Literal(_)) => {
+      let opts = EmitOptions::default();
+      left.to_sql(Dialect::default(), &opts) == right.to_sql(Dialect::default(), &opts)
+    }
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::compiler::compile;
+  use crate::config::Config;
+  use crate::diagnostics::maneger::Diagnostic;
+  use crate::emiter::Dialect;
+  use crate::utils::source::Source;
+
+  fn check(raw: &str, config: &Config) -> Vec<Diagnostic> {
+    let source = Source::new("test.sql", raw);
+    compile(&source, None, &[], config).1.get_diagnostics()
+  }
+
+  fn codes_with(raw: &str, config: &Config) -> Vec<&'static str> {
+    check(raw, config).into_iter().map(|diagnostic| diagnostic.code).collect()
+  }
+
+  fn codes(raw: &str, dialect: Dialect) -> Vec<&'static str> {
+    codes_with(raw, &Config { dialect, ..Config::default() })
+  }
+
+  #[test]
+  fn groupings_need_the_syntax_of_the_dialect() {
+    let rollup = "FROM sales |> AGGREGATE SUM(amount) AS total GROUP BY ROLLUP(region, product);";
+    let cube = "FROM sales |> AGGREGATE SUM(amount) AS total GROUP BY CUBE(region, product);";
+    let sets = "FROM sales |> AGGREGATE SUM(amount) AS total GROUP BY GROUPING SETS ((region), ());";
+    for raw in [rollup, cube, sets] {
+      assert!(codes(raw, Dialect::Postgres).is_empty(), "{}", raw);
+      assert_eq!(codes(raw, Dialect::Sqlite), ["E0221"], "{}", raw);
+    }
+    assert!(codes(rollup, Dialect::MySql).is_empty());
+    assert_eq!(codes(cube, Dialect::MySql), ["E0221"]);
+    assert_eq!(codes(sets, Dialect::MySql), ["E0221"]);
+  }
+
+  #[test]
+  fn chained_comparisons_are_split_with_and() {
+    let raw = "FROM orders |> WHERE 10 < total_amount <= 100 |> SELECT id;";
+    let diagnostics = check(raw, &Config::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "E0105");
+    let suggestion = diagnostics[0].suggestion.as_ref().unwrap();
+    assert_eq!(&raw[suggestion.range.start..suggestion.range.end], "10 < total_amount <= 100");
+    assert_eq!(suggestion.new_text, "10 < total_amount AND total_amount <= 100");
+    let fixed = raw.replace("10 < total_amount <= 100", &suggestion.new_text);
+    assert!(codes(&fixed, Dialect::Ansi).is_empty(), "{}", fixed);
+  }
+
+  #[test]
+  fn columns_are_duplicate_only_within_one_select() {
+    let separate = [
+      "FROM users |> SELECT id, name;\nFROM orders |> SELECT id, total_amount;",
+      "FROM orders |> SELECT id, total_amount |> SELECT id;",
+      "FROM users |> WHERE id IN (FROM orders |> SELECT id) |> SELECT id;",
+      "FROM users |> SELECT id, (FROM orders |> SELECT id |> LIMIT 1) AS last_order;",
+    ];
+    for raw in separate {
+      assert!(codes(raw, Dialect::Ansi).is_empty(), "{}", raw);
+    }
+    let raw = "FROM users |> SELECT id, name, id;";
+    let diagnostics = check(raw, &Config::default());
+    assert_eq!(diagnostics.iter().map(|diagnostic| diagnostic.code).collect::<Vec<_>>(), ["W0203"]);
+    assert_eq!(diagnostics[0].range.start, raw.rfind("id").unwrap());
+    assert_eq!(diagnostics[0].children[0].range.start, raw.find("id").unwrap());
+  }
+
+  #[test]
+  fn rules_are_turned_off_by_name() {
+    let raw = "FROM orders AS o |> SELECT id, id;";
+    assert_eq!(codes_with(raw, &Config::default()), ["W0203", "W0204"]);
+    // the `[lints]` of spipe.toml
+    let config = Config::from_toml("[lints]\nunused-alias = false\n").unwrap();
+    assert_eq!(codes_with(raw, &config), ["W0203"]);
+    let config = Config::from_toml("[lints]\nunused-alias = true\n").unwrap();
+    assert_eq!(codes_with(raw, &config), ["W0203", "W0204"]);
+    // `--allow`, which wins over the config
+    let config = Config::from_toml("[lints]\nduplicate-column = true\n").unwrap();
+    let config = config.with_allowed(&["duplicate-column".to_string()]);
+    assert_eq!(codes_with(raw, &config), ["W0204"]);
+  }
+
+  #[test]
+  fn allow_comments_silence_the_next_statement() {
+    let raw = "-- spipe:allow(unused-alias)\nFROM orders AS o |> SELECT id, id;\n\nFROM orders AS o |> SELECT id;";
+    assert_eq!(codes(raw, Dialect::Ansi), ["W0203", "W0204"]);
+    let raw = "FROM orders AS o -- spipe:allow(duplicate-column)\n|> SELECT id, id;";
+    assert_eq!(codes(raw, Dialect::Ansi), ["W0204"]);
+    let raw = "-- spipe:allow-file(unused-alias)\nFROM orders AS o |> SELECT id;\nFROM users AS u |> SELECT id;";
+    assert!(codes(raw, Dialect::Ansi).is_empty());
+  }
+
+  // a query reading `count` distinct tables, the first one and a join to each other
+  fn joining(count: usize) -> String {
+    let joins = (1..count).map(|table| format!(" |> JOIN t{} ON t0.id = t{}.id", table, table));
+    format!("FROM t0{} |> SELECT t0.id;", joins.collect::<String>())
+  }
+
+  fn wide_joins(raw: &str, config: &Config) -> Vec<Diagnostic> {
+    check(raw, config).into_iter().filter(|diagnostic| diagnostic.code == "W0217").collect()
+  }
+
+  #[test]
+  fn warns_past_ten_joined_tables() {
+    assert!(wide_joins(&joining(10), &Config::default()).is_empty());
+    let raw = joining(11);
+    let warnings = wide_joins(&raw, &Config::default());
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(&raw[warnings[0].range.start..warnings[0].range.end], "t10 ON t0.id = t10.id");
+    assert_eq!(wide_joins(&joining(14), &Config::default()).len(), 1);
+    // a table joined again is not counted twice
+    let again = joining(10).replace(" |> SELECT", " |> JOIN t1 ON t0.id = t1.id |> SELECT");
+    assert!(wide_joins(&again, &Config::default()).is_empty());
+    let config = Config { max_joined_tables: Some(12), ..Config::default() };
+    assert!(wide_joins(&raw, &config).is_empty());
+    assert_eq!(wide_joins(&joining(13), &config).len(), 1);
+  }
+
+  fn tautologies(raw: &str) -> Vec<String> {
+    let diagnostics = check(raw, &Config::default()).into_iter();
+    diagnostics.filter(|diagnostic| diagnostic.code == "W0221").map(|diagnostic| diagnostic.message).collect()
+  }
+
+  #[test]
+  fn warns_when_a_value_is_compared_with_itself() {
+    let raw = "FROM products |> WHERE price = price |> SELECT id;";
+    assert_eq!(tautologies(raw), ["`price = price` compares a value with itself"]);
+    let raw = "FROM orders AS o |> WHERE 1 = 1 AND 'a' = 'a' |> SELECT id;";
+    assert_eq!(tautologies(raw).len(), 2);
+    let raw = "FROM orders AS o |> WHERE o.total > 10 OR o.status = o.status |> SELECT id;";
+    assert_eq!(tautologies(raw), ["`o.status = o.status` compares a value with itself"]);
+  }
+
+  #[test]
+  fn different_values_are_not_tautologies() {
+    let raw = "FROM products AS o |> WHERE price = o.price AND price = list_price |> SELECT id;";
+    assert!(tautologies(raw).is_empty());
+    let raw = "FROM orders |> WHERE 1 = 2 AND 'a' = 'b' AND total > total |> SELECT id;";
+    assert!(tautologies(raw).is_empty());
   }
 }
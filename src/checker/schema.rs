@@ -0,0 +1,24 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+// Tables and their columns, loaded from `--schema`:
+// { "tables": { "users": ["id", "name"] } }
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schema {
+  pub tables: HashMap<String, Vec<String>>,
+}
+
+impl Schema {
+  pub fn from_json(raw: &str) -> serde_json::Result<Self> {
+    serde_json::from_str(raw)
+  }
+
+  pub fn has_table(&self, table: &str) -> bool {
+    self.tables.contains_key(table)
+  }
+
+  pub fn has_column(&self, table: &str, column: &str) -> bool {
+    self.tables.get(table).is_some_and(|columns| columns.iter().any(|name| name == column))
+  }
+}
@@ -0,0 +1,65 @@
+use crate::diagnostics::codes::rule_code;
+use crate::diagnostics::maneger::{Diagnostic, TypeError};
+use crate::parser::ast::{Comment, Program};
+use crate::utils::range::Range;
+
+const ALLOW: &str = "spipe:allow(";
+const ALLOW_FILE: &str = "spipe:allow-file(";
+
+// the codes a comment silences, in one statement or, without a scope, the whole file
+pub struct Suppression {
+  codes: Vec<&'static str>,
+  scope: Option<Range>,
+}
+
+impl Suppression {
+  pub fn covers(&self, diagnostic: &Diagnostic) -> bool {
+    let in_scope = self
+      .scope
+      .as_ref()
+      .is_none_or(|scope| scope.start <= diagnostic.range.start && diagnostic.range.end <= scope.end);
+    in_scope && self.codes.contains(&diagnostic.code)
+  }
+}
+
+// `-- spipe:allow(rule, ...)` on the line before a statement or at the end of one of its lines,
+// `-- spipe:allow-file(rule, ...)` anywhere; unknown rule names come back as warnings
+pub fn collect(program: &Program) -> (Vec<Suppression>, Vec<Diagnostic>) {
+  let mut suppressions = vec![];
+  let mut unknown = vec![];
+  for comment in &program.comments {
+    let text = comment.text.trim();
+    let (rules, file_wide) = if let Some(rules) = text.strip_prefix(ALLOW_FILE) {
+      (rules, true)
+    } else if let Some(rules) = text.strip_prefix(ALLOW) {
+      (rules, false)
+    } else {
+      continue;
+    };
+    let Some(rules) = rules.strip_suffix(')') else {
+      continue;
+    };
+    let mut codes = vec![];
+    for name in rules.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+      match rule_code(name) {
+        Some(code) => codes.push(code),
+        None => unknown.push(TypeError::UnknownRule { name: name.to_string(), range: comment.range.clone() }.into()),
+      }
+    }
+    let scope = if file_wide { None } else { statement_scope(program, comment) };
+    // a statement comment with nothing after it has nothing to silence
+    if file_wide || scope.is_some() {
+      suppressions.push(Suppression { codes, scope });
+    }
+  }
+  (suppressions, unknown)
+}
+
+fn statement_scope(program: &Program, comment: &Comment) -> Option<Range> {
+  let ranges = program.statements.iter().map(|stmt| stmt.get_range());
+  if comment.is_trailing() {
+    ranges.take_while(|range| range.start <= comment.range.start).last()
+  } else {
+    ranges.into_iter().find(|range| range.start >= comment.range.end)
+  }
+}
@@ -1,28 +1,814 @@
-use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, Command};
+
+use crate::diagnostics::codes::RULES;
+use crate::diagnostics::maneger::{ReportFilter, ReportFormat, Severity, DEFAULT_ERROR_LIMIT};
+use crate::emiter::{Dialect, TargetVersion};
+use crate::format::FormatStyle;
+use crate::utils::files::SOURCE_EXTENSIONS;
+use crate::utils::ColorMode;
 
 pub fn command_line() -> clap::ArgMatches {
-  let matches = Command::new("Hoshi")
+  Command::new("Hoshi")
     .about(env!("CARGO_PKG_DESCRIPTION"))
     .version(env!("CARGO_PKG_VERSION"))
     .author(env!("CARGO_PKG_AUTHORS"))
     .subcommand_required(true)
     .arg_required_else_help(true)
+    .arg(
+      Arg::new("color")
+        .long("color")
+        .value_parser(["auto", "always", "never"])
+        .default_value("auto")
+        .global(true)
+        .help("when to color diagnostics and token kinds, `auto` also honors NO_COLOR."),
+    )
     .subcommand(
       Command::new("compile")
         .about("compile hoshi sintax to native sql.")
-        .arg(Arg::new("file").help("the hoshi file to compile.").required(true)),
+        .arg(
+          Arg::new("file")
+            .help("the hoshi files to compile, directories for every source file under them, `-` for stdin.")
+            .num_args(1..)
+            .required(true),
+        )
+        .arg(
+          Arg::new("keep-comments")
+            .long("keep-comments")
+            .help("keep `--` comments in the native sql.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("output")
+            .short('o')
+            .long("output")
+            .value_name("FILE")
+            .help("write the native sql to a file, creating its directory; diagnostics stay on stderr."),
+        )
+        .arg(
+          Arg::new("force")
+            .long("force")
+            .requires("output")
+            .help("overwrite the `--output` file when it already exists.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("output-dir")
+            .long("output-dir")
+            .value_name("DIR")
+            .conflicts_with("output")
+            .help("write each file's sql under DIR, at the path the file has under the directory it was found in."),
+        )
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .help("compile and check without writing, showing the sql `--output` would get.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("target-version")
+            .long("target-version")
+            .value_name("VERSION")
+            .value_parser(|version: &str| version.parse::<TargetVersion>())
+            .help("the database version to emit for, e.g. `3.44`; newer syntax is rewritten for older ones."),
+        )
+        .arg(
+          Arg::new("emit")
+            .long("emit")
+            .value_parser(["sql", "ast"])
+            .default_value("sql")
+            .help("what to print, the native sql or the parsed program as JSON."),
+        )
+        .arg(
+          Arg::new("pretty").long("pretty").help("indent the JSON printed by `--emit ast`.").action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("count-only")
+            .long("count-only")
+            .help("print how many statements, joins, subqueries, aggregates and tables the file has instead of sql.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("explain-pipe")
+            .long("explain-pipe")
+            .conflicts_with("count-only")
+            .help("print the sql each pipe chain adds up to after every stage instead of the final sql.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(format_arg())
+        .arg(schema_arg())
+        .arg(include_arg())
+        .arg(dialect_arg())
+        .arg(config_arg())
+        .arg(error_limit_arg())
+        .arg(diagnostics_out_arg())
+        .arg(deny_warnings_arg())
+        .arg(strict_arg())
+        .arg(warnings_arg())
+        .arg(report_suppressed_arg())
+        .arg(allow_arg())
+        .arg(no_injection_checks_arg())
+        .arg(stdin_filename_arg())
+        .arg(extension_arg())
+        .arg(min_severity_arg())
+        .arg(quiet_arg()),
     )
     .subcommand(
       Command::new("check")
-        .about("check the syntax of the hoshi sql.")
-        .arg(Arg::new("file").help("the hoshi sql file to check.").required(true)),
+        .about(
+          "check the hoshi sql without compiling it, exiting 1 when a file fails and 2 when one cannot be checked.",
+        )
+        .arg(
+          Arg::new("file")
+            .help("the hoshi files to check, directories for every source file under them, `-` for stdin.")
+            .num_args(1..)
+            .required(true),
+        )
+        .arg(
+          Arg::new("fix")
+            .long("fix")
+            .help("apply the suggested fixes, keeping the original in `<file>.bak`.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .requires("fix")
+            .help("print the fixed source instead of writing it.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(format_arg())
+        .arg(schema_arg())
+        .arg(include_arg())
+        .arg(dialect_arg())
+        .arg(config_arg())
+        .arg(error_limit_arg())
+        .arg(diagnostics_out_arg())
+        .arg(deny_warnings_arg())
+        .arg(strict_arg())
+        .arg(warnings_arg())
+        .arg(report_suppressed_arg())
+        .arg(allow_arg())
+        .arg(no_injection_checks_arg())
+        .arg(stdin_filename_arg())
+        .arg(extension_arg())
+        .arg(min_severity_arg())
+        .arg(quiet_arg()),
     )
     .subcommand(
       Command::new("run")
-        .about("run the compiled hoshi sql.")
-        .arg(Arg::new("file").help("the compiled hoshi sql file.").required(true)),
+        .about("compile the hoshi sql and run it on a database, printing the rows of each query.")
+        .arg(Arg::new("file").help("the hoshi sql file to run, `-` for stdin.").required(true))
+        .arg(
+          Arg::new("db")
+            .long("db")
+            .value_name("FILE")
+            .default_value(":memory:")
+            .help("the sqlite database to run against, `:memory:` for one that is gone after the run."),
+        )
+        .arg(
+          Arg::new("url")
+            .long("url")
+            .value_name("URL")
+            .conflicts_with("db")
+            .value_parser(|url: &str| match Dialect::from_url(url) {
+              Some(_) => Ok(url.to_string()),
+              None => Err(format!("expected a `postgres://` or `mysql://` url but got `{}`", url)),
+            })
+            .help("the postgres or mysql server to run against, the sql is emitted for its dialect."),
+        )
+        .arg(
+          Arg::new("table")
+            .long("table")
+            .value_name("NAME=FILE")
+            .conflicts_with("url")
+            .action(ArgAction::Append)
+            .value_parser(|table: &str| match table.split_once('=') {
+              Some((name, path)) if is_identifier(name) && !path.is_empty() => Ok((name.to_string(), path.to_string())),
+              _ => Err(format!("expected `name=file.csv` but got `{}`", table)),
+            })
+            .help("load a csv file into the sqlite database as a table, its columns typed from the first rows."),
+        )
+        .arg(
+          Arg::new("no-header")
+            .long("no-header")
+            .requires("table")
+            .help("the `--table` files start with data, their columns are named column1, column2...")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("transaction")
+            .long("transaction")
+            .help("run every statement in one transaction, rolled back when one fails.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("timeout")
+            .long("timeout")
+            .value_name("SECONDS")
+            .value_parser(clap::value_parser!(u64))
+            .help("how long to wait for the server to connect, or for a locked sqlite file to free up."),
+        )
+        .arg(
+          Arg::new("output")
+            .long("output")
+            .value_name("FORMAT")
+            .value_parser(["table", "csv", "json"])
+            .default_value("table")
+            .help("how to print the rows: an aligned table, csv, or a JSON array of objects per query."),
+        )
+        .arg(
+          Arg::new("out").short('o').long("out").value_name("FILE").help("write the rows to a file instead of stdout."),
+        )
+        .arg(
+          Arg::new("max-col-width")
+            .long("max-col-width")
+            .value_name("N")
+            .value_parser(clap::value_parser!(usize))
+            .help("cut the table's cells down to N characters."),
+        )
+        .arg(
+          Arg::new("limit-display")
+            .long("limit-display")
+            .value_name("N")
+            .value_parser(clap::value_parser!(usize))
+            .help("print at most N rows of each query, still noting how many it returned."),
+        )
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .help("print the sql that would run instead of running it.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(schema_arg())
+        .arg(config_arg())
+        .arg(stdin_filename_arg()),
+    )
+    .subcommand(
+      Command::new("fmt")
+        .about("format hoshi files in place, one pipe stage per line, exiting 1 with `--check` when one is not.")
+        .arg(
+          Arg::new("file")
+            .help("the hoshi files to format, directories for every source file under them, `-` for stdin.")
+            .num_args(1..)
+            .required(true),
+        )
+        .arg(
+          Arg::new("check")
+            .long("check")
+            .help("write nothing, list the files that are not formatted and exit 1 if there are any.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("stdout")
+            .long("stdout")
+            .conflicts_with("check")
+            .help("print the formatted source instead of writing it.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("align-aliases")
+            .long("align-aliases")
+            .help("put each column of a select with several aliases on its own line, their `AS` lined up.")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("max-width")
+            .long("max-width")
+            .value_name("COLUMNS")
+            .value_parser(clap::value_parser!(usize))
+            .help("wrap select lists and WHERE conditions longer than this, no line is wrapped without it.")
+        )
+        .arg(stdin_filename_arg())
+        .arg(extension_arg()),
+    )
+    .subcommand(
+      Command::new("ast")
+        .about("print the parsed program, even one the checker rejects, as a tree, as JSON or as canonical hoshi.")
+        .arg(Arg::new("file").help("the hoshi file to parse, `-` for stdin.").required(true))
+        .arg(
+          Arg::new("format")
+            .long("format")
+            .value_parser(["tree", "json", "spipe"])
+            .default_value("tree")
+            .help("an indented tree of node kinds, tokens and ranges, the nodes as pretty JSON, or the program written back as pipes."),
+        )
+        .arg(
+          Arg::new("statement")
+            .long("statement")
+            .value_name("N")
+            .value_parser(clap::builder::RangedU64ValueParser::<usize>::new().range(1..))
+            .help("print only the Nth top-level statement, counting from 1."),
+        )
+        .arg(stdin_filename_arg()),
     )
-    .get_matches();
+    .subcommand(
+      Command::new("tokens")
+        .about("print the tokens the lexer reads, comments, errors and the final EOF included, one per line.")
+        .arg(Arg::new("file").help("the hoshi file to read, `-` for stdin.").required(true))
+        .arg(
+          Arg::new("format")
+            .long("format")
+            .value_parser(["text", "json"])
+            .default_value("text")
+            .help("each token's byte range, kind and text, or the tokens as a JSON array."),
+        )
+        .arg(stdin_filename_arg()),
+    )
+    .subcommand(
+      Command::new("explain")
+        .about("explain a diagnostic code, with an example and its fix.")
+        .arg(Arg::new("code").help("the code to explain, e.g. `E0102`.").required(true)),
+    )
+    .subcommand(Command::new("init").about("create a queries/ directory with a sample hoshi query."))
+    .subcommand(Command::new("repl").about("compile hoshi queries typed on stdin, each one ended by a blank line."))
+    .get_matches()
+}
+
+fn format_arg() -> Arg {
+  Arg::new("format")
+    .long("format")
+    .value_parser(["human", "json", "sarif", "github", "lsp"])
+    .default_value("human")
+    .help("how diagnostics are reported.")
+}
+
+fn schema_arg() -> Arg {
+  Arg::new("schema").long("schema").value_name("FILE").help("a json file with the tables and columns to check against.")
+}
+
+fn include_arg() -> Arg {
+  Arg::new("include")
+    .short('I')
+    .long("include")
+    .value_name("FILE")
+    .action(ArgAction::Append)
+    .help("a hoshi file parsed first whose ctes and tables the checked files may read, it is not emitted.")
+}
+
+fn error_limit_arg() -> Arg {
+  Arg::new("error-limit")
+    .long("error-limit")
+    .value_name("N")
+    .value_parser(clap::value_parser!(usize))
+    .help("show at most N errors (20 by default), 0 shows them all.")
+}
+
+fn report_suppressed_arg() -> Arg {
+  Arg::new("report-suppressed")
+    .long("report-suppressed")
+    .help("say how many diagnostics `spipe:allow` comments silenced.")
+    .action(ArgAction::SetTrue)
+}
+
+fn extension_arg() -> Arg {
+  Arg::new("extension")
+    .long("extension")
+    .value_name("EXT")
+    .action(ArgAction::Append)
+    .help("what files a directory is searched for, `spipe` and `sp` without it.")
+}
+
+fn stdin_filename_arg() -> Arg {
+  Arg::new("stdin-filename")
+    .long("stdin-filename")
+    .value_name("PATH")
+    .help("the path diagnostics give the program read from `-`, `<stdin>` without it.")
+}
+
+fn dialect_arg() -> Arg {
+  Arg::new("dialect")
+    .long("dialect")
+    .value_parser(["ansi", "postgres", "postgresql", "mysql", "sqlite"])
+    .help("the sql dialect to emit, it wins over the `dialect` of spipe.toml.")
+}
+
+fn config_arg() -> Arg {
+  Arg::new("config")
+    .long("config")
+    .value_name("FILE")
+    .help("the config file to use instead of the spipe.toml or .spipe.toml found from the first file up.")
+}
+
+fn allow_arg() -> Arg {
+  Arg::new("allow")
+    .long("allow")
+    .value_name("RULE")
+    .value_parser(RULES.iter().map(|(rule, _)| *rule).collect::<Vec<_>>())
+    .action(ArgAction::Append)
+    .help("turn a lint off, e.g. `--allow unused-alias`, it wins over the `[lints]` of spipe.toml.")
+}
+
+fn no_injection_checks_arg() -> Arg {
+  Arg::new("no-injection-checks")
+    .long("no-injection-checks")
+    .help("do not look for sql injection patterns in string literals, the same as `--allow potential-injection`.")
+    .action(ArgAction::SetTrue)
+}
+
+fn deny_warnings_arg() -> Arg {
+  Arg::new("deny-warnings").long("deny-warnings").help("fail on warnings as well as errors.").action(ArgAction::SetTrue)
+}
+
+fn strict_arg() -> Arg {
+  Arg::new("strict")
+    .long("strict")
+    .help("fail on any diagnostic, infos as well as warnings and errors, for zero-warning pipelines.")
+    .action(ArgAction::SetTrue)
+}
+
+fn warnings_arg() -> Arg {
+  Arg::new("warnings").short('W').value_name("LEVEL").value_parser(["error"]).help("`-W error` is `--strict`.")
+}
+
+fn min_severity_arg() -> Arg {
+  Arg::new("min-severity")
+    .long("min-severity")
+    .value_parser(["info", "warning", "error"])
+    .default_value("info")
+    .help("report only diagnostics this severe, the summary counts the rest as hidden.")
+}
+
+fn quiet_arg() -> Arg {
+  Arg::new("quiet")
+    .short('q')
+    .long("quiet")
+    .help("report no diagnostics, only the exit code tells whether the file failed.")
+    .action(ArgAction::SetTrue)
+}
+
+fn diagnostics_out_arg() -> Arg {
+  Arg::new("diagnostics-out")
+    .long("diagnostics-out")
+    .value_name("FILE")
+    .help("write the diagnostics to a file as JSON, or as SARIF or LSP with that `--format`, leaving only a summary on stderr.")
+}
+
+// a name hoshi sql can refer to a table by
+fn is_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars.next().is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+    && chars.all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+fn extensions(matches: &clap::ArgMatches) -> Vec<String> {
+  match matches.get_many::<String>("extension") {
+    Some(extensions) => extensions.map(|extension| extension.trim_start_matches('.').to_string()).collect(),
+    None => SOURCE_EXTENSIONS.iter().map(|extension| extension.to_string()).collect(),
+  }
+}
+
+fn dialect(matches: &clap::ArgMatches) -> Option<Dialect> {
+  matches.get_one::<String>("dialect").and_then(|name| Dialect::from_name(name))
+}
+
+fn strict(matches: &clap::ArgMatches) -> bool {
+  matches.get_flag("strict") || matches.get_one::<String>("warnings").is_some()
+}
+
+fn allowed_rules(matches: &clap::ArgMatches) -> Vec<String> {
+  let mut rules: Vec<String> =
+    matches.get_many::<String>("allow").map(|rules| rules.cloned().collect()).unwrap_or_default();
+  if matches.get_flag("no-injection-checks") {
+    rules.push("potential-injection".to_string());
+  }
+  rules
+}
+
+pub fn report_format(matches: &clap::ArgMatches) -> ReportFormat {
+  match matches.get_one::<String>("format").map(String::as_str) {
+    Some("json") => ReportFormat::Json,
+    Some("sarif") => ReportFormat::Sarif,
+    Some("github") => ReportFormat::Github,
+    Some("lsp") => ReportFormat::Lsp,
+    _ => ReportFormat::Human,
+  }
+}
+
+pub fn report_filter(matches: &clap::ArgMatches) -> ReportFilter {
+  let min_severity = match matches.get_one::<String>("min-severity").map(String::as_str) {
+    Some("error") => Severity::Error,
+    Some("warning") => Severity::Warning,
+    _ => Severity::Info,
+  };
+  ReportFilter { min_severity, quiet: matches.get_flag("quiet") }
+}
+
+pub fn color_mode(matches: &clap::ArgMatches) -> ColorMode {
+  match matches.get_one::<String>("color").map(String::as_str) {
+    Some("always") => ColorMode::Always,
+    Some("never") => ColorMode::Never,
+    _ => ColorMode::Auto,
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FixMode {
+  Off,
+  Write,  // rewrite the file, keeping a `.bak` copy
+  DryRun, // print the fixed source
+}
+
+pub fn fix_mode(matches: &clap::ArgMatches) -> FixMode {
+  match (matches.get_flag("fix"), matches.get_flag("dry-run")) {
+    (true, true) => FixMode::DryRun,
+    (true, false) => FixMode::Write,
+    _ => FixMode::Off,
+  }
+}
+
+pub struct CompileOptions {
+  pub keep_comments: bool,
+  pub output: Option<String>,
+  pub force: bool, // `--output` may replace an existing file
+  pub dry_run: bool,
+  pub format: ReportFormat,
+  pub schema: Option<String>,
+  pub includes: Vec<String>,
+  pub color: ColorMode,
+  pub target_version: Option<TargetVersion>,
+  pub error_limit: usize,
+  pub count_only: bool,
+  pub explain_pipe: bool, // each stage's cumulative sql instead of the final sql
+  pub emit: EmitKind,
+  pub pretty: bool,
+  pub diagnostics_out: Option<String>,
+  pub deny_warnings: bool,
+  pub strict: bool, // `--strict` or `-W error`
+  pub report_suppressed: bool,
+  pub filter: ReportFilter,
+  pub allow: Vec<String>,
+  pub stdin_filename: Option<String>,
+  pub extensions: Vec<String>,
+  pub output_dir: Option<PathBuf>,
+  pub dialect: Option<Dialect>,
+  pub config: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EmitKind {
+  Sql, // the native sql for the dialect
+  Ast, // the parsed program, before any dialect rewrite
+}
+
+impl CompileOptions {
+  pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+    let keep_comments = matches.get_flag("keep-comments");
+    let output = matches.get_one::<String>("output").cloned();
+    let force = matches.get_flag("force");
+    let dry_run = matches.get_flag("dry-run");
+    let format = report_format(matches);
+    let schema = matches.get_one::<String>("schema").cloned();
+    let includes = matches.get_many::<String>("include").unwrap_or_default().cloned().collect();
+    let color = color_mode(matches);
+    let target_version = matches.get_one::<TargetVersion>("target-version").copied();
+    let error_limit = error_limit(matches);
+    let count_only = matches.get_flag("count-only");
+    let explain_pipe = matches.get_flag("explain-pipe");
+    let emit = match matches.get_one::<String>("emit").map(String::as_str) {
+      Some("ast") => EmitKind::Ast,
+      _ => EmitKind::Sql,
+    };
+    let pretty = matches.get_flag("pretty");
+    let diagnostics_out = matches.get_one::<String>("diagnostics-out").cloned();
+    let deny_warnings = matches.get_flag("deny-warnings");
+    let strict = strict(matches);
+    let report_suppressed = matches.get_flag("report-suppressed");
+    let filter = report_filter(matches);
+    let allow = allowed_rules(matches);
+    let stdin_filename = matches.get_one::<String>("stdin-filename").cloned();
+    let extensions = extensions(matches);
+    let output_dir = matches.get_one::<String>("output-dir").map(PathBuf::from);
+    let dialect = dialect(matches);
+    let config = matches.get_one::<String>("config").cloned();
+    Self {
+      keep_comments,
+      output,
+      force,
+      dry_run,
+      format,
+      schema,
+      includes,
+      color,
+      target_version,
+      error_limit,
+      count_only,
+      explain_pipe,
+      emit,
+      pretty,
+      diagnostics_out,
+      deny_warnings,
+      strict,
+      report_suppressed,
+      filter,
+      allow,
+      stdin_filename,
+      extensions,
+      output_dir,
+      dialect,
+      config,
+    }
+  }
+}
+
+pub struct CheckOptions {
+  pub format: ReportFormat,
+  pub schema: Option<String>,
+  pub includes: Vec<String>,
+  pub color: ColorMode,
+  pub fix: FixMode,
+  pub error_limit: usize,
+  pub diagnostics_out: Option<String>,
+  pub deny_warnings: bool,
+  pub strict: bool, // `--strict` or `-W error`
+  pub report_suppressed: bool,
+  pub filter: ReportFilter,
+  pub allow: Vec<String>,
+  pub stdin_filename: Option<String>,
+  pub extensions: Vec<String>,
+  pub dialect: Option<Dialect>,
+  pub config: Option<String>,
+}
+
+impl CheckOptions {
+  pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+    let format = report_format(matches);
+    let schema = matches.get_one::<String>("schema").cloned();
+    let includes = matches.get_many::<String>("include").unwrap_or_default().cloned().collect();
+    let color = color_mode(matches);
+    let fix = fix_mode(matches);
+    let error_limit = error_limit(matches);
+    let diagnostics_out = matches.get_one::<String>("diagnostics-out").cloned();
+    let deny_warnings = matches.get_flag("deny-warnings");
+    let strict = strict(matches);
+    let report_suppressed = matches.get_flag("report-suppressed");
+    let filter = report_filter(matches);
+    let allow = allowed_rules(matches);
+    let stdin_filename = matches.get_one::<String>("stdin-filename").cloned();
+    let extensions = extensions(matches);
+    let dialect = dialect(matches);
+    let config = matches.get_one::<String>("config").cloned();
+    Self {
+      format,
+      schema,
+      includes,
+      color,
+      fix,
+      error_limit,
+      diagnostics_out,
+      deny_warnings,
+      strict,
+      report_suppressed,
+      filter,
+      allow,
+      stdin_filename,
+      extensions,
+      dialect,
+      config,
+    }
+  }
+}
+
+pub struct FmtOptions {
+  pub check: bool,  // only report the files that would change
+  pub stdout: bool, // print instead of writing
+  pub style: FormatStyle,
+  pub color: ColorMode,
+  pub stdin_filename: Option<String>,
+  pub extensions: Vec<String>,
+}
+
+impl FmtOptions {
+  pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+    let check = matches.get_flag("check");
+    let stdout = matches.get_flag("stdout");
+    let align_aliases = matches.get_flag("align-aliases");
+    let style = FormatStyle { align_aliases, max_width: matches.get_one::<usize>("max-width").copied() };
+    let color = color_mode(matches);
+    let stdin_filename = matches.get_one::<String>("stdin-filename").cloned();
+    let extensions = extensions(matches);
+    Self { check, stdout, style, color, stdin_filename, extensions }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AstFormat {
+  Tree,  // an indented outline of the nodes
+  Json,  // the nodes as pretty JSON
+  Spipe, // the program written back in the pipe language, without its comments
+}
+
+pub struct AstOptions {
+  pub format: AstFormat,
+  pub statement: Option<usize>, // the top-level statement to print, from 1
+  pub color: ColorMode,
+  pub stdin_filename: Option<String>,
+}
+
+impl AstOptions {
+  pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+    let format = match matches.get_one::<String>("format").map(String::as_str) {
+      Some("json") => AstFormat::Json,
+      Some("spipe") => AstFormat::Spipe,
+      _ => AstFormat::Tree,
+    };
+    let statement = matches.get_one::<usize>("statement").copied();
+    let color = color_mode(matches);
+    let stdin_filename = matches.get_one::<String>("stdin-filename").cloned();
+    Self { format, statement, color, stdin_filename }
+  }
+}
+
+pub struct TokensOptions {
+  pub json: bool, // a JSON array instead of one line per token
+  pub color: ColorMode,
+  pub stdin_filename: Option<String>,
+}
+
+impl TokensOptions {
+  pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+    let json = matches.get_one::<String>("format").is_some_and(|format| format == "json");
+    let color = color_mode(matches);
+    let stdin_filename = matches.get_one::<String>("stdin-filename").cloned();
+    Self { json, color, stdin_filename }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ResultFormat {
+  Table, // aligned columns under their names, like psql
+  Csv,   // RFC 4180, a header line then one line per row
+  Json,  // an array of objects keyed by column name
+}
+
+// the connection and output settings are only read by the runner, which needs one of the database features
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")), allow(dead_code))]
+pub struct RunOptions {
+  pub db: String,
+  pub url: Option<String>,
+  pub tables: Vec<(String, String)>, // `--table name=file.csv`
+  pub header: bool,
+  pub transaction: bool,
+  pub timeout: Option<u64>,
+  pub output: ResultFormat,
+  pub out: Option<String>,
+  pub max_col_width: Option<usize>,
+  pub limit_display: Option<usize>,
+  pub dry_run: bool,
+  pub schema: Option<String>,
+  pub stdin_filename: Option<String>,
+  pub color: ColorMode,
+  pub config: Option<String>,
+}
+
+impl RunOptions {
+  pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+    let db = matches.get_one::<String>("db").cloned().unwrap_or_default();
+    let url = matches.get_one::<String>("url").cloned();
+    let tables =
+      matches.get_many::<(String, String)>("table").map(|tables| tables.cloned().collect()).unwrap_or_default();
+    let header = !matches.get_flag("no-header");
+    let transaction = matches.get_flag("transaction");
+    let timeout = matches.get_one::<u64>("timeout").copied();
+    let output = match matches.get_one::<String>("output").map(String::as_str) {
+      Some("csv") => ResultFormat::Csv,
+      Some("json") => ResultFormat::Json,
+      _ => ResultFormat::Table,
+    };
+    let out = matches.get_one::<String>("out").cloned();
+    let max_col_width = matches.get_one::<usize>("max-col-width").copied();
+    let limit_display = matches.get_one::<usize>("limit-display").copied();
+    let dry_run = matches.get_flag("dry-run");
+    let schema = matches.get_one::<String>("schema").cloned();
+    let stdin_filename = matches.get_one::<String>("stdin-filename").cloned();
+    let color = color_mode(matches);
+    let config = matches.get_one::<String>("config").cloned();
+    Self {
+      db,
+      url,
+      tables,
+      header,
+      transaction,
+      timeout,
+      output,
+      out,
+      max_col_width,
+      limit_display,
+      dry_run,
+      schema,
+      stdin_filename,
+      color,
+      config,
+    }
+  }
+
+  // the server of `--url`, sqlite otherwise
+  pub fn dialect(&self) -> Dialect {
+    self.url.as_deref().and_then(Dialect::from_url).unwrap_or(Dialect::Sqlite)
+  }
+}
 
-  return matches;
+fn error_limit(matches: &clap::ArgMatches) -> usize {
+  matches.get_one::<usize>("error-limit").copied().unwrap_or(DEFAULT_ERROR_LIMIT)
 }
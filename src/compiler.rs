@@ -0,0 +1,42 @@
+use crate::checker::{Checker, Schema};
+use crate::config::Config;
+use crate::diagnostics::maneger::{DiagnosticsManager, Severity, TypeError};
+use crate::lexer::Lexer;
+use crate::parser::ast::Program;
+use crate::parser::Parser;
+use crate::utils::range::Range;
+use crate::utils::source::Source;
+
+// Lexes, parses and checks a source without exiting on errors.
+// Syntax errors are reported together with what the checker finds in the statements that did parse,
+// but the program is `None` when the source has any. The `included` programs only lend their names to the checker.
+pub fn compile(
+  source: &Source,
+  schema: Option<&Schema>,
+  included: &[Program],
+  config: &Config,
+) -> (Option<Program>, DiagnosticsManager) {
+  let mut lexer = Lexer::new(source);
+  let mut parser = Parser::new(&mut lexer);
+  let (program, errors) = parser.parse();
+  let mut checker = if config.strict { Checker::new().with_strict_mode() } else { Checker::new() };
+  checker.with_target(config.dialect, config.keep_qualify);
+  checker.with_lints(config.lint_config());
+  if let Some(schema) = schema {
+    checker.with_schema(schema.clone());
+  }
+  included.iter().for_each(|program| checker.with_included(program));
+  checker.check(&program);
+  let mut diagnostics = DiagnosticsManager::new();
+  let parsed = !errors.iter().any(|diagnostic| diagnostic.severity == Severity::Error);
+  // whitespace and comments alone compile to nothing, which is rarely what was meant
+  if parsed && program.statements.is_empty() {
+    diagnostics.add(TypeError::EmptyProgram { range: Range::new(0, source.raw.len()) }.into());
+  }
+  errors.into_iter().for_each(|error| diagnostics.add(error));
+  let checked = checker.into_diagnostics();
+  diagnostics.suppressed = checked.suppressed;
+  diagnostics.strict = checked.strict;
+  checked.diagnostics.into_iter().for_each(|diagnostic| diagnostics.add(diagnostic));
+  (parsed.then_some(program), diagnostics)
+}
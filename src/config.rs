@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::checker::LintConfig;
+use crate::emiter::Dialect;
+
+// looked for in this order in each directory, the hidden name keeps the project root tidy
+pub const CONFIG_FILES: [&str; 2] = ["spipe.toml", ".spipe.toml"];
+
+// Project settings from `spipe.toml` or `.spipe.toml`, command line flags win over them:
+// dialect = "postgresql"
+// output_dir = "./sql/"
+// schema = "./schema.json"
+// keep_qualify = true  # emit QUALIFY as written even for dialects without it
+// tab_width = 2        # columns a tab takes in diagnostic snippets, 4 without it
+// max_joined_tables = 12  # distinct tables a query may join before `wide-join` warns, 10 without it
+// strict = true        # any diagnostic fails compile and check, the same as `--strict`
+// [lints]
+// unused-alias = false  # turn a lint off for compile and check
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  pub dialect: Dialect,
+  pub output_dir: Option<PathBuf>,
+  pub schema: Option<PathBuf>,
+  pub keep_qualify: bool,
+  pub tab_width: Option<usize>,
+  pub max_joined_tables: Option<usize>,
+  pub strict: bool,
+  pub lints: HashMap<String, bool>,
+}
+
+impl Config {
+  pub fn from_toml(raw: &str) -> Result<Self, toml::de::Error> {
+    toml::from_str(raw)
+  }
+
+  // the nearest config file in `start` or one of its parents
+  pub fn discover(start: &Path) -> Result<Option<Config>, String> {
+    let mut candidates = start.ancestors().flat_map(|dir| CONFIG_FILES.map(|name| dir.join(name)));
+    let Some(path) = candidates.find(|path| path.is_file()) else {
+      return Ok(None);
+    };
+    Config::load(&path).map(Some)
+  }
+
+  // paths in the file are relative to it
+  pub fn load(path: &Path) -> Result<Config, String> {
+    let raw = std::fs::read_to_string(path).map_err(|err| format!("cannot read '{}': {}", path.display(), err))?;
+    let mut config = Config::from_toml(&raw).map_err(|err| format!("invalid '{}': {}", path.display(), err))?;
+    if let Some(name) = config.lints.keys().find(|name| !LintConfig::new().set(name, true)) {
+      return Err(format!("invalid '{}': unknown lint '{}'", path.display(), name));
+    }
+    let root = path.parent().unwrap_or(Path::new("."));
+    config.output_dir = config.output_dir.map(|dir| root.join(dir));
+    config.schema = config.schema.map(|schema| root.join(schema));
+    Ok(config)
+  }
+
+  // the lints on by default, with the `[lints]` table applied
+  pub fn lint_config(&self) -> LintConfig {
+    let mut lints = LintConfig::new();
+    if let Some(max_joined_tables) = self.max_joined_tables {
+      lints = lints.with_max_joined_tables(max_joined_tables);
+    }
+    for (name, enabled) in &self.lints {
+      lints.set(name, *enabled);
+    }
+    lints
+  }
+
+  // rules given to `--allow` are off whatever `[lints]` says
+  pub fn with_allowed(mut self, rules: &[String]) -> Self {
+    for rule in rules {
+      self.lints.insert(rule.clone(), false);
+    }
+    self
+  }
+
+  // a `--dialect` flag wins over the config's
+  pub fn with_dialect(mut self, dialect: Option<Dialect>) -> Self {
+    if let Some(dialect) = dialect {
+      self.dialect = dialect;
+    }
+    self
+  }
+
+  // `--strict` turns strict mode on whatever the config says
+  pub fn with_strict(mut self, strict: bool) -> Self {
+    self.strict |= strict;
+    self
+  }
+
+  // `<output_dir>/<dir>/<name>.sql` for a source file at `<dir>/<name>.<ext>` under the directory it was found in
+  pub fn output_for(&self, relative: &Path) -> Option<String> {
+    let output_dir = self.output_dir.as_ref()?;
+    relative.file_stem()?;
+    Some(output_dir.join(relative).with_extension("sql").display().to_string())
+  }
+}
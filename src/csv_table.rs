@@ -0,0 +1,122 @@
+use crate::checker::Schema;
+
+// how many rows the column types are guessed from
+const SAMPLE_ROWS: usize = 100;
+
+// A CSV file `hoshi run --table name=file.csv` loads as a table:
+// name,age        CREATE TABLE "people" ("name" TEXT, "age" INTEGER)
+// ana,31     =>   INSERT INTO "people" VALUES ('ana', 31)
+// rui,            INSERT INTO "people" VALUES ('rui', NULL)
+// the rows are only read by the runner, which needs one of the database features
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")), allow(dead_code))]
+#[derive(Debug, Clone)]
+pub struct CsvTable {
+  pub name: String,
+  pub columns: Vec<(String, ColumnKind)>,
+  pub rows: Vec<Vec<String>>, // an empty field is NULL
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ColumnKind {
+  Integer,
+  Real,
+  Boolean, // true and false, in any case
+  Text,
+}
+
+impl ColumnKind {
+  pub fn emit(&self) -> &'static str {
+    match self {
+      ColumnKind::Integer => "INTEGER",
+      ColumnKind::Real => "REAL",
+      ColumnKind::Boolean => "BOOLEAN",
+      ColumnKind::Text => "TEXT",
+    }
+  }
+
+  pub fn of(value: &str) -> Self {
+    let numeric = value.bytes().all(|byte| byte.is_ascii_digit() || b"+-.eE".contains(&byte));
+    if value.parse::<i64>().is_ok() {
+      ColumnKind::Integer
+    } else if numeric && value.parse::<f64>().is_ok() {
+      ColumnKind::Real
+    } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+      ColumnKind::Boolean
+    } else {
+      ColumnKind::Text
+    }
+  }
+
+  // integers widen to reals, any other mix is a conflict
+  pub fn merge(self, other: Self) -> Option<Self> {
+    match (self, other) {
+      (left, right) if left == right => Some(left),
+      (ColumnKind::Integer, ColumnKind::Real) | (ColumnKind::Real, ColumnKind::Integer) => Some(ColumnKind::Real),
+      _ => None,
+    }
+  }
+}
+
+impl CsvTable {
+  // columns are named by the header, or `column1`, `column2`... without one,
+  // the notices name the columns whose values clashed and were loaded as TEXT
+  pub fn read(name: &str, path: &str, header: bool) -> Result<(Self, Vec<String>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+      .has_headers(header)
+      .from_path(path)
+      .map_err(|err| format!("cannot read '{}': {}", path, err))?;
+    let mut rows = vec![];
+    for record in reader.records() {
+      let record = record.map_err(|err| format!("cannot read '{}': {}", path, err))?;
+      rows.push(record.iter().map(str::to_string).collect::<Vec<_>>());
+    }
+    let headers = match header {
+      true => reader.headers().map_err(|err| format!("cannot read '{}': {}", path, err))?.clone(),
+      false => csv::StringRecord::new(),
+    };
+    let width = rows.first().map_or(headers.len(), Vec::len);
+    let mut columns = vec![];
+    let mut notices = vec![];
+    for index in 0..width {
+      let column = match headers.get(index) {
+        Some(column) if !column.is_empty() => column.to_string(),
+        _ => format!("column{}", index + 1),
+      };
+      let (kind, conflict) = infer(rows.iter().take(SAMPLE_ROWS).map(|row| row[index].as_str()));
+      if let Some((first, second)) = conflict {
+        notices.push(format!(
+          "column `{}` of `{}` mixes {} and {} values, it is loaded as TEXT",
+          column,
+          name,
+          first.emit(),
+          second.emit()
+        ));
+      }
+      columns.push((column, kind));
+    }
+    Ok((CsvTable { name: name.to_string(), columns, rows }, notices))
+  }
+}
+
+// the columns of the loaded tables, added to the schema the checker already has
+pub fn extend_schema(schema: &mut Schema, tables: &[CsvTable]) {
+  for table in tables {
+    let columns = table.columns.iter().map(|(column, _)| column.clone()).collect();
+    schema.tables.insert(table.name.clone(), columns);
+  }
+}
+
+// the kind every sampled value fits, TEXT and the first two that clash when there is none
+fn infer<'a>(values: impl Iterator<Item = &'a str>) -> (ColumnKind, Option<(ColumnKind, ColumnKind)>) {
+  let mut kinds = values.filter(|value| !value.is_empty()).map(ColumnKind::of);
+  let Some(mut inferred) = kinds.next() else {
+    return (ColumnKind::Text, None);
+  };
+  for kind in kinds {
+    match inferred.merge(kind) {
+      Some(merged) => inferred = merged,
+      None => return (ColumnKind::Text, Some((inferred, kind))),
+    }
+  }
+  (inferred, None)
+}
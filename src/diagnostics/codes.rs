@@ -0,0 +1,118 @@
+// every stable diagnostic code with a description that holds for each of its messages,
+// the ones nothing reports yet are kept so they are not given to something else
+pub const CODES: &[(&str, &str)] = &[
+  ("E0001", "unexpected character"),
+  ("E0002", "incomplete operator"),
+  ("E0003", "unterminated string literal"),
+  ("E0004", "malformed number"),
+  ("E0101", "unexpected token"),
+  ("E0102", "expected a different token"),
+  ("E0103", "expected a literal"),
+  ("E0104", "unknown interval unit"),
+  ("E0105", "chained comparison"),
+  ("W0101", "trailing comma in a list"),
+  ("W0102", "keyword used as a name"),
+  ("E0201", "pipe missing `FROM` clause (reserved)"),
+  ("E0202", "unexpected token (reserved)"),
+  ("E0203", "missing `SELECT` clause"),
+  ("E0204", "unsupported operator (reserved)"),
+  ("E0205", "missing `GROUP BY` clause"),
+  ("E0206", "ambiguous column reference (reserved)"),
+  ("E0207", "function argument mismatch (reserved)"),
+  ("E0208", "duplicate alias"),
+  ("E0209", "interval value is not a number"),
+  ("E0210", "unknown table"),
+  ("E0211", "unknown column"),
+  ("E0212", "unknown window"),
+  ("E0213", "INSERT column count mismatch"),
+  ("E0214", "ORDER BY key does not resolve"),
+  ("E0215", "DROP INDEX without its table"),
+  ("E0216", "DISTINCT ON outside postgres"),
+  ("E0217", "malformed recursive CTE"),
+  ("E0218", "row value size mismatch"),
+  ("E0219", "percentile per group without percentiles"),
+  ("E0220", "ORDER BY position out of range"),
+  ("E0221", "grouping the dialect has no syntax for"),
+  ("E0301", "the database rejected the statement"),
+  ("W0201", "missing index on join"),
+  ("W0202", "redundant subquery"),
+  ("W0203", "duplicate column"),
+  ("W0204", "unused alias"),
+  ("W0205", "duplicate table"),
+  ("W0206", "`LIMIT count, offset` is read the other way round by mysql"),
+  ("W0207", "QUALIFY kept for a dialect without it"),
+  ("W0208", "TABLESAMPLE approximated with a random filter"),
+  ("W0209", "unknown rule in a suppression comment"),
+  ("W0210", "COPY outside postgres"),
+  ("W0211", "empty program"),
+  ("W0212", "statement removes every row of a table"),
+  ("W0213", "COMMENT ON outside postgres"),
+  ("W0214", "GRANT or REVOKE needs administrator privileges"),
+  ("W0215", "COMMIT without BEGIN"),
+  ("W0216", "recursive CTE without a WHERE"),
+  ("W0217", "query joins too many tables"),
+  ("W0218", "string literal looks like sql injection"),
+  ("W0219", "PRAGMA outside sqlite"),
+  ("W0220", "PERCENTILE_CONT approximated by the nearest value"),
+  ("W0221", "condition compares a value with itself"),
+  ("W0222", "OR in a WHERE condition"),
+  ("I0201", "statement rewritten for the dialect"),
+];
+
+pub fn describe(code: &str) -> Option<&'static str> {
+  CODES.iter().find(|(known, _)| *known == code).map(|(_, description)| *description)
+}
+
+// names for the codes a `spipe:allow(...)` comment can silence
+pub const RULES: &[(&str, &str)] = &[
+  ("missing-join-index", "W0201"),
+  ("redundant-subquery", "W0202"),
+  ("duplicate-column", "W0203"),
+  ("unused-alias", "W0204"),
+  ("duplicate-table", "W0205"),
+  ("comma-offset", "W0206"),
+  ("unsupported-qualify", "W0207"),
+  ("approximated-sample", "W0208"),
+  ("postgres-only-copy", "W0210"),
+  ("destructive-operation", "W0212"),
+  ("postgres-only-comment", "W0213"),
+  ("access-control", "W0214"),
+  ("commit-without-begin", "W0215"),
+  ("unbounded-recursion", "W0216"),
+  ("wide-join", "W0217"),
+  ("potential-injection", "W0218"),
+  ("sqlite-only-pragma", "W0219"),
+  ("approximated-percentile", "W0220"),
+  ("tautological-condition", "W0221"),
+  ("or-in-where", "W0222"),
+  ("dialect-rewrite", "I0201"),
+  ("duplicate-alias", "E0208"),
+];
+
+pub fn rule_code(name: &str) -> Option<&'static str> {
+  RULES.iter().find(|(rule, _)| *rule == name).map(|(_, code)| *code)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn codes_are_unique() {
+    for (index, (code, _)) in CODES.iter().enumerate() {
+      assert!(!CODES[index + 1..].iter().any(|(other, _)| other == code), "{} is listed twice", code);
+      let (kind, number) = code.split_at(1);
+      assert!(["E", "W", "I"].contains(&kind) && number.len() == 4 && number.parse::<u16>().is_ok(), "{}", code);
+    }
+  }
+
+  #[test]
+  fn rules_name_one_known_code_each() {
+    for (index, (rule, code)) in RULES.iter().enumerate() {
+      let others = &RULES[index + 1..];
+      assert!(!others.iter().any(|(other, _)| other == rule), "{} is listed twice", rule);
+      assert!(!others.iter().any(|(_, other)| other == code), "{} has two rules", code);
+      assert!(describe(code).is_some(), "{} names the unknown code {}", rule, code);
+    }
+  }
+}
@@ -0,0 +1,489 @@
+// the long form of each code, for `hoshi explain <code>`
+pub struct Explanation {
+  pub code: &'static str,
+  pub text: &'static str,
+  pub example: &'static str, // pipe sql that gets the diagnostic
+  pub fix: &'static str,     // the same query without it, or under the setting that avoids it
+}
+
+pub const EXPLANATIONS: &[Explanation] = &[
+  Explanation {
+    code: "E0001",
+    text: "The lexer met a character that starts no token. Pipe sql has no `#` comments and no operators such as `@` \
+           or `~`, so the character is most likely a typo or pasted from another language.",
+    example: "FROM users\n|> WHERE age > 18 # adults\n|> SELECT name;",
+    fix: "FROM users\n|> WHERE age > 18 -- adults\n|> SELECT name;",
+  },
+  Explanation {
+    code: "E0002",
+    text: "An operator was cut short: `!` only exists as part of `!=`, and a lone `|` only as part of the `|>` pipe.",
+    example: "FROM users | SELECT name;",
+    fix: "FROM users |> SELECT name;",
+  },
+  Explanation {
+    code: "E0003",
+    text: "A string literal was opened with `'` and the file or line ended before the closing quote.",
+    example: "FROM users\n|> WHERE name = 'alice\n|> SELECT name;",
+    fix: "FROM users\n|> WHERE name = 'alice'\n|> SELECT name;",
+  },
+  Explanation {
+    code: "E0004",
+    text: "A number has more than one decimal point, or some other shape no database reads as a number.",
+    example: "FROM orders\n|> WHERE total_amount > 1.2.3\n|> SELECT id;",
+    fix: "FROM orders\n|> WHERE total_amount > 1.23\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0101",
+    text: "The parser found a token where no statement or expression can start, often a doubled operator or a \
+           keyword in the wrong stage.",
+    example: "FROM users\n|> WHERE age > > 18\n|> SELECT name;",
+    fix: "FROM users\n|> WHERE age > 18\n|> SELECT name;",
+  },
+  Explanation {
+    code: "E0102",
+    text: "The grammar wanted a specific token at this point, the message names it. A JOIN needs a table before its \
+           ON, a cast needs AS, a pipe stage needs `|>` before it.",
+    example: "FROM orders\n|> JOIN ON orders.customer_id = customers.id\n|> SELECT id;",
+    fix: "FROM orders\n|> JOIN customers ON orders.customer_id = customers.id\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0103",
+    text: "A literal has the right kind but a value the statement cannot take, such as a COPY delimiter longer \
+           than one character.",
+    example: "COPY orders FROM '/tmp/orders.csv' WITH (DELIMITER ';;');",
+    fix: "COPY orders FROM '/tmp/orders.csv' WITH (DELIMITER ';');",
+  },
+  Explanation {
+    code: "E0104",
+    text: "An interval names a unit the dialects do not share. The units are SECOND, MINUTE, HOUR, DAY, WEEK, MONTH \
+           and YEAR, singular or plural.",
+    example: "FROM orders\n|> WHERE created_at > NOW() - INTERVAL '3 fortnights'\n|> SELECT id;",
+    fix: "FROM orders\n|> WHERE created_at > NOW() - INTERVAL '6 weeks'\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0105",
+    text: "Comparisons do not chain in sql. `a < b < c` is not a range test: it would compare the true or false of \
+           `a < b` with `c`. Write each comparison out and join them with AND, `hoshi check --fix` does it for you.",
+    example: "FROM orders\n|> WHERE 10 < total_amount < 100\n|> SELECT id;",
+    fix: "FROM orders\n|> WHERE 10 < total_amount AND total_amount < 100\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0101",
+    text: "A SELECT or GROUP BY list ends with a comma, often left behind after removing its last column. The comma \
+           is dropped, and `hoshi check --fix` removes it from the file.",
+    example: "FROM orders\n|> SELECT id, customer_id,\n|> GROUP BY id, customer_id,;",
+    fix: "FROM orders\n|> SELECT id, customer_id\n|> GROUP BY id, customer_id;",
+  },
+  Explanation {
+    code: "W0102",
+    text: "A keyword such as COUNT or SUM follows AS, where only a name can go, so it is read as the name. hoshi \
+           only reads uppercase words as keywords and sql folds unquoted names, so the lowercase spelling is the \
+           same name without the clash; `hoshi check --fix` rewrites it. A word the database itself reserves, \
+           like ORDER, still needs another name.",
+    example: "FROM orders\n|> AGGREGATE COUNT(id) AS COUNT GROUP BY customer_id;",
+    fix: "FROM orders\n|> AGGREGATE COUNT(id) AS count GROUP BY customer_id;",
+  },
+  Explanation {
+    code: "E0201",
+    text: "A pipe has no FROM to read its rows from. Every pipe query starts from a table, and each `|>` stage works \
+           on the rows of the stage before it. hoshi does not report it yet, the code is reserved for it.",
+    example: "|> AGGREGATE SUM(total_amount)\nGROUP BY customer_id;",
+    fix: "FROM orders\n|> AGGREGATE SUM(total_amount)\nGROUP BY customer_id;",
+  },
+  Explanation {
+    code: "E0202",
+    text: "The checker found a token that makes no sense where it stands, even though the parser accepted it. hoshi does not report it yet, the code is reserved for it.",
+    example: "FROM users\n|> SELECT name name;",
+    fix: "FROM users\n|> SELECT name;",
+  },
+  Explanation {
+    code: "E0203",
+    text: "A SELECT lists no columns. Name the columns to keep, or `*` for all of them.",
+    example: "FROM users\n|> SELECT;",
+    fix: "FROM users\n|> SELECT name, email;",
+  },
+  Explanation {
+    code: "E0204",
+    text: "The condition uses an operator hoshi cannot translate for every dialect. Comparisons, the arithmetic \
+           `+`, `-`, `*`, `/` and `%`, AND, OR, NOT and IN are supported, string concatenation is written with \
+           CONCAT. hoshi does not report it yet, the code is reserved for it.",
+    example: "FROM users\n|> WHERE name || ' ' || surname = 'ana lima'\n|> SELECT id;",
+    fix: "FROM users\n|> WHERE CONCAT(name, ' ', surname) = 'ana lima'\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0205",
+    text: "A query mixes aggregates with plain columns, or has a GROUP BY with nothing to group on. Every column next \
+           to an aggregate must be grouped on, `hoshi check --fix` adds the missing GROUP BY.",
+    example: "FROM orders\n|> SELECT customer_id, SUM(total_amount);",
+    fix: "FROM orders\n|> SELECT customer_id, SUM(total_amount)\n|> GROUP BY customer_id;",
+  },
+  Explanation {
+    code: "E0206",
+    text: "A column name exists in more than one of the joined tables, so the database cannot tell which one is meant. hoshi does not report it yet, the code is reserved for it.",
+    example: "FROM orders\n|> JOIN customers ON customer_id = customers.id\n|> SELECT id;",
+    fix: "FROM orders\n|> JOIN customers ON orders.customer_id = customers.id\n|> SELECT orders.id;",
+  },
+  Explanation {
+    code: "E0207",
+    text: "A function was called with the wrong number of arguments for every dialect. hoshi does not report it yet, the code is reserved for it.",
+    example: "FROM orders\n|> SELECT ROUND();",
+    fix: "FROM orders\n|> SELECT ROUND(total_amount);",
+  },
+  Explanation {
+    code: "E0208",
+    text: "The same alias names two things in one query, so later stages cannot tell which one a reference means.",
+    example: "FROM orders AS o\n|> JOIN customers AS o ON o.customer_id = o.id\n|> SELECT o.id;",
+    fix: "FROM orders AS o\n|> JOIN customers AS c ON o.customer_id = c.id\n|> SELECT o.id;",
+  },
+  Explanation {
+    code: "E0209",
+    text: "An interval's value is not a number. Each dialect spells intervals differently, and hoshi can only \
+           rewrite the ones with a numeric amount.",
+    example: "FROM orders\n|> WHERE created_at > NOW() - INTERVAL 'few days'\n|> SELECT id;",
+    fix: "FROM orders\n|> WHERE created_at > NOW() - INTERVAL '3 days'\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0210",
+    text: "The table is not in the schema passed with `--schema` or set in spipe.toml, most often a typo or a table \
+           that was renamed.",
+    example: "FROM order\n|> SELECT id;",
+    fix: "FROM orders\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0211",
+    text: "None of the tables the query reads has this column in the schema.",
+    example: "FROM orders\n|> SELECT total_amount;",
+    fix: "FROM orders\n|> SELECT total;",
+  },
+  Explanation {
+    code: "E0212",
+    text: "`OVER name` refers to a window no WINDOW stage of the query defines.",
+    example: "FROM employees\n|> SELECT name, RANK() OVER w;",
+    fix: "FROM employees\n|> WINDOW w AS (PARTITION BY dept ORDER BY salary DESC)\n|> SELECT name, RANK() OVER w;",
+  },
+  Explanation {
+    code: "E0213",
+    text: "An INSERT lists its columns, but a row of VALUES or the query it reads from fills a different number of \
+           them. Every row needs exactly one value per listed column, in the same order.",
+    example: "INSERT INTO summary (dept, total)\nFROM employees\n|> SELECT dept, name, salary;",
+    fix: "INSERT INTO summary (dept, total)\nFROM employees\n|> SELECT dept, salary;",
+  },
+  Explanation {
+    code: "E0214",
+    text: "An ORDER BY key names nothing the query still has. A qualified column needs a table the query reads, \
+           and after an AGGREGATE only its alias and the GROUP BY columns are left to order by.",
+    example: "FROM employees\n|> AGGREGATE SUM(salary) AS total GROUP BY dept\n|> ORDER BY salary DESC;",
+    fix: "FROM employees\n|> AGGREGATE SUM(salary) AS total GROUP BY dept\n|> ORDER BY total DESC;",
+  },
+  Explanation {
+    code: "E0215",
+    text: "mysql keeps index names per table, so DROP INDEX needs the table the index is on. The other dialects \
+           name indexes per schema and get the DROP INDEX without it.",
+    example: "DROP INDEX orders_customer;",
+    fix: "DROP INDEX orders_customer ON orders;",
+  },
+  Explanation {
+    code: "E0216",
+    text: "DISTINCT ON keeps the first row of each group of the listed expressions, which only postgres can do \
+           in the SELECT itself. Elsewhere, number the rows of each group with ROW_NUMBER() and keep the first \
+           with QUALIFY, which hoshi rewrites for the dialects without it.",
+    example: "FROM orders\n|> SELECT DISTINCT ON (customer_id) customer_id, total_amount;",
+    fix: "FROM orders\n|> SELECT customer_id, total_amount\n|> QUALIFY ROW_NUMBER() OVER (PARTITION BY customer_id) = 1;",
+  },
+  Explanation {
+    code: "E0217",
+    text: "A recursive cte reads itself: a base query that does not gives the first rows, and a second query \
+           joined to it by UNION ALL reads the rows found so far to find the next ones. The database runs \
+           nothing else, so a cte reading itself in its first query, in more than two queries, or under a plain \
+           UNION is rejected.",
+    example: "WITH RECURSIVE tree AS (FROM tree |> SELECT id)\nFROM tree;",
+    fix: "WITH RECURSIVE tree AS (\n  FROM categories |> WHERE parent_id = 0 |> SELECT id\n  UNION ALL\n  \
+          FROM categories |> JOIN tree ON categories.parent_id = tree.id |> SELECT categories.id\n)\nFROM tree;",
+  },
+  Explanation {
+    code: "E0218",
+    text: "A row value `(a, b)` on the left of IN is compared with each row of the list a value at a time, so \
+           every row needs as many values as the left side. sqlite only compares row values with a subquery, \
+           there the list is written out as equalities joined by OR.",
+    example: "FROM orders\n|> WHERE (customer_id, status) IN ((1, 'paid'), (2))\n|> SELECT id;",
+    fix: "FROM orders\n|> WHERE (customer_id, status) IN ((1, 'paid'), (2, 'paid'))\n|> SELECT id;",
+  },
+  Explanation {
+    code: "E0219",
+    text: "mysql and sqlite have no PERCENTILE_CONT or PERCENTILE_DISC, so hoshi looks the value up with a subquery \
+           that ranks every row the query reads. That subquery cannot see the groups of a GROUP BY, so it cannot give \
+           each group its own percentile. Keep a single group with WHERE, or target a dialect with percentiles.",
+    example: "-- spipe.toml: dialect = \"mysql\"\nFROM employees\n|> AGGREGATE PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY salary) AS median\nGROUP BY dept;",
+    fix: "-- spipe.toml: dialect = \"mysql\"\nFROM employees\n|> WHERE dept = 'sales'\n|> AGGREGATE PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY salary) AS median;",
+  },
+  Explanation {
+    code: "E0220",
+    text: "An ORDER BY position points past the selected columns. A position counts the columns of the SELECT from 1, \
+           so it is between 1 and how many columns the SELECT lists.",
+    example: "FROM employees\n|> SELECT name, salary\n|> ORDER BY 3 DESC;",
+    fix: "FROM employees\n|> SELECT name, salary\n|> ORDER BY 2 DESC;",
+  },
+  Explanation {
+    code: "E0221",
+    text: "ROLLUP, CUBE and GROUPING SETS group the rows several ways in one query. postgres has all three, mysql \
+           only has ROLLUP, written `GROUP BY a, b WITH ROLLUP`, and sqlite has none. Without them, run one query \
+           per grouping and stack the results with UNION ALL.",
+    example: "-- spipe.toml: dialect = \"sqlite\"\nFROM sales\n|> AGGREGATE SUM(amount) AS total\nGROUP BY ROLLUP(region);",
+    fix: "-- spipe.toml: dialect = \"sqlite\"\nFROM sales\n|> AGGREGATE SUM(amount) AS total\nGROUP BY region;",
+  },
+  Explanation {
+    code: "E0301",
+    text: "`hoshi run` hands each statement to the database, which can still refuse one that compiles: a table \
+           it does not have, a constraint the rows break. The statements before it have already run, unless \
+           `--transaction` rolled them back.",
+    example: "FROM missing_table\n|> SELECT id;",
+    fix: "FROM users\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0201",
+    text: "A join reads a table the query has not read before. Without an index on the join column the database \
+           compares every row of one table with every row of the other; check the ON columns are indexed, or \
+           silence it with `spipe:allow(missing-join-index)` once they are.",
+    example: "FROM orders\n|> JOIN customers ON orders.customer_id = customers.id\n|> SELECT orders.id;",
+    fix:
+      "-- spipe:allow(missing-join-index)\nFROM orders\n|> JOIN customers ON orders.customer_id = customers.id\n\
+              |> SELECT orders.id;",
+  },
+  Explanation {
+    code: "W0202",
+    text: "An aggregate runs over a subquery that could be a stage of the pipe, which makes the query harder to read \
+           and can stop the planner from merging the two.",
+    example: "FROM orders\n|> AGGREGATE SUM((FROM orders |> SELECT total_amount));",
+    fix: "FROM orders\n|> AGGREGATE SUM(total_amount);",
+  },
+  Explanation {
+    code: "W0203",
+    text: "A SELECT lists the same column twice, which returns two identical columns.",
+    example: "FROM users\n|> SELECT id, id;",
+    fix: "FROM users\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0204",
+    text: "An alias is bound but no later stage refers to it, so it can go.",
+    example: "FROM orders AS o\n|> SELECT id;",
+    fix: "FROM orders\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0205",
+    text: "A query reads the same table twice. It is sometimes meant, as in a self join, but often a stage was \
+           copied by mistake.",
+    example: "FROM orders\n|> FROM orders\n|> SELECT id;",
+    fix: "FROM orders\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0206",
+    text: "`LIMIT a, b` means offset `a` and count `b` in mysql and sqlite, the reverse of what it reads like. \
+           `LIMIT count OFFSET offset` means the same thing everywhere.",
+    example: "FROM products\n|> LIMIT 10, 20;",
+    fix: "FROM products\n|> LIMIT 10 OFFSET 20;",
+  },
+  Explanation {
+    code: "W0207",
+    text: "`keep_qualify` in spipe.toml keeps QUALIFY as written, but the dialect has no QUALIFY and will reject it. \
+           Without `keep_qualify` hoshi rewrites it into a filtered subquery.",
+    example: "-- spipe.toml: dialect = \"mysql\", keep_qualify = true\nFROM orders\n|> QUALIFY ROW_NUMBER() OVER (PARTITION BY customer_id ORDER BY created_at DESC) = 1;",
+    fix: "-- spipe.toml: dialect = \"mysql\", keep_qualify = false\nFROM orders\n|> QUALIFY ROW_NUMBER() OVER (PARTITION BY customer_id ORDER BY created_at DESC) = 1;",
+  },
+  Explanation {
+    code: "W0208",
+    text: "The dialect has no TABLESAMPLE, so the sample becomes a random filter. It still reads the whole table, \
+           and the number of rows kept varies from one run to the next.",
+    example: "FROM events TABLESAMPLE BERNOULLI (10)\n|> SELECT id;",
+    fix: "-- spipe:allow(approximated-sample)\nFROM events TABLESAMPLE BERNOULLI (10)\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0209",
+    text: "A `spipe:allow` comment names a rule that does not exist, so it silences nothing. The rule names are the \
+           ones listed by the warnings they silence, such as `unused-alias`.",
+    example: "-- spipe:allow(unused-aliases)\nFROM orders AS o\n|> SELECT id;",
+    fix: "-- spipe:allow(unused-alias)\nFROM orders AS o\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0210",
+    text: "COPY is postgres syntax and is emitted unchanged, so other databases will not run it. Use the database's \
+           own bulk loader instead, such as LOAD DATA in mysql or `.import` in sqlite.",
+    example: "-- spipe.toml: dialect = \"mysql\"\nCOPY orders TO '/tmp/orders.csv' WITH (FORMAT csv, HEADER);",
+    fix: "-- spipe.toml: dialect = \"postgres\"\nCOPY orders TO '/tmp/orders.csv' WITH (FORMAT csv, HEADER);",
+  },
+  Explanation {
+    code: "W0211",
+    text: "The file has no statements, only whitespace or comments, so it compiles to no sql at all. It is usually \
+           a file saved before its query was written, or a query left commented out.",
+    example: "-- FROM orders\n-- |> SELECT id;",
+    fix: "FROM orders\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0212",
+    text: "TRUNCATE empties the whole table at once, and some databases cannot roll it back. The warning is there so \
+           it is never run by accident; once it is meant, silence it with `spipe:allow(destructive-operation)`. \
+           sqlite has no TRUNCATE and gets a DELETE without WHERE, which removes the same rows.",
+    example: "TRUNCATE TABLE orders;",
+    fix: "-- spipe:allow(destructive-operation)\nTRUNCATE TABLE orders;",
+  },
+  Explanation {
+    code: "W0213",
+    text: "COMMENT ON is postgres syntax. The other dialects keep no comments on their tables this way, so the \
+           statement is left out of their sql and the schema goes undocumented there.",
+    example: "-- spipe.toml: dialect = \"mysql\"\nCOMMENT ON TABLE users IS 'user accounts';",
+    fix: "-- spipe.toml: dialect = \"postgres\"\nCOMMENT ON TABLE users IS 'user accounts';",
+  },
+  Explanation {
+    code: "W0214",
+    text: "GRANT and REVOKE change who may read or write a table, so only its owner or an administrator can run \
+           them; anyone else gets a permission error halfway through the script. sqlite has no users and runs \
+           neither. Once the script runs as such a role, silence it with `spipe:allow(access-control)`.",
+    example: "GRANT SELECT ON orders TO analyst;",
+    fix: "-- spipe:allow(access-control)\nGRANT SELECT ON orders TO analyst;",
+  },
+  Explanation {
+    code: "W0215",
+    text: "A COMMIT with no BEGIN before it in the same file either commits a transaction some other script \
+           opened or, in autocommit mode, nothing at all; most databases answer with a warning or an error. \
+           When the file is meant to run inside a caller's transaction, silence it with \
+           `spipe:allow(commit-without-begin)`.",
+    example: "INSERT INTO orders VALUES (1);\nCOMMIT;",
+    fix: "BEGIN;\nINSERT INTO orders VALUES (1);\nCOMMIT;",
+  },
+  Explanation {
+    code: "W0216",
+    text: "The recursive query of a cte runs until it finds no new rows. On data with a cycle, a category that \
+           is its own ancestor say, it never stops unless a WHERE bounds it, usually on a depth counted along. \
+           When the data is known to be a tree, silence it with `spipe:allow(unbounded-recursion)`.",
+    example: "WITH RECURSIVE tree AS (\n  FROM categories |> WHERE parent_id = 0 |> SELECT id\n  UNION ALL\n  \
+              FROM categories |> JOIN tree ON categories.parent_id = tree.id |> SELECT categories.id\n)\nFROM tree;",
+    fix: "WITH RECURSIVE tree AS (\n  FROM categories |> WHERE parent_id = 0 |> SELECT id, 1 AS depth\n  \
+          UNION ALL\n  FROM categories |> JOIN tree ON categories.parent_id = tree.id\n  \
+          |> WHERE tree.depth < 10 |> SELECT categories.id, tree.depth + 1\n)\nFROM tree;",
+  },
+  Explanation {
+    code: "W0217",
+    text: "Every table joined multiplies the plans the database weighs, and past a dozen or so most planners \
+           stop searching and guess, so very wide joins are often slow in ways that are hard to see. The warning \
+           comes once a query joins more distinct tables than `max_joined_tables` in spipe.toml, 10 by default. \
+           Split the query with a WITH, or silence it with `spipe:allow(wide-join)`.",
+    example: "FROM t1\n|> JOIN t2 ON t1.id = t2.id\n-- and so on up to\n|> JOIN t11 ON t10.id = t11.id;",
+    fix: "-- spipe:allow(wide-join)\nFROM t1\n|> JOIN t2 ON t1.id = t2.id\n-- and so on up to\n|> JOIN t11 ON t10.id = t11.id;",
+  },
+  Explanation {
+    code: "W0218",
+    text: "When queries are generated from outside input, a value that was not escaped shows up as a string \
+           literal holding sql: a quote followed by OR, a `;` starting another statement, or a `--` or `/*` \
+           commenting out the rest. The sql still compiles, doing something else than meant. Bind such values \
+           as parameters; when the text is genuine, silence it with `spipe:allow(potential-injection)` or turn \
+           the check off with `--no-injection-checks`.",
+    example: "FROM users\n|> WHERE name = \"x' OR '1'='1\";",
+    fix: "FROM users\n|> WHERE name = 'x';",
+  },
+  Explanation {
+    code: "W0219",
+    text: "PRAGMA reads and sets sqlite's own settings, like whether foreign keys are enforced, and describes \
+           its tables; no other database has it. hoshi leaves the statement out of the sql for any other \
+           dialect, so a script relying on it behaves differently there. Target sqlite, or keep the pragmas in a \
+           script compiled for it.",
+    example: "-- spipe.toml: dialect = \"postgres\"\nPRAGMA foreign_keys = ON;",
+    fix: "-- spipe.toml: dialect = \"sqlite\"\nPRAGMA foreign_keys = ON;",
+  },
+  Explanation {
+    code: "W0220",
+    text: "mysql and sqlite have no PERCENTILE_CONT, so hoshi ranks the values in a subquery and takes the first \
+           one at the percentile. PERCENTILE_CONT would interpolate between the two values around that position, \
+           so an even number of rows can give a different median. PERCENTILE_DISC is defined as that first value \
+           and is exact.",
+    example: "-- spipe.toml: dialect = \"mysql\"\nFROM employees\n|> AGGREGATE PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY salary) AS median;",
+    fix: "-- spipe.toml: dialect = \"mysql\"\nFROM employees\n|> AGGREGATE PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY salary) AS median;",
+  },
+  Explanation {
+    code: "W0221",
+    text: "A WHERE compares a column with itself, or a literal with the same literal, so it keeps every row, \
+           except where the column is NULL. That is rarely what was meant: usually one side was mistyped, copied \
+           from the other, or is a placeholder that was never replaced.",
+    example: "FROM products\n|> WHERE price = price\n|> SELECT id;",
+    fix: "FROM products\n|> WHERE price = list_price\n|> SELECT id;",
+  },
+  Explanation {
+    code: "W0222",
+    text: "The WHERE has an OR. It is valid, but a database often cannot use an index to find the rows matching \
+           either side, and reads the whole table instead. When both sides compare one column, IN says the same \
+           and keeps the index. Silence it with `spipe:allow(or-in-where)`.",
+    example: "FROM orders\n|> WHERE status = 'paid' OR status = 'shipped'\n|> SELECT id;",
+    fix: "FROM orders\n|> WHERE status IN ('paid', 'shipped')\n|> SELECT id;",
+  },
+  Explanation {
+    code: "I0201",
+    text: "The dialect has no such statement or clause, so hoshi emits an equivalent it does have. Nothing needs \
+           fixing; the info says why the sql differs from the query. Silence it with \
+           `spipe:allow(dialect-rewrite)` or hide infos with `--min-severity warning`.",
+    example: "FROM employees\n|> QUALIFY ROW_NUMBER() OVER (PARTITION BY dept ORDER BY salary DESC) = 1\n|> SELECT name;",
+    fix: "-- spipe:allow(dialect-rewrite)\nFROM employees\n|> QUALIFY ROW_NUMBER() OVER (PARTITION BY dept ORDER BY salary DESC) = 1\n|> SELECT name;",
+  },
+];
+
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+  EXPLANATIONS.iter().find(|explanation| explanation.code.eq_ignore_ascii_case(code))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diagnostics::codes::CODES;
+  use crate::lexer::Lexer;
+  use crate::parser::Parser;
+  use crate::utils::source::Source;
+
+  // every `"E0204"`-like string literal in the sources
+  fn quoted_codes(source: &str) -> Vec<String> {
+    let is_code = |text: &[u8]| {
+      text[0] == b'"' && text[6] == b'"' && b"EWI".contains(&text[1]) && text[2..6].iter().all(u8::is_ascii_digit)
+    };
+    let literals = source.as_bytes().windows(7).filter(|text| is_code(text));
+    literals.map(|text| String::from_utf8_lossy(&text[1..6]).into_owned()).collect()
+  }
+
+  fn sources(dir: &std::path::Path, found: &mut Vec<(String, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+      let path = entry.unwrap().path();
+      if path.is_dir() {
+        sources(&path, found);
+      } else if path.extension().is_some_and(|extension| extension == "rs") {
+        found.push((path.display().to_string(), std::fs::read_to_string(&path).unwrap()));
+      }
+    }
+  }
+
+  #[test]
+  fn every_code_is_explained() {
+    for (code, _) in CODES {
+      assert!(explain(code).is_some(), "{} has no explanation", code);
+    }
+    for explanation in EXPLANATIONS {
+      assert!(CODES.iter().any(|(code, _)| *code == explanation.code), "{} is not in CODES", explanation.code);
+    }
+  }
+
+  #[test]
+  fn every_emitted_code_is_known() {
+    let mut found = vec![];
+    sources(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut found);
+    let listed = ["codes.rs", "explain.rs"];
+    let emitted = found.iter().filter(|(path, _)| !listed.iter().any(|name| path.ends_with(name)));
+    let emitted = emitted.flat_map(|(_, source)| quoted_codes(source)).collect::<Vec<_>>();
+    assert!(emitted.contains(&"E0301".to_string()), "the codes were not found in the sources");
+    for code in emitted {
+      assert!(CODES.iter().any(|(known, _)| *known == code), "{} is emitted but not in CODES", code);
+    }
+  }
+
+  #[test]
+  fn every_fix_parses() {
+    for explanation in EXPLANATIONS {
+      let source = Source::new("fix.sql", explanation.fix);
+      let (_, errors) = Parser::new(&mut Lexer::new(&source)).parse();
+      assert!(errors.is_empty(), "the fix for {} does not parse", explanation.code);
+    }
+  }
+}
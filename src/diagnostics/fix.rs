@@ -0,0 +1,28 @@
+use super::maneger::{Diagnostic, Replacement};
+
+// the fixed source, and the suggestions left out because they overlap one applied before them
+pub fn apply_fixes<'a>(raw: &str, diagnostics: &'a [Diagnostic]) -> (String, Vec<&'a Replacement>) {
+  let mut suggestions =
+    diagnostics.iter().filter_map(|diagnostic| diagnostic.suggestion.as_deref()).collect::<Vec<_>>();
+  suggestions.sort_by_key(|suggestion| (suggestion.range.start, suggestion.range.end));
+
+  let mut fixed = String::with_capacity(raw.len());
+  let mut skipped = vec![];
+  let mut cursor = 0;
+  let mut last_insert = None;
+  for suggestion in suggestions {
+    let range = &suggestion.range;
+    // two inserts at the same place would land in an arbitrary order
+    let repeated_insert = range.start == range.end && last_insert == Some(range.start);
+    if range.start < cursor || range.end > raw.len() || repeated_insert {
+      skipped.push(suggestion);
+      continue;
+    }
+    fixed.push_str(&raw[cursor..range.start]);
+    fixed.push_str(&suggestion.new_text);
+    cursor = range.end;
+    last_insert = if range.start == range.end { Some(range.start) } else { None };
+  }
+  fixed.push_str(&raw[cursor..]);
+  (fixed, skipped)
+}
@@ -0,0 +1,54 @@
+use super::maneger::{Diagnostic, Severity};
+use crate::utils::source::Source;
+
+// one workflow command per diagnostic, which github shows inline on the pull request, e.g.
+// `::error file=query.sql,line=12,col=5,endLine=12,endColumn=9,title=W0203::duplicate column`
+pub fn report_github(diagnostics: &[Diagnostic], source: &Source) -> String {
+  let mut commands = String::new();
+  for diagnostic in diagnostics {
+    commands.push_str(&to_command(diagnostic, diagnostic.code, source));
+    // children have no code, they borrow the title of the diagnostic they belong to
+    for child in &diagnostic.children {
+      commands.push_str(&to_command(child, diagnostic.code, source));
+    }
+  }
+  commands
+}
+
+fn to_command(diagnostic: &Diagnostic, title: &str, source: &Source) -> String {
+  let (line, col) = source.offset_to_line_col(diagnostic.range.start);
+  let (end_line, end_column) = source.offset_to_line_col(diagnostic.range.end);
+  let mut properties = vec![
+    format!("file={}", escape_property(source.path)),
+    format!("line={}", line),
+    format!("col={}", col),
+    format!("endLine={}", end_line),
+    format!("endColumn={}", end_column),
+  ];
+  if !title.is_empty() {
+    properties.push(format!("title={}", escape_property(title)));
+  }
+  let mut message = diagnostic.message.clone();
+  if let Some(hint) = &diagnostic.hint {
+    message = format!("{}\nhelp: {}", message, hint);
+  }
+  format!("::{} {}::{}\n", command(&diagnostic.severity), properties.join(","), escape_data(&message))
+}
+
+fn command(severity: &Severity) -> &'static str {
+  match severity {
+    Severity::Error => "error",
+    Severity::Warning => "warning",
+    Severity::Info | Severity::Note | Severity::Help => "notice",
+  }
+}
+
+// the runner reads `%`, `\r` and `\n` as escapes in the message
+fn escape_data(text: &str) -> String {
+  text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+// property values end at `,` and the properties at `:`, so those are escaped as well
+fn escape_property(text: &str) -> String {
+  escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
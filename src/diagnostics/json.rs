@@ -0,0 +1,66 @@
+use serde::Serialize;
+
+use super::maneger::{Diagnostic, Replacement, Severity};
+use crate::utils::range::Range;
+use crate::utils::source::Source;
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+  #[serde(skip_serializing_if = "str::is_empty")]
+  code: &'a str,
+  severity: &'a Severity,
+  message: &'a str,
+  hint: &'a Option<String>,
+  file: &'a str,
+  line: usize,
+  column: usize,
+  range: &'a Range,
+  children: Vec<JsonDiagnostic<'a>>,
+  suggestion: Option<&'a Replacement>,
+}
+
+// one JSON object per line, in the order the diagnostics were found
+pub fn report_json(diagnostics: &[Diagnostic], source: &Source) -> String {
+  let lines = diagnostics.iter().map(|diagnostic| {
+    let json = to_json(diagnostic, source);
+    serde_json::to_string(&json).expect("diagnostics always serialize")
+  });
+  lines.map(|line| format!("{}\n", line)).collect()
+}
+
+fn to_json<'a>(diagnostic: &'a Diagnostic, source: &'a Source) -> JsonDiagnostic<'a> {
+  let (line, column) = source.offset_to_line_col(diagnostic.range.start);
+  JsonDiagnostic {
+    code: diagnostic.code,
+    severity: &diagnostic.severity,
+    message: &diagnostic.message,
+    hint: &diagnostic.hint,
+    file: source.path,
+    line,
+    column,
+    range: &diagnostic.range,
+    children: diagnostic.children.iter().map(|child| to_json(child, source)).collect(),
+    suggestion: diagnostic.suggestion.as_deref(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use crate::compiler::compile;
+  use crate::config::Config;
+  use crate::diagnostics::maneger::ReportFilter;
+  use crate::utils::source::Source;
+
+  // tests/playground/json/diagnostics.jsonl is the report of the .sql next to it
+  #[test]
+  fn matches_the_golden_file() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground/json");
+    let raw = std::fs::read_to_string(dir.join("diagnostics.sql")).unwrap();
+    let golden = std::fs::read_to_string(dir.join("diagnostics.jsonl")).unwrap();
+    let source = Source::new("diagnostics.sql", &raw);
+    let (_, diagnostics) = compile(&source, None, &[], &Config::default());
+    assert_eq!(diagnostics.report_json(&source, &ReportFilter::default()), golden);
+  }
+}
@@ -0,0 +1,117 @@
+use serde::Serialize;
+
+use super::maneger::{Diagnostic, Severity};
+use crate::utils::range::Range;
+use crate::utils::source::Source;
+
+// the notification a language server sends for one file, editor plugins can forward it as is
+#[derive(Serialize)]
+struct Notification<'a> {
+  jsonrpc: &'static str,
+  method: &'static str,
+  params: PublishParams<'a>,
+}
+
+#[derive(Serialize)]
+struct PublishParams<'a> {
+  uri: &'a str,
+  diagnostics: Vec<LspDiagnostic<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LspDiagnostic<'a> {
+  range: LspRange,
+  severity: u8,
+  code: &'a str,
+  source: &'static str,
+  message: String,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  related_information: Vec<RelatedInformation<'a>>,
+}
+
+#[derive(Serialize)]
+struct RelatedInformation<'a> {
+  location: Location<'a>,
+  message: &'a str,
+}
+
+#[derive(Serialize)]
+struct Location<'a> {
+  uri: &'a str,
+  range: LspRange,
+}
+
+#[derive(Serialize)]
+struct LspRange {
+  start: Position,
+  end: Position,
+}
+
+// zero-based, the character counts utf-16 code units as the spec asks
+#[derive(Serialize)]
+struct Position {
+  line: usize,
+  character: usize,
+}
+
+pub fn report_lsp(diagnostics: &[Diagnostic], source: &Source) -> String {
+  let uri = file_uri(source.path);
+  let diagnostics = diagnostics.iter().map(|diagnostic| to_lsp(diagnostic, &uri, source)).collect();
+  let params = PublishParams { uri: &uri, diagnostics };
+  let notification = Notification { jsonrpc: "2.0", method: "textDocument/publishDiagnostics", params };
+  format!("{}\n", serde_json::to_string(&notification).expect("diagnostics always serialize"))
+}
+
+fn to_lsp<'a>(diagnostic: &'a Diagnostic, uri: &'a str, source: &Source) -> LspDiagnostic<'a> {
+  let mut message = diagnostic.message.clone();
+  if let Some(hint) = &diagnostic.hint {
+    message = format!("{}\nhelp: {}", message, hint);
+  }
+  // notes and help point at other places in the same file
+  let related_information = diagnostic.children.iter().map(|child| RelatedInformation {
+    location: Location { uri, range: to_range(&child.range, source) },
+    message: &child.message,
+  });
+  LspDiagnostic {
+    range: to_range(&diagnostic.range, source),
+    severity: severity(&diagnostic.severity),
+    code: diagnostic.code,
+    source: "spipe",
+    message,
+    related_information: related_information.collect(),
+  }
+}
+
+fn to_range(range: &Range, source: &Source) -> LspRange {
+  let position = |offset| {
+    let (line, character) = source.offset_to_utf16_position(offset);
+    Position { line, character }
+  };
+  LspRange { start: position(range.start), end: position(range.end) }
+}
+
+fn severity(severity: &Severity) -> u8 {
+  match severity {
+    Severity::Error => 1,
+    Severity::Warning => 2,
+    Severity::Info | Severity::Note => 3,
+    Severity::Help => 4,
+  }
+}
+
+// `file:///abs/path`, with what a uri cannot hold percent-encoded
+fn file_uri(path: &str) -> String {
+  let absolute = std::path::absolute(path).map_or(path.to_string(), |path| path.display().to_string());
+  let mut uri = String::from("file://");
+  if !absolute.starts_with('/') {
+    uri.push('/');
+  }
+  for byte in absolute.replace('\\', "/").bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => uri.push(byte as char),
+      _ => uri.push_str(&format!("%{:02X}", byte)),
+    }
+  }
+  uri
+}
@@ -1,21 +1,84 @@
+use serde::Serialize;
+
 use crate::utils::range::Range;
 use crate::utils::source::Source;
+use crate::utils::ColorMode;
 
-use super::report::report_error;
+use super::explain::explain;
+use super::github::report_github;
+use super::json::report_json;
+use super::lsp::report_lsp;
+use super::report::{report_error, report_explain_hint, report_hidden, report_summary, report_suppressed};
+use super::sarif::report_sarif;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+// errors shown before the rest are summed up in one line, follow-on errors rarely add anything
+pub const DEFAULT_ERROR_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
   Error,
   Warning,
+  Info, // says what hoshi did with the query, there is nothing to fix and it never fails a run
+  Note, // only attached to another diagnostic, never fails a run
+  Help,
+}
+
+impl Severity {
+  // higher is more severe
+  fn rank(&self) -> u8 {
+    match self {
+      Severity::Error => 4,
+      Severity::Warning => 3,
+      Severity::Info => 2,
+      Severity::Note => 1,
+      Severity::Help => 0,
+    }
+  }
+}
+
+// what a report leaves out, the exit code is decided from every diagnostic regardless
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ReportFilter {
+  pub min_severity: Severity, // less severe diagnostics are only counted in the summary
+  pub quiet: bool,            // nothing is printed at all
+}
+
+impl Default for ReportFilter {
+  fn default() -> Self {
+    Self { min_severity: Severity::Info, quiet: false }
+  }
+}
+
+impl ReportFilter {
+  pub fn shows(&self, diagnostic: &Diagnostic) -> bool {
+    diagnostic.severity.rank() >= self.min_severity.rank()
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReportFormat {
+  Human,  // colored snippets on stderr
+  Json,   // one JSON object per diagnostic on stderr
+  Sarif,  // one SARIF 2.1.0 log for code-scanning tools
+  Github, // github actions workflow commands, shown as annotations
+  Lsp,    // a language server `textDocument/publishDiagnostics` notification
 }
 
 pub struct DiagnosticsManager {
   pub diagnostics: Vec<Diagnostic>,
+  pub suppressed: usize, // silenced by `spipe:allow` comments
+  pub strict: bool,      // any diagnostic at all counts as an error
+  error_limit: usize,    // 0 shows every error
 }
 
 impl DiagnosticsManager {
   pub fn new() -> Self {
-    Self { diagnostics: vec![] }
+    Self { diagnostics: vec![], suppressed: 0, strict: false, error_limit: DEFAULT_ERROR_LIMIT }
+  }
+
+  pub fn with_error_limit(&mut self, error_limit: usize) {
+    self.error_limit = error_limit;
   }
 
   pub fn add(&mut self, diagnostic: Diagnostic) {
@@ -26,29 +89,198 @@ impl DiagnosticsManager {
     self.diagnostics.clone()
   }
 
+  // attaches a note or help to the diagnostic added last
+  pub fn attach(&mut self, child: Diagnostic) {
+    if let Some(last) = self.diagnostics.last_mut() {
+      last.children.push(child);
+    }
+  }
+
   pub fn contains_error(&self) -> bool {
-    self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    self.diagnostics.iter().any(|d| self.strict || d.severity == Severity::Error)
   }
 
-  pub fn report(&self, source: &Source) {
-    for diagnostic in self.diagnostics.iter() {
-      report_error(
-        &diagnostic.message,
-        &diagnostic.hint,
-        &diagnostic.range,
-        &source,
-        diagnostic.severity == Severity::Warning,
-      );
+  pub fn contains_warning(&self) -> bool {
+    self.diagnostics.iter().any(|d| d.severity == Severity::Warning)
+  }
+
+  // whether the run should exit non-zero, notes only count in strict mode
+  pub fn fails(&self, deny_warnings: bool) -> bool {
+    self.contains_error() || (deny_warnings && self.contains_warning())
+  }
+
+  pub fn report_as(&self, source: &Source, format: &ReportFormat, color: ColorMode, filter: &ReportFilter) {
+    if filter.quiet {
+      return;
+    }
+    match format {
+      ReportFormat::Human => self.report(source, color, filter),
+      ReportFormat::Json => eprint!("{}", self.report_json(source, filter)),
+      ReportFormat::Sarif => eprint!("{}", self.report_sarif(source, filter)),
+      ReportFormat::Github => eprint!("{}", self.report_github(source, filter)),
+      ReportFormat::Lsp => eprint!("{}", self.report_lsp(source, filter)),
+    }
+  }
+
+  // uncapped like the JSON report, the annotations are the whole report
+  pub fn report_github(&self, source: &Source, filter: &ReportFilter) -> String {
+    report_github(&self.shown(source, filter), source)
+  }
+
+  // uncapped as well, an empty list clears what the editor showed for the file
+  pub fn report_lsp(&self, source: &Source, filter: &ReportFilter) -> String {
+    report_lsp(&self.shown(source, filter), source)
+  }
+
+  // sorted and deduplicated like the JSON report, an empty run when nothing was found
+  pub fn report_sarif(&self, source: &Source, filter: &ReportFilter) -> String {
+    report_sarif(&self.shown(source, filter), source)
+  }
+
+  // sorted and deduplicated like the human report, but never capped
+  pub fn report_json(&self, source: &Source, filter: &ReportFilter) -> String {
+    report_json(&self.shown(source, filter), source)
+  }
+
+  pub fn report(&self, source: &Source, color: ColorMode, filter: &ReportFilter) {
+    if filter.quiet {
+      return;
+    }
+    let colored = color.is_colored();
+    let mut errors = 0;
+    let mut hidden = 0;
+    for diagnostic in self.shown(source, filter) {
+      if diagnostic.severity == Severity::Error {
+        errors += 1;
+        if self.error_limit > 0 && errors > self.error_limit {
+          hidden += 1;
+          continue;
+        }
+      }
+      report_error(&diagnostic, source, colored);
+    }
+    if hidden > 0 {
+      report_hidden(hidden, colored);
+    }
+    self.report_summary(color, filter);
+  }
+
+  // counts every diagnostic the filter shows, the ones past the error limit too,
+  // and only when there is one, the filtered out ones alone leave nothing to sum up
+  pub fn report_summary(&self, color: ColorMode, filter: &ReportFilter) {
+    let (shown, filtered): (Vec<_>, Vec<_>) = self.diagnostics.iter().partition(|d| filter.shows(d));
+    if filter.quiet || shown.is_empty() {
+      return;
+    }
+    let count = |severity: Severity| shown.iter().filter(|d| d.severity == severity).count();
+    let (errors, warnings, infos) = (count(Severity::Error), count(Severity::Warning), count(Severity::Info));
+    report_summary(errors, warnings, infos, filtered.len(), color.is_colored());
+    let first_error = shown.iter().find(|d| d.severity == Severity::Error);
+    if let Some(diagnostic) = first_error.or(shown.first()).filter(|d| explain(d.code).is_some()) {
+      report_explain_hint(diagnostic.code, color.is_colored());
     }
   }
+
+  // the errors and warnings the filter shows, as the summary counts them
+  pub fn counts(&self, filter: &ReportFilter) -> (usize, usize) {
+    let count =
+      |severity: Severity| self.diagnostics.iter().filter(|d| d.severity == severity && filter.shows(d)).count();
+    (count(Severity::Error), count(Severity::Warning))
+  }
+
+  pub fn report_suppressed(&self, color: ColorMode, filter: &ReportFilter) {
+    if !filter.quiet {
+      report_suppressed(self.suppressed, color.is_colored());
+    }
+  }
+
+  fn shown(&self, source: &Source, filter: &ReportFilter) -> Vec<Diagnostic> {
+    self.deduplicated(source).into_iter().filter(|diagnostic| filter.shows(diagnostic)).collect()
+  }
+
+  // in source order, without exact repeats or a parse error echoed at each following token
+  fn deduplicated(&self, source: &Source) -> Vec<Diagnostic> {
+    let mut sorted = self.diagnostics.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|diagnostic| diagnostic.range.start);
+    let mut kept: Vec<Diagnostic> = vec![];
+    for diagnostic in sorted {
+      if kept.iter().any(|seen| seen.code == diagnostic.code && seen.range == diagnostic.range) {
+        continue;
+      }
+      if kept.last().is_some_and(|last| last.continues_into(diagnostic, source.raw)) {
+        continue;
+      }
+      kept.push(diagnostic.clone());
+    }
+    kept
+  }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Diagnostic {
+  pub code: &'static str, // E0xxx for errors, W0xxx for warnings; never reuse a code
   pub message: String,
   pub hint: Option<String>,
   pub range: Range,
   pub severity: Severity,
+  pub children: Vec<Diagnostic>, // notes and help shown under the diagnostic, e.g. where a name first appeared
+  pub suggestion: Option<Box<Replacement>>, // a mechanical fix, applied by `check --fix`
+}
+
+// `new_text` in place of the source under `range`, an empty range inserts
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct Replacement {
+  pub range: Range,
+  pub new_text: String,
+}
+
+impl Replacement {
+  pub fn new(range: Range, new_text: String) -> Self {
+    Replacement { range, new_text }
+  }
+
+  pub fn describe(&self) -> String {
+    if self.new_text.is_empty() {
+      return "remove it".to_string();
+    }
+    if self.range.start == self.range.end {
+      return format!("insert `{}`", self.new_text.trim());
+    }
+    format!("replace with `{}`", self.new_text)
+  }
+}
+
+impl Diagnostic {
+  pub fn error(code: &'static str, message: String, range: Range) -> Self {
+    Diagnostic { code, message, hint: None, range, severity: Severity::Error, children: vec![], suggestion: None }
+  }
+
+  pub fn warning(code: &'static str, message: String, range: Range) -> Self {
+    Diagnostic { severity: Severity::Warning, ..Diagnostic::error(code, message, range) }
+  }
+
+  pub fn note(message: &str, range: Range) -> Self {
+    Diagnostic { severity: Severity::Note, ..Diagnostic::error("", message.to_string(), range) }
+  }
+
+  pub fn help(message: &str, range: Range) -> Self {
+    Diagnostic { severity: Severity::Help, ..Diagnostic::error("", message.to_string(), range) }
+  }
+
+  pub fn is_parse_error(&self) -> bool {
+    self.code.starts_with("E01")
+  }
+
+  // the same parse error again with only whitespace between the two
+  fn continues_into(&self, next: &Diagnostic, raw: &str) -> bool {
+    let between = raw.get(self.range.end..next.range.start).unwrap_or_default();
+    self.is_parse_error() && self.code == next.code && self.message == next.message && between.trim().is_empty()
+  }
+
+  pub fn with_suggestion(mut self, suggestion: Replacement) -> Self {
+    self.suggestion = Some(Box::new(suggestion));
+    self
+  }
 }
 
 pub enum TypeError {
@@ -57,76 +289,581 @@ pub enum TypeError {
   RedundantSubQuery { range: Range },
   UnexpectedToken { range: Range },
   MissingSelectClause { range: Range },
-  DuplicateColumn { range: Range },
+  DuplicateColumn { range: Range, first: Range },
   UnsupportedOperator { range: Range },
   MissingGroupBy { range: Range },
   AmbiguousColumn { range: Range },
   FunctionArgumentMismatch { range: Range },
+  UnusedAlias { range: Range },
+  DuplicateAlias { range: Range },
+  InvalidInterval { range: Range },
+  UnknownTable { table: String, range: Range },
+  UnknownColumn { table: String, column: String, range: Range },
+  DuplicateTable { range: Range, first: Range },
+  CommaOffset { range: Range },
+  UnsupportedQualify { dialect: String, range: Range },
+  ApproximatedSample { dialect: String, range: Range },
+  ApproximatedPercentile { dialect: String, range: Range },
+  GroupedPercentile { function: String, dialect: String, range: Range },
+  UnknownWindow { name: String, range: Range },
+  UnknownRule { name: String, range: Range },
+  PostgresOnlyCopy { dialect: String, range: Range },
+  EmptyProgram { range: Range },
+  InsertColumnCount { expected: usize, found: usize, range: Range },
+  DestructiveOperation { table: String, range: Range },
+  UnknownOrderKey { key: String, range: Range },
+  OrderPositionOutOfRange { position: String, width: usize, range: Range },
+  DropIndexWithoutTable { name: String, range: Range },
+  PostgresOnlyComment { dialect: String, range: Range },
+  AccessControl { statement: String, table: String, range: Range },
+  PostgresOnlyDistinctOn { dialect: String, range: Range },
+  CommitWithoutBegin { range: Range },
+  MalformedRecursiveCte { name: String, range: Range },
+  UnboundedRecursion { name: String, range: Range },
+  TooManyJoinedTables { count: usize, limit: usize, range: Range },
+  PotentialInjection { pattern: String, range: Range },
+  SqliteOnlyPragma { dialect: String, range: Range },
+  DialectRewrite { rewrite: String, dialect: String, range: Range },
+  RowValueSize { expected: usize, found: usize, range: Range },
+  TautologicalCondition { condition: String, range: Range },
+  OrInWhere { range: Range },
+  UnsupportedGrouping { grouping: String, dialect: String, range: Range },
 }
 
 impl From<TypeError> for Diagnostic {
   fn from(error: TypeError) -> Self {
     match error {
       TypeError::MissingIndexOnJoin { range } => Diagnostic {
+        code: "W0201",
         message: "missing index on join".to_string(),
         range,
         severity: Severity::Warning,
         hint: Some("consider adding an index to improve performance".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::PipeWithoutFrom { range } => Diagnostic {
+        code: "E0201",
         message: "pipe missing `FROM` clause".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("ensure `FROM` clause is present after aggregate".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::RedundantSubQuery { range } => Diagnostic {
+        code: "W0202",
         message: "redundant subquery".to_string(),
         range,
         severity: Severity::Warning,
         hint: Some("optimize by refactoring the subquery".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::UnexpectedToken { range } => Diagnostic {
+        code: "E0202",
         message: "unexpected token".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("check the SQL syntax".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::MissingSelectClause { range } => Diagnostic {
+        code: "E0203",
         message: "missing `SELECT` clause".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("ensure the query starts with `SELECT`".to_string()),
+        children: vec![],
+        suggestion: None,
       },
-      TypeError::DuplicateColumn { range } => Diagnostic {
+      TypeError::DuplicateColumn { range, first } => Diagnostic {
+        code: "W0203",
         message: "duplicate column".to_string(),
         range,
         severity: Severity::Warning,
         hint: Some("remove or rename the duplicate column".to_string()),
+        children: vec![Diagnostic::note("first selected here", first)],
+        suggestion: None,
       },
       TypeError::UnsupportedOperator { range } => Diagnostic {
+        code: "E0204",
         message: "unsupported operator".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("use supported operators like `=`, `<`, `>`".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::MissingGroupBy { range } => Diagnostic {
+        code: "E0205",
         message: "missing `GROUP BY` clause".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("add `GROUP BY` to group results correctly".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::AmbiguousColumn { range } => Diagnostic {
+        code: "E0206",
         message: "ambiguous column reference".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("qualify column names with table names".to_string()),
+        children: vec![],
+        suggestion: None,
       },
       TypeError::FunctionArgumentMismatch { range } => Diagnostic {
+        code: "E0207",
         message: "function argument mismatch".to_string(),
         range,
         severity: Severity::Error,
         hint: Some("check the number and types of arguments".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnusedAlias { range } => Diagnostic {
+        code: "W0204",
+        message: "unused alias".to_string(),
+        range,
+        severity: Severity::Warning,
+        hint: Some("reference the alias downstream or remove it".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::DuplicateAlias { range } => Diagnostic {
+        code: "E0208",
+        message: "duplicate alias".to_string(),
+        range,
+        severity: Severity::Error,
+        hint: Some("the alias is already bound in this query, rename one of them".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::InvalidInterval { range } => Diagnostic {
+        code: "E0209",
+        message: "interval value is not a number".to_string(),
+        range,
+        severity: Severity::Error,
+        hint: Some("write intervals like `INTERVAL '7 days'`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnknownTable { table, range } => Diagnostic {
+        code: "E0210",
+        message: format!("unknown table `{}`", table),
+        range,
+        severity: Severity::Error,
+        hint: Some("check the table name against the schema".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnknownColumn { table, column, range } => Diagnostic {
+        code: "E0211",
+        message: format!("column `{}` does not exist in `{}`", column, table),
+        range,
+        severity: Severity::Error,
+        hint: Some("check the column name against the schema".to_string()),
+        children: vec![],
+        suggestion: None,
       },
+      TypeError::DuplicateTable { range, first } => Diagnostic {
+        code: "W0205",
+        message: "duplicate table".to_string(),
+        range,
+        severity: Severity::Warning,
+        hint: Some("read each table once per query".to_string()),
+        children: vec![Diagnostic::note("first read here", first)],
+        suggestion: None,
+      },
+      TypeError::CommaOffset { range } => Diagnostic {
+        code: "W0206",
+        message: "`LIMIT count, offset` means `LIMIT offset, count` in mysql".to_string(),
+        range,
+        severity: Severity::Warning,
+        hint: Some("write `LIMIT count OFFSET offset`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnsupportedQualify { dialect, range } => Diagnostic {
+        code: "W0207",
+        message: format!("{} has no QUALIFY", dialect),
+        range,
+        severity: Severity::Warning,
+        hint: Some("drop `keep_qualify` from spipe.toml to rewrite it into a subquery".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::ApproximatedSample { dialect, range } => Diagnostic {
+        code: "W0208",
+        message: format!("{} has no TABLESAMPLE, rows are sampled with a random filter", dialect),
+        range,
+        severity: Severity::Warning,
+        hint: Some("the filter still reads the whole table and the sample size varies between runs".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnknownWindow { name, range } => Diagnostic {
+        code: "E0212",
+        message: format!("window `{}` is not defined", name),
+        range,
+        severity: Severity::Error,
+        hint: Some(format!("name it in a `|> WINDOW {} AS (...)` stage", name)),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnknownRule { name, range } => Diagnostic {
+        code: "W0209",
+        message: format!("unknown rule `{}`, nothing is suppressed for it", name),
+        range,
+        severity: Severity::Warning,
+        hint: Some("use a rule name such as `unused-alias` or `duplicate-column`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::PostgresOnlyCopy { dialect, range } => Diagnostic {
+        code: "W0210",
+        message: format!("COPY is postgres only, {} will not run it", dialect),
+        range,
+        severity: Severity::Warning,
+        hint: Some("set `dialect = \"postgres\"` or use the database's own bulk loader".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::MalformedRecursiveCte { name, range } => Diagnostic {
+        code: "E0217",
+        message: format!("recursive cte `{}` needs a base query and a recursive one joined by UNION ALL", name),
+        range,
+        severity: Severity::Error,
+        hint: Some(format!("write it as (base query UNION ALL query reading `{}`)", name)),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::ApproximatedPercentile { dialect, range } => Diagnostic {
+        code: "W0220",
+        message: format!("{} has no PERCENTILE_CONT, the nearest value is taken instead of interpolating", dialect),
+        range,
+        severity: Severity::Warning,
+        hint: Some("PERCENTILE_DISC is exact there, it always returns one of the values".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::GroupedPercentile { function, dialect, range } => Diagnostic {
+        code: "E0219",
+        message: format!("{} has no {}, it cannot be taken per group", dialect, function),
+        range,
+        severity: Severity::Error,
+        hint: Some("keep one group with WHERE instead of GROUP BY, or target postgres".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnsupportedGrouping { grouping, dialect, range } => Diagnostic {
+        code: "E0221",
+        message: format!("{} has no GROUP BY {}", dialect, grouping),
+        range,
+        severity: Severity::Error,
+        hint: Some("run one query per grouping and stack them with UNION ALL, or target postgres".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::TautologicalCondition { condition, range } => Diagnostic {
+        code: "W0221",
+        message: format!("`{}` compares a value with itself", condition),
+        range,
+        severity: Severity::Warning,
+        hint: Some(
+          "it keeps every row but those with NULL, one side was likely meant to be something else".to_string(),
+        ),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::OrInWhere { range } => Diagnostic {
+        code: "W0222",
+        message: "OR in a WHERE condition".to_string(),
+        range,
+        severity: Severity::Warning,
+        hint: Some("an OR can keep the database from using an index, `IN` or a UNION of each side may not".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::RowValueSize { expected, found, range } => Diagnostic {
+        code: "E0218",
+        message: format!("IN compares {} values but the row has {}", expected, found),
+        range,
+        severity: Severity::Error,
+        hint: Some("each row after IN needs one value per value on its left, e.g. `(a, b) IN ((1, 2))`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnboundedRecursion { name, range } => Diagnostic {
+        code: "W0216",
+        message: format!("the recursive query of `{}` has no WHERE, nothing stops it on a cycle", name),
+        range,
+        severity: Severity::Warning,
+        hint: Some("add a WHERE that limits the depth, e.g. `|> WHERE depth < 10`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::TooManyJoinedTables { count, limit, range } => Diagnostic {
+        code: "W0217",
+        message: format!("this join brings the query to {} distinct tables, more than {}", count, limit),
+        range,
+        severity: Severity::Warning,
+        hint: Some("split the query with a WITH, or raise `max_joined_tables` in spipe.toml".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::PotentialInjection { pattern, range } => Diagnostic {
+        code: "W0218",
+        message: format!("this string has {} in it, which looks like sql injection", pattern),
+        range,
+        severity: Severity::Warning,
+        hint: Some("check where the value comes from, and pass it as a bound parameter".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::SqliteOnlyPragma { dialect, range } => Diagnostic {
+        code: "W0219",
+        message: format!("PRAGMA is sqlite only, it is left out of the {} sql", dialect),
+        range,
+        severity: Severity::Warning,
+        hint: Some("set `dialect = \"sqlite\"` in spipe.toml, or keep the PRAGMA in a sqlite-only script".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::CommitWithoutBegin { range } => Diagnostic {
+        code: "W0215",
+        message: "COMMIT without a BEGIN before it in this file".to_string(),
+        range,
+        severity: Severity::Warning,
+        hint: Some("start the transaction with BEGIN, or drop the COMMIT".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::PostgresOnlyComment { dialect, range } => Diagnostic {
+        code: "W0213",
+        message: format!("COMMENT ON is postgres only, it is left out of the {} sql", dialect),
+        range,
+        severity: Severity::Warning,
+        hint: Some("set `dialect = \"postgres\"` to keep the comment".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::EmptyProgram { range } => Diagnostic {
+        code: "W0211",
+        message: "empty program, there is nothing to compile".to_string(),
+        range,
+        severity: Severity::Warning,
+        hint: Some("start a query with `FROM <table>`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::InsertColumnCount { expected, found, range } => Diagnostic {
+        code: "E0213",
+        message: format!("INSERT names {} columns but the row has {} values", expected, found),
+        range,
+        severity: Severity::Error,
+        hint: Some("list one column for each value the rows fill".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::UnknownOrderKey { key, range } => Diagnostic {
+        code: "E0214",
+        message: format!("ORDER BY `{}` is not a column or alias in scope", key),
+        range,
+        severity: Severity::Error,
+        hint: Some(
+          "order by a table of the query, a column alias, or after an AGGREGATE its GROUP BY columns".to_string(),
+        ),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::OrderPositionOutOfRange { position, width, range } => Diagnostic {
+        code: "E0220",
+        message: format!("ORDER BY position {} is not between 1 and the {} selected column(s)", position, width),
+        range,
+        severity: Severity::Error,
+        hint: Some("positions count the selected columns from 1".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::PostgresOnlyDistinctOn { dialect, range } => Diagnostic {
+        code: "E0216",
+        message: format!("DISTINCT ON is postgres only, {} will not run it", dialect),
+        range,
+        severity: Severity::Error,
+        hint: Some("set `dialect = \"postgres\"`, or pick the row with ROW_NUMBER() and QUALIFY".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::DropIndexWithoutTable { name, range } => Diagnostic {
+        code: "E0215",
+        message: format!("mysql drops the index `{}` through its table", name),
+        range,
+        severity: Severity::Error,
+        hint: Some(format!("name the table, `DROP INDEX {} ON <table>`", name)),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::DialectRewrite { rewrite, dialect, range } => Diagnostic {
+        code: "I0201",
+        message: format!("{} for {}", rewrite, dialect),
+        range,
+        severity: Severity::Info,
+        hint: None,
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::AccessControl { statement, table, range } => Diagnostic {
+        code: "W0214",
+        message: format!(
+          "{} changes who can reach `{}`, only its owner or an administrator may run it",
+          statement, table
+        ),
+        range,
+        severity: Severity::Warning,
+        hint: Some("run it as a role that may grant, then silence it with `spipe:allow(access-control)`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+      TypeError::DestructiveOperation { table, range } => Diagnostic {
+        code: "W0212",
+        message: format!("this removes every row of `{}`", table),
+        range,
+        severity: Severity::Warning,
+        hint: Some("make sure this is intended, then silence it with `spipe:allow(destructive-operation)`".to_string()),
+        children: vec![],
+        suggestion: None,
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // one of each variant, the match below stops compiling when a variant is left out
+  fn every_type_error() -> Vec<TypeError> {
+    let range = Range::default;
+    let text = String::new;
+    let errors = vec![
+      TypeError::MissingIndexOnJoin { range: range() },
+      TypeError::PipeWithoutFrom { range: range() },
+      TypeError::RedundantSubQuery { range: range() },
+      TypeError::UnexpectedToken { range: range() },
+      TypeError::MissingSelectClause { range: range() },
+      TypeError::DuplicateColumn { range: range(), first: range() },
+      TypeError::UnsupportedOperator { range: range() },
+      TypeError::MissingGroupBy { range: range() },
+      TypeError::AmbiguousColumn { range: range() },
+      TypeError::FunctionArgumentMismatch { range: range() },
+      TypeError::UnusedAlias { range: range() },
+      TypeError::DuplicateAlias { range: range() },
+      TypeError::InvalidInterval { range: range() },
+      TypeError::UnknownTable { table: text(), range: range() },
+      TypeError::UnknownColumn { table: text(), column: text(), range: range() },
+      TypeError::DuplicateTable { range: range(), first: range() },
+      TypeError::CommaOffset { range: range() },
+      TypeError::UnsupportedQualify { dialect: text(), range: range() },
+      TypeError::ApproximatedSample { dialect: text(), range: range() },
+      TypeError::ApproximatedPercentile { dialect: text(), range: range() },
+      TypeError::GroupedPercentile { function: text(), dialect: text(), range: range() },
+      TypeError::UnknownWindow { name: text(), range: range() },
+      TypeError::UnknownRule { name: text(), range: range() },
+      TypeError::PostgresOnlyCopy { dialect: text(), range: range() },
+      TypeError::EmptyProgram { range: range() },
+      TypeError::InsertColumnCount { expected: 1, found: 2, range: range() },
+      TypeError::DestructiveOperation { table: text(), range: range() },
+      TypeError::UnknownOrderKey { key: text(), range: range() },
+      TypeError::OrderPositionOutOfRange { position: text(), width: 1, range: range() },
+      TypeError::DropIndexWithoutTable { name: text(), range: range() },
+      TypeError::PostgresOnlyComment { dialect: text(), range: range() },
+      TypeError::AccessControl { statement: text(), table: text(), range: range() },
+      TypeError::PostgresOnlyDistinctOn { dialect: text(), range: range() },
+      TypeError::CommitWithoutBegin { range: range() },
+      TypeError::MalformedRecursiveCte { name: text(), range: range() },
+      TypeError::UnboundedRecursion { name: text(), range: range() },
+      TypeError::TooManyJoinedTables { count: 2, limit: 1, range: range() },
+      TypeError::PotentialInjection { pattern: text(), range: range() },
+      TypeError::SqliteOnlyPragma { dialect: text(), range: range() },
+      TypeError::DialectRewrite { rewrite: text(), dialect: text(), range: range() },
+      TypeError::RowValueSize { expected: 1, found: 2, range: range() },
+      TypeError::TautologicalCondition { condition: text(), range: range() },
+      TypeError::OrInWhere { range: range() },
+      TypeError::UnsupportedGrouping { grouping: text(), dialect: text(), range: range() },
+    ];
+    for error in &errors {
+      match error {
+        TypeError::MissingIndexOnJoin { .. }
+        | TypeError::PipeWithoutFrom { .. }
+        | TypeError::RedundantSubQuery { .. }
+        | TypeError::UnexpectedToken { .. }
+        | TypeError::MissingSelectClause { .. }
+        | TypeError::DuplicateColumn { .. }
+        | TypeError::UnsupportedOperator { .. }
+        | TypeError::MissingGroupBy { .. }
+        | TypeError::AmbiguousColumn { .. }
+        | TypeError::FunctionArgumentMismatch { .. }
+        | TypeError::UnusedAlias { .. }
+        | TypeError::DuplicateAlias { .. }
+        | TypeError::InvalidInterval { .. }
+        | TypeError::UnknownTable { .. }
+        | TypeError::UnknownColumn { .. }
+        | TypeError::DuplicateTable { .. }
+        | TypeError::CommaOffset { .. }
+        | TypeError::UnsupportedQualify { .. }
+        | TypeError::ApproximatedSample { .. }
+        | TypeError::ApproximatedPercentile { .. }
+        | TypeError::GroupedPercentile { .. }
+        | TypeError::UnknownWindow { .. }
+        | TypeError::UnknownRule { .. }
+        | TypeError::PostgresOnlyCopy { .. }
+        | TypeError::EmptyProgram { .. }
+        | TypeError::InsertColumnCount { .. }
+        | TypeError::DestructiveOperation { .. }
+        | TypeError::UnknownOrderKey { .. }
+        | TypeError::OrderPositionOutOfRange { .. }
+        | TypeError::DropIndexWithoutTable { .. }
+        | TypeError::PostgresOnlyComment { .. }
+        | TypeError::AccessControl { .. }
+        | TypeError::PostgresOnlyDistinctOn { .. }
+        | TypeError::CommitWithoutBegin { .. }
+        | TypeError::MalformedRecursiveCte { .. }
+        | TypeError::UnboundedRecursion { .. }
+        | TypeError::TooManyJoinedTables { .. }
+        | TypeError::PotentialInjection { .. }
+        | TypeError::SqliteOnlyPragma { .. }
+        | TypeError::DialectRewrite { .. }
+        | TypeError::RowValueSize { .. }
+        | TypeError::TautologicalCondition { .. }
+        | TypeError::OrInWhere { .. }
+        | TypeError::UnsupportedGrouping { .. } => {}
+      }
+    }
+    errors
+  }
+
+  #[test]
+  fn each_variant_has_its_own_code() {
+    let codes = every_type_error().into_iter().map(|error| Diagnostic::from(error).code).collect::<Vec<_>>();
+    for (index, code) in codes.iter().enumerate() {
+      assert!(!codes[index + 1..].contains(code), "{} is given to two variants", code);
+      assert!(crate::diagnostics::codes::describe(code).is_some(), "{} is not in the codes table", code);
+    }
+  }
+
+  #[test]
+  fn codes_follow_the_severity() {
+    for diagnostic in every_type_error().into_iter().map(Diagnostic::from) {
+      let kind = match diagnostic.severity {
+        Severity::Error => "E",
+        Severity::Warning => "W",
+        Severity::Info => "I",
+        Severity::Note | Severity::Help => panic!("{} is only ever attached to another diagnostic", diagnostic.code),
+      };
+      assert!(diagnostic.code.starts_with(kind), "{} is a {:?}", diagnostic.code, diagnostic.severity);
     }
   }
 }
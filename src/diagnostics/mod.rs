@@ -1,4 +1,12 @@
 #![allow(dead_code)]
 
+pub mod codes;
+pub mod explain;
+pub mod fix;
+pub mod github;
+pub mod json;
+pub mod lsp;
 pub mod maneger;
 pub mod report;
+pub mod sarif;
+pub mod snippet;
@@ -1,44 +1,148 @@
 use crate::utils::{
-  highlight_text_with_cyan, highlight_text_with_green, highlight_text_with_red, highlight_text_with_white,
-  highlight_text_with_yellow,
+  highlight_text_with_blue, highlight_text_with_cyan, highlight_text_with_green, highlight_text_with_red,
+  highlight_text_with_white, highlight_text_with_yellow,
 };
 
 use crate::utils::range::Range;
 use crate::utils::source::Source;
 
-use code_highlighter::{highlight_error, highlight_warning};
+use super::maneger::{Diagnostic, Severity};
+use super::snippet::render_snippet;
 
-pub fn report_error(message: &str, hint: &Option<String>, range: &Range, source: &Source, warning: bool) {
-  println!("");
-  if !warning {
-    println!("{} {}", highlight_text_with_red("ERROR >>>"), highlight_text_with_white(message));
+pub fn report_error(diagnostic: &Diagnostic, source: &Source, colored: bool) {
+  let Diagnostic { code, message, hint, range, children, suggestion, .. } = diagnostic;
+  let range = clamp_range(range, source);
+  eprintln!();
+  let header = match diagnostic.severity {
+    Severity::Error => highlight_text_with_red(&format!("ERROR[{}] >>>", code), colored),
+    Severity::Warning => highlight_text_with_yellow(&format!("WARNING[{}] >>>", code), colored),
+    Severity::Info => highlight_text_with_blue(&format!("INFO[{}] >>>", code), colored),
+    Severity::Note => highlight_text_with_cyan("NOTE >>>", colored),
+    Severity::Help => highlight_text_with_green("HELP >>>", colored),
+  };
+  eprintln!("{} {}", header, highlight_text_with_white(message, colored));
+  let file_highlight = highlight_text_with_cyan(&source.location(range.start), colored);
+  eprintln!("{}", file_highlight);
+  eprintln!();
+  if is_end_of_file(&range, source) {
+    eprintln!("{}", highlight_text_with_white("at end of file", colored));
   } else {
-    let warning = highlight_text_with_yellow("WARNING >>>");
-    let message = format!("{} {}", warning, highlight_text_with_white(message));
-    println!("{}", message);
+    let paint = match diagnostic.severity {
+      Severity::Error => highlight_text_with_red,
+      Severity::Info => highlight_text_with_blue,
+      _ => highlight_text_with_yellow,
+    };
+    eprintln!("{}", render_snippet(&range, source, paint, colored));
   }
-  let file_highlight = highlight_text_with_cyan(source.path);
-  println!("{}", file_highlight);
-  println!("");
-  if warning {
-    let code_highliter = format!("{}", highlight_warning(range.start, range.end, source.raw));
-    println!("{}", code_highliter);
-  } else {
-    let code_highliter = format!("{}", highlight_error(range.start, range.end, source.raw));
-    println!("{}", code_highliter);
+
+  for child in children {
+    report_child(child, source, colored);
   }
 
   if let Some(hint) = hint {
-    println!();
+    eprintln!();
+
+    eprintln!("{} {}", highlight_text_with_green("HELP:", colored), highlight_text_with_white(hint, colored));
+  }
+
+  if let Some(suggestion) = suggestion {
+    let fix = suggestion.describe();
+    eprintln!("{} {}", highlight_text_with_green("HELP:", colored), highlight_text_with_white(&fix, colored));
+  }
+  eprintln!();
+}
 
-    println!("{} {}", highlight_text_with_green("HELP:"), highlight_text_with_white(hint));
+// notes in cyan and help in green, each under its own snippet
+fn report_child(child: &Diagnostic, source: &Source, colored: bool) {
+  let label = match child.severity {
+    Severity::Help => highlight_text_with_green("HELP:", colored),
+    _ => highlight_text_with_cyan("NOTE:", colored),
+  };
+  eprintln!();
+  eprintln!("{} {}", label, highlight_text_with_white(&child.message, colored));
+  let range = clamp_range(&child.range, source);
+  eprintln!("{}", highlight_text_with_cyan(&source.location(range.start), colored));
+  eprintln!();
+  if is_end_of_file(&range, source) {
+    eprintln!("{}", highlight_text_with_white("at end of file", colored));
+  } else {
+    eprintln!("{}", render_snippet(&range, source, highlight_text_with_yellow, colored));
   }
-  println!();
 }
 
-pub fn report_and_exit(message: &str, range: &Range, source: &Source) -> ! {
-  report_error(message, &None, range, source, false);
-  std::process::exit(1);
+// a range past the source, inverted or inside a character would break the snippet, so it is fixed up first
+fn clamp_range(range: &Range, source: &Source) -> Range {
+  let clamp = |offset: usize| {
+    let mut offset = offset.min(source.raw.len());
+    while !source.raw.is_char_boundary(offset) {
+      offset -= 1;
+    }
+    offset
+  };
+  let (start, end) = (clamp(range.start), clamp(range.end));
+  Range::new(start.min(end), start.max(end))
+}
+
+// an empty range at the very end, where there is nothing left to underline
+fn is_end_of_file(range: &Range, source: &Source) -> bool {
+  range.start == range.end && range.end == source.raw.len()
+}
+
+pub fn report_hidden(count: usize, colored: bool) {
+  let errors = if count == 1 { "error" } else { "errors" };
+  eprintln!("{}", highlight_text_with_red(&format!("and {} more {}", count, errors), colored));
+  eprintln!();
+}
+
+// infos are only counted when there are some
+pub fn report_summary(errors: usize, warnings: usize, infos: usize, filtered: usize, colored: bool) {
+  let errors = plural(errors, "error");
+  let warnings = plural(warnings, "warning");
+  let mut summary = match infos {
+    0 => format!("{}, {} emitted", errors, warnings),
+    _ => format!("{}, {}, {} emitted", errors, warnings, plural(infos, "info")),
+  };
+  if filtered > 0 {
+    summary = format!("{}, {} hidden by --min-severity", summary, plural(filtered, "diagnostic"));
+  }
+  eprintln!("{}", highlight_text_with_white(&summary, colored));
+}
+
+// `a.sp: ok`, printed after each of several files
+pub fn report_file_status(path: &str, failed: bool, colored: bool) {
+  let status = format!("{}: {}", path, if failed { "failed" } else { "ok" });
+  eprintln!("{}", highlight_text_with_white(&status, colored));
+}
+
+// the totals of a check over several files, after each file's own summary
+pub fn report_files_summary(verb: &str, files: usize, failed: usize, errors: usize, warnings: usize, colored: bool) {
+  let summary = format!(
+    "{} {}, {} failed: {}, {}",
+    verb,
+    plural(files, "file"),
+    failed,
+    plural(errors, "error"),
+    plural(warnings, "warning")
+  );
+  eprintln!("{}", highlight_text_with_white(&summary, colored));
+}
+
+// names one of the reported codes, so the command is ready to copy
+pub fn report_explain_hint(code: &str, colored: bool) {
+  eprintln!("{}", highlight_text_with_white(&format!("run `hoshi explain {}` for more about a code", code), colored));
+}
+
+pub fn report_suppressed(count: usize, colored: bool) {
+  let diagnostics = plural(count, "diagnostic");
+  eprintln!("{}", highlight_text_with_white(&format!("{} suppressed by spipe:allow comments", diagnostics), colored));
+}
+
+fn plural(count: usize, noun: &str) -> String {
+  if count == 1 {
+    format!("1 {}", noun)
+  } else {
+    format!("{} {}s", count, noun)
+  }
 }
 
 fn is_warning(message: &str) -> bool {
@@ -0,0 +1,198 @@
+use serde::Serialize;
+
+use super::codes::CODES;
+use super::maneger::{Diagnostic, Severity};
+use crate::utils::range::Range;
+use crate::utils::source::Source;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Serialize)]
+struct Log<'a> {
+  #[serde(rename = "$schema")]
+  schema: &'static str,
+  version: &'static str,
+  runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+struct Run<'a> {
+  tool: Tool,
+  results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+  driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+  name: &'static str,
+  version: &'static str,
+  rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+  id: &'static str,
+  short_description: Message<'static>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+  rule_id: &'a str,
+  rule_index: usize,
+  level: &'static str,
+  message: Message<'a>,
+  locations: Vec<Location<'a>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  related_locations: Vec<Location<'a>>,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+  text: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location<'a> {
+  physical_location: PhysicalLocation<'a>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  message: Option<Message<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation<'a> {
+  artifact_location: ArtifactLocation<'a>,
+  region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation<'a> {
+  uri: &'a str,
+}
+
+// 1-based, and the end column is the one just past the range
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+  start_line: usize,
+  start_column: usize,
+  end_line: usize,
+  end_column: usize,
+}
+
+// a SARIF 2.1.0 log with one run, every known code is a rule so rule indexes never shift
+pub fn report_sarif(diagnostics: &[Diagnostic], source: &Source) -> String {
+  let rules = CODES.iter().map(|(id, text)| Rule { id, short_description: Message { text } }).collect();
+  let driver = Driver { name: env!("CARGO_PKG_NAME"), version: env!("CARGO_PKG_VERSION"), rules };
+  let results = diagnostics.iter().filter_map(|diagnostic| to_result(diagnostic, source)).collect();
+  let log = Log { schema: SARIF_SCHEMA, version: SARIF_VERSION, runs: vec![Run { tool: Tool { driver }, results }] };
+  serde_json::to_string_pretty(&log).expect("sarif logs always serialize") + "\n"
+}
+
+// diagnostics without a known code have no rule to point at and are left out
+fn to_result<'a>(diagnostic: &'a Diagnostic, source: &'a Source) -> Option<SarifResult<'a>> {
+  let rule_index = CODES.iter().position(|(code, _)| *code == diagnostic.code)?;
+  let related = diagnostic.children.iter().map(|child| to_location(&child.range, Some(&child.message), source));
+  Some(SarifResult {
+    rule_id: diagnostic.code,
+    rule_index,
+    level: level(&diagnostic.severity),
+    message: Message { text: &diagnostic.message },
+    locations: vec![to_location(&diagnostic.range, None, source)],
+    related_locations: related.collect(),
+  })
+}
+
+fn to_location<'a>(range: &Range, message: Option<&'a str>, source: &'a Source) -> Location<'a> {
+  let (start_line, start_column) = source.offset_to_line_col(range.start);
+  let (end_line, end_column) = source.offset_to_line_col(range.end);
+  let region = Region { start_line, start_column, end_line, end_column };
+  let artifact_location = ArtifactLocation { uri: source.path };
+  Location {
+    physical_location: PhysicalLocation { artifact_location, region },
+    message: message.map(|text| Message { text }),
+  }
+}
+
+fn level(severity: &Severity) -> &'static str {
+  match severity {
+    Severity::Error => "error",
+    Severity::Warning => "warning",
+    Severity::Info | Severity::Note | Severity::Help => "note",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use serde_json::Value;
+
+  use crate::compiler::compile;
+  use crate::config::Config;
+  use crate::diagnostics::maneger::ReportFilter;
+  use crate::utils::source::Source;
+
+  fn sarif_of_fixture() -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground/sarif.sql");
+    let raw = std::fs::read_to_string(path).unwrap();
+    let source = Source::new("sarif.sql", &raw);
+    let (_, diagnostics) = compile(&source, None, &[], &Config::default());
+    serde_json::from_str(&diagnostics.report_sarif(&source, &ReportFilter::default())).unwrap()
+  }
+
+  // the properties the SARIF 2.1.0 schema requires of a log, its runs and their results
+  #[test]
+  fn follows_the_sarif_schema() {
+    let log = sarif_of_fixture();
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["$schema"], super::SARIF_SCHEMA);
+    let runs = log["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    let driver = &runs[0]["tool"]["driver"];
+    assert_eq!(driver["name"], "hoshi");
+    let rules = driver["rules"].as_array().unwrap();
+    assert!(rules.iter().all(|rule| rule["id"].is_string() && rule["shortDescription"]["text"].is_string()));
+    let results = runs[0]["results"].as_array().unwrap();
+    let levels = ["none", "note", "warning", "error"];
+    for result in results {
+      let index = result["ruleIndex"].as_u64().unwrap() as usize;
+      assert_eq!(rules[index]["id"], result["ruleId"]);
+      assert!(levels.contains(&result["level"].as_str().unwrap()));
+      assert!(result["message"]["text"].is_string());
+      let locations = result["locations"].as_array().unwrap().iter();
+      for location in locations.chain(result["relatedLocations"].as_array().into_iter().flatten()) {
+        let physical = &location["physicalLocation"];
+        assert_eq!(physical["artifactLocation"]["uri"], "sarif.sql");
+        let region = &physical["region"];
+        let position = |key: &str| region[key].as_u64().unwrap();
+        assert!(position("startLine") >= 1 && position("startColumn") >= 1);
+        assert!((position("endLine"), position("endColumn")) > (position("startLine"), position("startColumn")));
+      }
+    }
+  }
+
+  #[test]
+  fn reports_each_diagnostic_of_the_fixture() {
+    let log = sarif_of_fixture();
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    let codes = results.iter().map(|result| result["ruleId"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(codes, ["W0201", "E0208", "W0206"]);
+    let related = &results[1]["relatedLocations"][0];
+    assert_eq!(related["message"]["text"], "`o` was first bound here");
+    let region = &results[2]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(
+      (&region["startLine"], &region["startColumn"], &region["endColumn"]),
+      (&5.into(), &4.into(), &16.into())
+    );
+  }
+}
@@ -0,0 +1,128 @@
+use crate::utils::highlight_text_with_cyan;
+use crate::utils::range::Range;
+use crate::utils::source::Source;
+
+// the lines `range` covers, each with `^` under the part of it in the range. ranges are byte offsets,
+// so the underline is measured the way a terminal shows the line: a tab reaches the next tab stop,
+// wide characters such as emoji take two columns and combining accents none
+pub fn render_snippet(range: &Range, source: &Source, paint: fn(&str, bool) -> String, colored: bool) -> String {
+  let first = source.line_index(range.start);
+  // an end right after a line break belongs to the line before it
+  let last = if range.end > range.start { source.line_index(range.end - 1) } else { first };
+  let gutter = (last + 1).to_string().len();
+  let mut snippet = String::new();
+  for line in first..=last {
+    let (line_start, text) = source.line(line);
+    // the lines after the first are underlined from their text, not their indentation
+    let indentation = if line == first { 0 } else { text.len() - text.trim_start().len() };
+    let from = range.start.saturating_sub(line_start).clamp(indentation, text.len());
+    let to = range.end.saturating_sub(line_start).min(text.len());
+    let (padding, underline) = match (text.get(..from), text.get(..to)) {
+      (Some(before), Some(through)) => {
+        let padding = display_width(before, source.tab_width);
+        (padding, display_width(through, source.tab_width).saturating_sub(padding).max(1))
+      }
+      // a range inside a character, which `clamp_range` already rules out
+      _ => (0, 1),
+    };
+    let number = highlight_text_with_cyan(&format!("{:>gutter$} |", line + 1), colored);
+    let margin = highlight_text_with_cyan(&format!("{:>gutter$} |", ""), colored);
+    let carets = paint(&"^".repeat(underline), colored);
+    snippet.push_str(&format!("{} {}\n", number, expand_tabs(text, source.tab_width)));
+    snippet.push_str(&format!("{} {}{}\n", margin, " ".repeat(padding), carets));
+  }
+  snippet.pop();
+  snippet
+}
+
+fn display_width(text: &str, tab_width: usize) -> usize {
+  text.chars().fold(0, |column, c| advance(column, c, tab_width))
+}
+
+fn expand_tabs(text: &str, tab_width: usize) -> String {
+  let mut expanded = String::with_capacity(text.len());
+  let mut column = 0;
+  for c in text.chars() {
+    let next = advance(column, c, tab_width);
+    if c == '\t' {
+      expanded.push_str(&" ".repeat(next - column));
+    } else {
+      expanded.push(c);
+    }
+    column = next;
+  }
+  expanded
+}
+
+// the column after `c` when it is written at `column`
+fn advance(column: usize, c: char, tab_width: usize) -> usize {
+  match c {
+    '\t' => (column / tab_width + 1) * tab_width,
+    _ => column + char_width(c),
+  }
+}
+
+// terminal columns of a character, close to unicode's east asian width without a table crate
+fn char_width(c: char) -> usize {
+  match c as u32 {
+    // combining marks, zero width joiners and variation selectors draw over the character before
+    0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F => 0,
+    // hangul, cjk, fullwidth forms and emoji
+    0x1100..=0x115F
+    | 0x2E80..=0x303E
+    | 0x3041..=0x33FF
+    | 0x3400..=0x4DBF
+    | 0x4E00..=0x9FFF
+    | 0xA000..=0xA4CF
+    | 0xAC00..=0xD7A3
+    | 0xF900..=0xFAFF
+    | 0xFE30..=0xFE4F
+    | 0xFF00..=0xFF60
+    | 0xFFE0..=0xFFE6
+    | 0x1F300..=0x1F64F
+    | 0x1F680..=0x1F6FF
+    | 0x1F900..=0x1F9FF
+    | 0x20000..=0x3FFFD => 2,
+    _ => 1,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // the uncolored snippet of `raw` with `marked` underlined, where it first appears after `after`
+  fn snippet(raw: &str, after: &str, marked: &str, tab_width: usize) -> String {
+    let from = raw.find(after).unwrap() + after.len();
+    let start = from + raw[from..].find(marked).unwrap();
+    let source = Source::new("test.sql", raw).with_tab_width(tab_width);
+    render_snippet(&Range::new(start, start + marked.len()), &source, |text, _| text.to_string(), false)
+  }
+
+  fn underline(line: &str, column: usize, width: usize) -> String {
+    format!("1 | {}\n  | {}{}", line, " ".repeat(column), "^".repeat(width))
+  }
+
+  #[test]
+  fn caret_follows_accents_and_emoji() {
+    let raw = "WHERE name = 'café 🎉' AND total > > 10";
+    // `é` is two bytes and one column, `🎉` four bytes and two columns, so the second `>` is at column 35
+    assert_eq!(snippet(raw, "total >", ">", 4), underline(raw, 35, 1));
+  }
+
+  #[test]
+  fn caret_follows_wide_characters_and_tabs() {
+    let raw = "|>\tSELECT\tname, '名前' AS label, @;";
+    let expanded = "|>  SELECT  name, '名前' AS label, @;";
+    assert_eq!(snippet(raw, "label, ", "@", 4), underline(expanded, 35, 1));
+    let expanded = "|>      SELECT  name, '名前' AS label, @;";
+    assert_eq!(snippet(raw, "label, ", "@", 8), underline(expanded, 39, 1));
+  }
+
+  #[test]
+  fn underline_spans_the_characters_of_the_token() {
+    let raw = "FROM cafés |> WHERE name = 'ñandú'";
+    assert_eq!(snippet(raw, "FROM ", "cafés", 4), underline(raw, 5, 5));
+    assert_eq!(snippet(raw, "= ", "'ñandú'", 4), underline(raw, 27, 7));
+  }
+}
@@ -0,0 +1,82 @@
+use serde::Deserialize;
+
+// SQL flavours the emitter can target, syntax that differs between engines is resolved here
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dialect {
+  #[default]
+  Ansi,
+  #[serde(alias = "postgresql")]
+  Postgres,
+  MySql,
+  Sqlite,
+}
+
+impl Dialect {
+  pub fn name(&self) -> &str {
+    match self {
+      Dialect::Ansi => "ansi",
+      Dialect::Postgres => "postgres",
+      Dialect::MySql => "mysql",
+      Dialect::Sqlite => "sqlite",
+    }
+  }
+
+  // the names `--dialect` takes, `postgresql` as well like in spipe.toml
+  pub fn from_name(name: &str) -> Option<Dialect> {
+    match name {
+      "ansi" => Some(Dialect::Ansi),
+      "postgres" | "postgresql" => Some(Dialect::Postgres),
+      "mysql" => Some(Dialect::MySql),
+      "sqlite" => Some(Dialect::Sqlite),
+      _ => None,
+    }
+  }
+
+  // the server a `postgres://` or `mysql://` connection url points at
+  pub fn from_url(url: &str) -> Option<Dialect> {
+    match url.split_once("://")?.0 {
+      "postgres" | "postgresql" => Some(Dialect::Postgres),
+      "mysql" => Some(Dialect::MySql),
+      _ => None,
+    }
+  }
+
+  // QUALIFY comes from bigquery and snowflake, ansi passes it through as written
+  pub fn has_qualify(&self) -> bool {
+    matches!(self, Dialect::Ansi)
+  }
+
+  // the others approximate a sample with a random row filter
+  pub fn has_tablesample(&self) -> bool {
+    matches!(self, Dialect::Ansi | Dialect::Postgres)
+  }
+
+  // mysql and sqlite have no ordered-set aggregates, a percentile is looked up with a subquery
+  pub fn has_percentile(&self) -> bool {
+    matches!(self, Dialect::Ansi | Dialect::Postgres)
+  }
+
+  // mysql only has `WITH ROLLUP`, sqlite has none of ROLLUP, CUBE and GROUPING SETS
+  pub fn has_grouping_sets(&self) -> bool {
+    matches!(self, Dialect::Ansi | Dialect::Postgres)
+  }
+
+  // sqlite compares a row value only against a subquery, `(a, b) IN ((1, 2))` is spelled out as equalities
+  pub fn has_row_value_list(&self) -> bool {
+    !matches!(self, Dialect::Sqlite)
+  }
+
+  // sqlite empties a table with a DELETE without WHERE instead
+  pub fn has_truncate(&self) -> bool {
+    !matches!(self, Dialect::Sqlite)
+  }
+
+  // a column name that may not be a valid bare identifier, e.g. `'Q1'` pivoted into a column
+  pub fn quote_identifier(&self, name: &str) -> String {
+    match self {
+      Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+      _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+  }
+}
@@ -1,58 +1,532 @@
 #![allow(dead_code)]
+mod dialect;
+mod spipe_printer;
+mod target;
+
+pub use dialect::Dialect;
+pub use target::TargetVersion;
+
+use std::borrow::Cow;
+
+use crate::lexer::{Token, TokenType};
 use crate::parser::ast::*;
+use crate::utils::range::{range_from, Range};
+
+// every node is written through this, so the dialect and the options reach all of them the same way
+pub trait ToSql {
+  // appends to `buf`, so a whole program is written into one string instead of one per node
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions);
+
+  fn to_sql(&self, dialect: Dialect, opts: &EmitOptions) -> String {
+    let mut buf = String::new();
+    self.write_sql(&mut buf, dialect, opts);
+    buf
+  }
+}
+
+// how to write the sql besides the dialect
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmitOptions<'a> {
+  // re-emitted next to the statements they annotate, none by default
+  pub comments: &'a [Comment],
+}
+
+impl<'a> EmitOptions<'a> {
+  pub fn with_comments(mut self, comments: &'a [Comment]) -> Self {
+    self.comments = comments;
+    self
+  }
+}
+
+impl ToSql for Program {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    if !opts.comments.is_empty() {
+      return self.write_sql_with_comments(buf, dialect, opts);
+    }
+    for (index, query) in self.emitted_queries(dialect).into_iter().enumerate() {
+      if index > 0 {
+        buf.push_str(";\n");
+      }
+      query.write_sql(buf, dialect, opts);
+    }
+  }
+}
+
+// the statements of one `;`-separated query
+impl ToSql for [Statement] {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    for (position, stmt) in regroup(self, dialect).iter().enumerate() {
+      if position > 0 {
+        buf.push(' ');
+      }
+      write_select_first(buf, stmt, dialect, opts);
+      hoist_windows(buf, stmt, dialect, opts);
+    }
+  }
+}
+
+// `AGGREGATE COUNT(id) AS n GROUP BY d |> WHERE n > 1` parses as the aggregate and a chain led by its GROUP BY,
+// which goes back on the aggregate so the stages after the GROUP BY read the groups
+fn regroup(query: &[Statement], dialect: Dialect) -> Vec<Cow<'_, Statement>> {
+  let mut statements: Vec<Cow<Statement>> = vec![];
+  for stmt in query.iter().filter(|stmt| stmt.is_emitted_for(dialect)) {
+    match statements.pop() {
+      Some(previous) if matches!(stmt, Statement::Pipe(_)) && matches!(stmt.first_stage(), Statement::GroupBy(_)) => {
+        statements.push(Cow::Owned(stmt.clone().on_top_of(previous.into_owned())))
+      }
+      previous => {
+        statements.extend(previous);
+        statements.push(Cow::Borrowed(stmt));
+      }
+    }
+  }
+  statements
+}
 
 impl Program {
-  pub fn to_sql(&self) -> String {
-    self.statements.iter().map(|stmt| stmt.to_sql()).collect::<Vec<_>>().join(" ")
+  // each query on its own with the source range it came from, for running them one at a time
+  pub fn queries_sql(&self, dialect: Dialect, opts: &EmitOptions) -> Vec<(String, Range)> {
+    let queries = self.emitted_queries(dialect).into_iter().map(|query| {
+      let first = query.first().map(|stmt| stmt.get_range()).unwrap_or_default();
+      let last = query.last().map(|stmt| stmt.get_range()).unwrap_or_default();
+      (query.to_sql(dialect, opts), range_from(&first, &last))
+    });
+    queries.collect()
+  }
+
+  // the queries left once the statements the dialect has no counterpart for are dropped
+  fn emitted_queries(&self, dialect: Dialect) -> Vec<&[Statement]> {
+    let queries = self.queries().into_iter();
+    queries.filter(|query| query.iter().any(|stmt| stmt.is_emitted_for(dialect))).collect()
+  }
+
+  // own-line comments are re-emitted before the statement that follows them,
+  // trailing comments after the statement they annotate.
+  fn write_sql_with_comments(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    let mut comments = opts.comments.iter().filter(|comment| comment.anchor.is_none()).peekable();
+    for (index, query) in self.emitted_queries(dialect).into_iter().enumerate() {
+      if index > 0 {
+        buf.truncate(buf.trim_end_matches(' ').len());
+        buf.push_str(";\n");
+      }
+      for stmt in regroup(query, dialect).iter() {
+        let range = stmt.get_range();
+        while let Some(comment) = comments.next_if(|comment| comment.range.start < range.end) {
+          separate(buf);
+          buf.push_str(&comment.emit());
+          buf.push('\n');
+        }
+        separate(buf);
+        write_select_first(buf, stmt, dialect, opts);
+        hoist_windows(buf, stmt, dialect, opts);
+      }
+    }
+    for comment in comments {
+      separate(buf);
+      buf.push_str(&comment.emit());
+      buf.push('\n');
+    }
   }
 }
 
 impl Statement {
-  pub fn to_sql(&self) -> String {
+  // whether the chain is written as a whole query, a SELECT or a rewritten stage that is not filtered or joined after.
+  // a filter, a join or another SELECT after one cannot be appended to it, so it reads it as a derived table
+  fn is_query(&self) -> bool {
+    match self {
+      Statement::Select(_) | Statement::Aggregate(_) | Statement::Pivot(_) | Statement::Unpivot(_) => true,
+      Statement::Qualify(qualify) => qualify.rewrite,
+      Statement::Pipe(pipe) => match &*pipe.right {
+        Statement::Where(_) | Statement::Join(_) => false,
+        right => right.is_query() || pipe.left.is_query(),
+      },
+      _ => false,
+    }
+  }
+
+  // the stage the chain starts with
+  fn first_stage(&self) -> &Statement {
+    match self {
+      Statement::Pipe(pipe) => pipe.left.first_stage(),
+      first => first,
+    }
+  }
+
+  // the chain with `base` piped in before its first stage
+  fn on_top_of(self, base: Statement) -> Statement {
+    match self {
+      Statement::Pipe(pipe) => Statement::Pipe(PipeStatement::new(pipe.left.on_top_of(base), *pipe.right)),
+      first => Statement::Pipe(PipeStatement::new(base, first)),
+    }
+  }
+
+  // how many derived tables the chain nests
+  fn derived_tables(&self) -> usize {
+    match self {
+      Statement::Pipe(pipe) => pipe.left.derived_tables() + usize::from(pipe.reads_derived_table()),
+      _ => 0,
+    }
+  }
+
+  // COMMENT ON is left out outside postgres and PRAGMA outside sqlite, the checker warns about both
+  fn is_emitted_for(&self, dialect: Dialect) -> bool {
+    match self {
+      Statement::CommentOn(_) => dialect == Dialect::Postgres,
+      Statement::Pragma(_) => dialect == Dialect::Sqlite,
+      _ => true,
+    }
+  }
+}
+
+impl ToSql for Statement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
     match self {
-      Statement::Select(s) => s.emit(),
-      Statement::From(f) => f.emit(),
-      Statement::Join(j) => j.emit(),
-      Statement::Where(w) => w.emit(),
-      Statement::GroupBy(g) => g.emit(),
-      Statement::Order(o) => o.emit(),
-      Statement::Limit(l) => l.emit(),
-      Statement::Pipe(p) => p.to_sql(),
-      Statement::Aggregate(a) => a.emit(),
-      Statement::Expression(e) => e.emit(),
+      Statement::Select(s) => s.write_sql(buf, dialect, opts),
+      Statement::From(f) => f.write_sql(buf, dialect, opts),
+      Statement::Join(j) => j.write_sql(buf, dialect, opts),
+      Statement::Where(w) => w.write_sql(buf, dialect, opts),
+      Statement::GroupBy(g) => g.write_sql(buf, dialect, opts),
+      Statement::Order(o) => o.write_sql(buf, dialect, opts),
+      Statement::Limit(l) => l.write_sql(buf, dialect, opts),
+      Statement::Pipe(p) => return p.write_sql(buf, dialect, opts),
+      Statement::Aggregate(a) => a.write_sql(buf, dialect, opts),
+      Statement::Pivot(p) => p.write_sql(buf, dialect, opts),
+      Statement::Unpivot(u) => u.write_sql(buf, dialect, opts),
+      Statement::Qualify(q) => q.write_sql(buf, dialect, opts),
+      Statement::Window(w) => w.write_sql(buf, dialect, opts),
+      Statement::Copy(c) => c.write_sql(buf, dialect, opts),
+      Statement::Insert(i) => i.write_sql(buf, dialect, opts),
+      Statement::Truncate(t) => t.write_sql(buf, dialect, opts),
+      Statement::AlterTable(a) => a.write_sql(buf, dialect, opts),
+      Statement::CreateIndex(c) => c.write_sql(buf, dialect, opts),
+      Statement::DropIndex(d) => d.write_sql(buf, dialect, opts),
+      Statement::CommentOn(c) => c.write_sql(buf, dialect, opts),
+      Statement::Pragma(p) => p.write_sql(buf, dialect, opts),
+      Statement::Grant(g) => g.write_sql(buf, dialect, opts),
+      Statement::Revoke(r) => r.write_sql(buf, dialect, opts),
+      Statement::Transaction(t) => t.write_sql(buf, dialect, opts),
+      Statement::With(w) => w.write_sql(buf, dialect, opts),
+      Statement::Expression(e) => e.write_sql(buf, dialect, opts),
     }
+    write_trailing_comments(buf, &self.get_range(), opts.comments)
+  }
+}
+
+impl ToSql for PipeStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    // the rewrites wrap the earlier stages, which they read from in place of a table
+    let base = |buf: &mut String| self.write_base(buf, dialect, opts);
+    let range = match &*self.right {
+      Statement::Aggregate(a) => a.write_with_base(buf, &base, dialect, opts),
+      Statement::Pivot(p) => p.write_with_base(buf, &base, dialect, opts),
+      Statement::Unpivot(u) => u.write_with_base(buf, &base, dialect, opts),
+      Statement::Qualify(q) if q.rewrite => q.write_with_base(buf, &base, dialect, opts),
+      // hoisted to the end of the query by `hoist_windows`
+      Statement::Window(w) => {
+        self.left.write_sql(buf, dialect, opts);
+        return write_trailing_comments(buf, &w.get_range(), opts.comments);
+      }
+      // the SELECT closing the FROM stages goes in front of them
+      Statement::Select(select) if select.from.is_none() && self.reads_from_base() => {
+        select.write_sql(buf, dialect, opts);
+        buf.push(' ');
+        base(buf);
+        return write_trailing_comments(buf, &select.get_range(), opts.comments);
+      }
+      // a filter or a join after a whole query reads the rows it gives
+      Statement::Where(_) | Statement::Join(_) if self.left.is_query() => {
+        base(buf);
+        buf.push(' ');
+        return self.right.write_sql(buf, dialect, opts);
+      }
+      _ => {
+        self.left.write_sql(buf, dialect, opts);
+        separate(buf);
+        return self.right.write_sql(buf, dialect, opts);
+      }
+    };
+    write_trailing_comments(buf, &range, opts.comments)
   }
 }
 
 impl PipeStatement {
-  pub fn to_sql(&self) -> String {
-    let left = self.left.to_sql();
-    match &*self.right {
-      Statement::Aggregate(a) => a.emit_with_base(left),
-      Statement::Join(j) => format!("{} {}", left, j.emit()),
-      Statement::Where(w) => format!("{} {}", left, w.emit()),
-      Statement::GroupBy(g) => format!("{} {}", left, g.emit()),
-      Statement::Order(o) => format!("{} {}", left, o.emit()),
-      Statement::Limit(l) => format!("{} {}", left, l.emit()),
-      _ => format!("{} {}", left, self.right.to_sql()),
+  // what the earlier stages read from: their own `FROM ...`, or after a whole query
+  // that query as a derived table, `FROM (SELECT a FROM t) AS _p1`, numbered from the innermost one
+  fn write_base(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    if !self.left.is_query() {
+      return self.left.write_sql(buf, dialect, opts);
     }
+    buf.push_str("FROM (");
+    write_select_first(buf, &self.left, dialect, opts);
+    buf.push_str(") AS _p");
+    buf.push_str(&(self.left.derived_tables() + 1).to_string());
+  }
+
+  // whether the earlier stages start with a FROM or make a whole query, either way a FROM can be put after a SELECT
+  fn reads_from_base(&self) -> bool {
+    self.left.is_query() || matches!(self.left.first_stage(), Statement::From(_))
+  }
+
+  // whether the stage reads the earlier ones as a derived table
+  fn reads_derived_table(&self) -> bool {
+    let reads_rows = match &*self.right {
+      Statement::Select(select) => select.from.is_none(),
+      Statement::Qualify(qualify) => qualify.rewrite,
+      Statement::Where(_) | Statement::Join(_) | Statement::Aggregate(_) => true,
+      Statement::Pivot(_) | Statement::Unpivot(_) => true,
+      _ => false,
+    };
+    reads_rows && self.left.is_query()
+  }
+}
+
+impl Comment {
+  pub fn emit(&self) -> String {
+    format!("--{}", self.text)
+  }
+}
+
+fn write_trailing_comments(buf: &mut String, range: &Range, comments: &[Comment]) {
+  for comment in comments.iter().filter(|comment| comment.is_trailing() && comment.is_anchored_to(range)) {
+    separate(buf);
+    buf.push_str(&comment.emit());
+    buf.push('\n');
+  }
+}
+
+// a line comment swallows the rest of its line, so whatever follows one starts on a new line
+fn separate(buf: &mut String) {
+  if !buf.is_empty() && !buf.ends_with('\n') {
+    buf.push(' ');
+  }
+}
+
+fn write_alias(buf: &mut String, alias: &Option<Token>) {
+  if let Some(alias) = alias {
+    buf.push_str(" AS ");
+    buf.push_str(alias.lexeme.as_ref().unwrap());
+  }
+}
+
+fn write_names(buf: &mut String, names: &[Token]) {
+  for (index, name) in names.iter().enumerate() {
+    if index > 0 {
+      buf.push_str(", ");
+    }
+    buf.push_str(name.lexeme.as_ref().unwrap());
+  }
+}
+
+fn write_list<T: ToSql>(buf: &mut String, items: &[T], dialect: Dialect, opts: &EmitOptions) {
+  for (index, item) in items.iter().enumerate() {
+    if index > 0 {
+      buf.push_str(", ");
+    }
+    item.write_sql(buf, dialect, opts);
+  }
+}
+
+// writes the FROM the earlier stages read from, and whatever they filter and join it with
+type Base<'a> = dyn Fn(&mut String) + 'a;
+
+fn write_select_from(buf: &mut String, columns: impl FnOnce(&mut String), base: &Base) {
+  buf.push_str("SELECT ");
+  columns(buf);
+  buf.push(' ');
+  base(buf);
+}
+
+impl ToSql for AggregateClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    self.write_call(buf, dialect, opts);
+    write_alias(buf, &self.alias);
   }
 }
 
 impl AggregateClause {
-  pub fn emit_with_base(&self, base: String) -> String {
-    let alias = self.alias.as_ref().map_or(String::new(), |a| format!(" AS {}", a.lexeme.as_ref().unwrap()));
-    let func = format!("{}({}){}", self.function.emit(), self.argument.emit(), alias);
-    if base.trim().starts_with("FROM") {
-      format!("SELECT {} {}", func, base)
-    } else {
-      format!("SELECT {} FROM {}", func, base)
+  pub fn write_with_base(&self, buf: &mut String, base: &Base, dialect: Dialect, opts: &EmitOptions) -> Range {
+    match &self.within_group {
+      Some(within_group) if !dialect.has_percentile() => {
+        buf.push_str("SELECT ");
+        self.write_ranked(buf, within_group, base, dialect, opts);
+        write_alias(buf, &self.alias);
+      }
+      _ => write_select_from(buf, |buf| self.write_sql(buf, dialect, opts), base),
     }
+    self.get_range()
   }
 
-  pub fn emit(&self) -> String {
-    let alias = self.alias.as_ref().map_or(String::new(), |a| format!(" AS {}", a.lexeme.as_ref().unwrap()));
-    format!("{}({}){}", self.function.emit(), self.argument.emit(), alias)
+  // only postgres has FILTER, elsewhere rows failing the filter become NULLs the aggregate skips
+  fn write_call(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str(self.function.emit());
+    buf.push('(');
+    match &self.within_group {
+      // the fraction of a percentile is not filtered, the values it orders are
+      Some(_) => self.argument.write_sql(buf, dialect, opts),
+      None => self.write_filtered(buf, &self.argument, dialect, opts),
+    }
+    buf.push(')');
+    if let Some(within_group) = &self.within_group {
+      buf.push_str(" WITHIN GROUP (ORDER BY ");
+      for (index, key) in within_group.order.columns.iter().enumerate() {
+        if index > 0 {
+          buf.push_str(", ");
+        }
+        self.write_filtered(buf, &key.column, dialect, opts);
+        buf.push(' ');
+        buf.push_str(key.direction.emit());
+      }
+      buf.push(')');
+    }
+    if let (Some(filter), Dialect::Postgres) = (&self.filter, dialect) {
+      buf.push_str(" FILTER (WHERE ");
+      filter.write_sql(buf, dialect, opts);
+      buf.push(')');
+    }
+  }
+
+  fn write_filtered(&self, buf: &mut String, value: &Expression, dialect: Dialect, opts: &EmitOptions) {
+    match &self.filter {
+      Some(filter) if dialect != Dialect::Postgres => {
+        buf.push_str("CASE WHEN ");
+        filter.write_sql(buf, dialect, opts);
+        buf.push_str(" THEN ");
+        value.write_sql(buf, dialect, opts);
+        buf.push_str(" END");
+      }
+      _ => value.write_sql(buf, dialect, opts),
+    }
+  }
+
+  // the value at the percentile's position among the non-null values, which are ranked first:
+  // (SELECT value FROM (SELECT salary AS value, ROW_NUMBER() OVER (ORDER BY salary IS NULL, salary ASC) AS position,
+  //   COUNT(salary) OVER () AS total FROM employees) AS ranked WHERE position >= 0.5 * total ORDER BY position LIMIT 1)
+  // it is exact for PERCENTILE_DISC, PERCENTILE_CONT gets the nearest value instead of interpolating
+  fn write_ranked(
+    &self,
+    buf: &mut String,
+    within_group: &WithinGroupClause,
+    base: &Base,
+    dialect: Dialect,
+    opts: &EmitOptions,
+  ) {
+    let key = &within_group.order.columns[0];
+    let mut value = String::new();
+    self.write_filtered(&mut value, &key.column, dialect, opts);
+    buf.push_str("(SELECT value FROM (");
+    let ranked = |buf: &mut String| {
+      buf.push_str(&value);
+      buf.push_str(" AS value, ROW_NUMBER() OVER (ORDER BY ");
+      buf.push_str(&value);
+      buf.push_str(" IS NULL, ");
+      buf.push_str(&value);
+      buf.push(' ');
+      buf.push_str(key.direction.emit());
+      buf.push_str(") AS position, COUNT(");
+      buf.push_str(&value);
+      buf.push_str(") OVER () AS total");
+    };
+    write_select_from(buf, ranked, base);
+    buf.push_str(") AS ranked WHERE position >= ");
+    self.argument.write_sql(buf, dialect, opts);
+    buf.push_str(" * total ORDER BY position LIMIT 1)");
+  }
+}
+
+impl ToSql for PivotClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("PIVOT (");
+    self.aggregate.write_sql(buf, dialect, opts);
+    buf.push_str(" FOR ");
+    buf.push_str(self.for_col.lexeme.as_ref().unwrap());
+    buf.push_str(" IN (");
+    write_list(buf, &self.in_values, dialect, opts);
+    buf.push_str("))");
+  }
+}
+
+impl PivotClause {
+  // ansi keeps PIVOT, the others get one conditional aggregate per pivoted value
+  pub fn write_with_base(&self, buf: &mut String, base: &Base, dialect: Dialect, opts: &EmitOptions) -> Range {
+    match dialect {
+      Dialect::Ansi => {
+        write_select_from(buf, |buf| buf.push('*'), base);
+        buf.push(' ');
+        self.write_sql(buf, dialect, opts);
+      }
+      _ => write_select_from(buf, |buf| self.write_conditional(buf, dialect, opts), base),
+    }
+    self.get_range()
+  }
+
+  // SUM(CASE WHEN quarter = 'Q1' THEN sales END) AS "Q1", ...
+  fn write_conditional(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    for (index, value) in self.in_values.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(", ");
+      }
+      buf.push_str(self.aggregate.function.emit());
+      buf.push_str("(CASE WHEN ");
+      buf.push_str(self.for_col.lexeme.as_ref().unwrap());
+      buf.push_str(" = ");
+      value.write_sql(buf, dialect, opts);
+      buf.push_str(" THEN ");
+      self.aggregate.argument.write_sql(buf, dialect, opts);
+      buf.push_str(" END) AS ");
+      buf.push_str(&dialect.quote_identifier(&pivot_column_name(value, dialect, opts)));
+    }
+  }
+}
+
+// the column a pivoted value becomes, `'Q1'` names its column `Q1`
+fn pivot_column_name(value: &Expression, dialect: Dialect, opts: &EmitOptions) -> String {
+  match value {
+    Expression::Literal(Literal::String(string)) => string.value.clone(),
+    _ => value.to_sql(dialect, opts),
+  }
+}
+
+impl ToSql for UnpivotClause {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str("UNPIVOT (");
+    buf.push_str(self.value_col.lexeme.as_ref().unwrap());
+    buf.push_str(" FOR ");
+    buf.push_str(self.for_col.lexeme.as_ref().unwrap());
+    buf.push_str(" IN (");
+    write_names(buf, &self.in_columns);
+    buf.push_str("))");
+  }
+}
+
+impl UnpivotClause {
+  // ansi keeps UNPIVOT, the others read the rows once per unpivoted column and stack them with UNION ALL
+  pub fn write_with_base(&self, buf: &mut String, base: &Base, dialect: Dialect, opts: &EmitOptions) -> Range {
+    if dialect == Dialect::Ansi {
+      write_select_from(buf, |buf| buf.push('*'), base);
+      buf.push(' ');
+      self.write_sql(buf, dialect, opts);
+      return self.get_range();
+    }
+    let for_col = self.for_col.lexeme.as_ref().unwrap();
+    let value_col = self.value_col.lexeme.as_ref().unwrap();
+    for (index, column) in self.in_columns.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(" UNION ALL ");
+      }
+      let column = column.lexeme.as_ref().unwrap();
+      let columns = |buf: &mut String| {
+        buf.push('\'');
+        buf.push_str(column);
+        buf.push_str("' AS ");
+        buf.push_str(for_col);
+        buf.push_str(", ");
+        buf.push_str(column);
+        buf.push_str(" AS ");
+        buf.push_str(value_col);
+      };
+      write_select_from(buf, columns, base);
+    }
+    self.get_range()
   }
 }
 
@@ -83,48 +557,91 @@ impl AggregateFn {
       AggregateFn::BitOr => "BIT_OR",
       AggregateFn::BoolAnd => "BOOL_AND",
       AggregateFn::BoolOr => "BOOL_OR",
+      AggregateFn::PercentileCont => "PERCENTILE_CONT",
+      AggregateFn::PercentileDisc => "PERCENTILE_DISC",
     }
   }
 }
 
-impl SelectStatement {
-  pub fn emit(&self) -> String {
-    let distinct = if self.distinct { "DISTINCT " } else { "" };
-    let exprs = self.expressions.iter().map(|e| e.emit()).collect::<Vec<_>>().join(", ");
-    let from = self.from.as_ref().map_or(String::new(), |f| format!(" {}", f.emit()));
-    format!("SELECT {}{}{}", distinct, exprs, from)
+impl ToSql for SelectStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("SELECT ");
+    match &self.distinct {
+      Distinct::None => {}
+      Distinct::All => buf.push_str("DISTINCT "),
+      // postgres only, the checker reports it for the other dialects
+      Distinct::On(expressions) => {
+        buf.push_str("DISTINCT ON (");
+        write_list(buf, expressions, dialect, opts);
+        buf.push_str(") ");
+      }
+    }
+    write_list(buf, &self.expressions, dialect, opts);
+    if let Some(from) = &self.from {
+      buf.push(' ');
+      from.write_sql(buf, dialect, opts);
+    }
   }
 }
 
-impl JoinClause {
-  pub fn emit(&self) -> String {
-    format!("JOIN {} ON {}", self.table.lexeme.as_ref().unwrap(), self.on.emit())
+impl ToSql for JoinClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("JOIN ");
+    buf.push_str(self.table.lexeme.as_ref().unwrap());
+    write_alias(buf, &self.alias);
+    buf.push_str(" ON ");
+    self.on.write_sql(buf, dialect, opts);
   }
 }
 
-impl WhereClause {
-  pub fn emit(&self) -> String {
-    format!("WHERE {}", self.condition.emit())
+impl ToSql for WhereClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("WHERE ");
+    self.condition.write_sql(buf, dialect, opts);
   }
 }
 
-impl GroupByClause {
-  pub fn emit(&self) -> String {
-    let cols = self.columns.iter().map(|expression| expression.emit()).collect::<Vec<_>>().join(", ");
-    format!("GROUP BY {}", cols)
+impl ToSql for GroupByClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    let (prefix, suffix) = match (&self.modifier, dialect) {
+      (GroupByModifier::None, _) => ("GROUP BY ", ""),
+      (GroupByModifier::Rollup, Dialect::MySql) => ("GROUP BY ", " WITH ROLLUP"),
+      // the checker reports the groupings a dialect has no syntax for
+      (GroupByModifier::Rollup, _) => ("GROUP BY ROLLUP(", ")"),
+      (GroupByModifier::Cube, _) => ("GROUP BY CUBE(", ")"),
+      (GroupByModifier::GroupingSets(sets), _) => return write_grouping_sets(buf, sets, dialect, opts),
+    };
+    buf.push_str(prefix);
+    write_list(buf, &self.columns, dialect, opts);
+    buf.push_str(suffix);
   }
 }
 
-impl OrderClause {
-  pub fn emit(&self) -> String {
-    let cols = self.columns.iter().map(|order| order.emit()).collect::<Vec<_>>().join(", ");
-    format!("ORDER BY {}", cols)
+fn write_grouping_sets(buf: &mut String, sets: &[Vec<Expression>], dialect: Dialect, opts: &EmitOptions) {
+  buf.push_str("GROUP BY GROUPING SETS (");
+  for (index, set) in sets.iter().enumerate() {
+    if index > 0 {
+      buf.push_str(", ");
+    }
+    buf.push('(');
+    write_list(buf, set, dialect, opts);
+    buf.push(')');
   }
+  buf.push(')');
 }
 
-impl OrderColumn {
-  pub fn emit(&self) -> String {
-    format!("{} {}", self.column.emit(), self.direction.emit())
+impl ToSql for OrderClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("ORDER BY ");
+    write_list(buf, &self.columns, dialect, opts);
+  }
+}
+
+impl ToSql for OrderColumn {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    self.column.write_sql(buf, dialect, opts);
+    buf.push(' ');
+    buf.push_str(self.direction.emit());
   }
 }
 
@@ -137,40 +654,94 @@ impl OrderDirection {
   }
 }
 
-impl LimitClause {
-  pub fn emit(&self) -> String {
-    let offset = self.offset.as_ref().map_or(String::new(), |literal| format!(", {}", literal.emit()));
-    format!("LIMIT {}{}", self.count.emit(), offset)
+impl ToSql for LimitClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("LIMIT ");
+    self.count.write_sql(buf, dialect, opts);
+    // `LIMIT a, b` reads as offset then count in mysql and sqlite, OFFSET is unambiguous everywhere
+    if let Some(offset) = &self.offset {
+      buf.push_str(" OFFSET ");
+      offset.write_sql(buf, dialect, opts);
+    }
   }
 }
 
-impl Expression {
-  pub fn emit(&self) -> String {
+impl ToSql for Expression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
     match self {
-      Expression::Column(c) => c.emit(),
-      Expression::Literal(l) => l.emit(),
-      Expression::Condition(c) => c.emit(),
-      Expression::FunctionCall(f) => f.emit(),
-      Expression::Subquery(s) => s.emit(),
+      Expression::Column(c) => c.write_sql(buf, dialect, opts),
+      Expression::Literal(l) => l.write_sql(buf, dialect, opts),
+      Expression::Condition(c) => c.write_sql(buf, dialect, opts),
+      Expression::FunctionCall(f) => f.write_sql(buf, dialect, opts),
+      Expression::Subquery(s) => s.write_sql(buf, dialect, opts),
+      Expression::CurrentTimestamp(t) => t.write_sql(buf, dialect, opts),
+      Expression::In(i) => i.write_sql(buf, dialect, opts),
+      Expression::Not(n) => n.write_sql(buf, dialect, opts),
+      Expression::Window(w) => w.write_sql(buf, dialect, opts),
+      Expression::Cast(c) => c.write_sql(buf, dialect, opts),
+      Expression::Tuple(t) => t.write_sql(buf, dialect, opts),
     }
   }
 }
 
-impl ColumnExpression {
-  pub fn emit(&self) -> String {
-    if self.table.is_none() {
-      return self.column.lexeme.as_ref().unwrap().to_string();
+impl ToSql for ColumnExpression {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    if let Some(table) = &self.table {
+      buf.push_str(table.lexeme.as_ref().unwrap());
+      buf.push('.');
     }
+    buf.push_str(self.column.lexeme.as_ref().unwrap());
+  }
+}
 
-    let table = self.table.as_ref().unwrap();
-
-    format!("{}.{}", self.column.lexeme.as_ref().unwrap(), table.lexeme.as_ref().unwrap())
+impl ToSql for ConditionExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    // sqlite has no interval type, a date is moved by a modifier of datetime: `datetime(created_at, '-7 days')`
+    if let (Some((date, interval)), Dialect::Sqlite) = (self.shifted_date(), dialect) {
+      buf.push_str("datetime(");
+      date.write_sql(buf, dialect, opts);
+      buf.push_str(", ");
+      interval.write_modifier(buf, self.operator == Operator::Minus);
+      return buf.push(')');
+    }
+    let precedence = self.operator.precedence();
+    write_operand(buf, &self.left, |operand| operand < precedence, dialect, opts);
+    buf.push(' ');
+    buf.push_str(self.operator.emit());
+    buf.push(' ');
+    // the operators group from the left, so an equal one on the right was grouped on purpose
+    write_operand(buf, &self.right, |operand| operand <= precedence, dialect, opts);
   }
 }
 
 impl ConditionExpression {
-  pub fn emit(&self) -> String {
-    format!("{} {} {}", self.left.emit(), self.operator.emit(), self.right.emit())
+  // `date + INTERVAL ...`, `INTERVAL ... + date` or `date - INTERVAL ...`
+  fn shifted_date(&self) -> Option<(&Expression, &IntervalLiteral)> {
+    match (&self.operator, &*self.left, &*self.right) {
+      (Operator::Plus | Operator::Minus, date, Expression::Literal(Literal::Interval(interval))) => {
+        Some((date, interval))
+      }
+      (Operator::Plus, Expression::Literal(Literal::Interval(interval)), date) => Some((date, interval)),
+      _ => None,
+    }
+  }
+}
+
+// parenthesized only where the tree groups differently than the operators would on their own
+fn write_operand(
+  buf: &mut String,
+  operand: &Expression,
+  grouped: impl Fn(u8) -> bool,
+  dialect: Dialect,
+  opts: &EmitOptions,
+) {
+  match operand {
+    Expression::Condition(condition) if grouped(condition.operator.precedence()) => {
+      buf.push('(');
+      operand.write_sql(buf, dialect, opts);
+      buf.push(')');
+    }
+    _ => operand.write_sql(buf, dialect, opts),
   }
 }
 
@@ -185,59 +756,808 @@ impl Operator {
       Operator::GreaterThanOrEqual => ">=",
       Operator::And => "AND",
       Operator::Or => "OR",
+      Operator::Plus => "+",
+      Operator::Minus => "-",
+      Operator::Multiply => "*",
+      Operator::Divide => "/",
+      Operator::Modulo => "%",
+    }
+  }
+
+  // higher binds tighter
+  fn precedence(&self) -> u8 {
+    match self {
+      Operator::Or => 1,
+      Operator::And => 2,
+      Operator::Equal
+      | Operator::NotEqual
+      | Operator::LessThan
+      | Operator::GreaterThan
+      | Operator::LessThanOrEqual
+      | Operator::GreaterThanOrEqual => 3,
+      Operator::Plus | Operator::Minus => 4,
+      Operator::Multiply | Operator::Divide | Operator::Modulo => 5,
     }
   }
 }
 
-impl FromClause {
-  pub fn emit(&self) -> String {
-    format!("FROM {}", self.table.lexeme.as_ref().unwrap())
+impl ToSql for FromClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    let table = self.table.lexeme.as_ref().unwrap();
+    buf.push_str("FROM ");
+    match &self.sample {
+      None => {
+        buf.push_str(table);
+        write_alias(buf, &self.alias);
+      }
+      Some(sample) if dialect.has_tablesample() => {
+        buf.push_str(table);
+        write_alias(buf, &self.alias);
+        buf.push(' ');
+        sample.write_sql(buf, dialect, opts);
+      }
+      // without TABLESAMPLE each row is kept at random, under the name the later stages use
+      Some(sample) => {
+        buf.push_str("(SELECT * FROM ");
+        buf.push_str(table);
+        buf.push_str(" WHERE ");
+        sample.write_filter(buf, dialect, opts);
+        buf.push_str(") AS ");
+        buf.push_str(self.alias.as_ref().map_or(table, |a| a.lexeme.as_ref().unwrap()));
+      }
+    }
   }
 }
 
-impl Literal {
-  pub fn emit(&self) -> String {
+impl ToSql for TableSampleClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("TABLESAMPLE ");
+    buf.push_str(self.method.emit(dialect));
+    buf.push('(');
+    self.size.write_sql(buf, dialect, opts);
+    buf.push(')');
+  }
+}
+
+impl TableSampleClause {
+  // a row filter keeping about `size` percent of the rows
+  fn write_filter(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    let percent = match &self.size {
+      Expression::Literal(Literal::Number(number)) => number.raw.parse::<f64>().ok(),
+      _ => None,
+    };
+    match (dialect, percent) {
+      (Dialect::Sqlite, _) => {
+        buf.push_str("ABS(RANDOM()) % 100 < ");
+        self.size.write_sql(buf, dialect, opts);
+      }
+      (_, Some(percent)) => {
+        buf.push_str("RAND() < ");
+        buf.push_str(&(percent / 100.0).to_string());
+      }
+      (_, None) => {
+        buf.push_str("RAND() < (");
+        self.size.write_sql(buf, dialect, opts);
+        buf.push_str(") / 100");
+      }
+    }
+  }
+}
+
+impl SampleMethod {
+  pub fn emit(&self, dialect: Dialect) -> &'static str {
+    match (self, dialect) {
+      (SampleMethod::Bernoulli, _) => "BERNOULLI",
+      (SampleMethod::Block, Dialect::Ansi) => "BLOCK",
+      (SampleMethod::System | SampleMethod::Block, _) => "SYSTEM",
+    }
+  }
+}
+
+impl ToSql for Literal {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
     match self {
-      Literal::String(s) => s.emit(),
-      Literal::Number(n) => n.emit(),
-      Literal::Boolean(b) => b.emit(),
+      Literal::String(s) => s.write_sql(buf, dialect, opts),
+      Literal::Number(n) => n.write_sql(buf, dialect, opts),
+      Literal::Boolean(b) => b.write_sql(buf, dialect, opts),
+      Literal::Interval(i) => i.write_sql(buf, dialect, opts),
     }
   }
 }
 
-impl NumberLiteral {
-  pub fn emit(&self) -> String {
-    self.raw.clone()
+impl ToSql for NumberLiteral {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str(&self.raw);
   }
 }
-impl StringLiteral {
-  pub fn emit(&self) -> String {
-    format!("'{}'", self.value)
+impl ToSql for StringLiteral {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push('\'');
+    buf.push_str(&self.value);
+    buf.push('\'');
   }
 }
-impl BooleanLiteral {
-  pub fn emit(&self) -> String {
-    self.value.to_string()
+impl ToSql for BooleanLiteral {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str(if self.value { "true" } else { "false" });
   }
 }
 
-impl FunctionCallExpression {
-  pub fn emit(&self) -> String {
-    let args = self.arguments.iter().map(|e| e.emit()).collect::<Vec<_>>().join(", ");
-    format!("{}({})", self.function_name.lexeme.as_ref().unwrap(), args)
+impl ToSql for IntervalLiteral {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, _: &EmitOptions) {
+    let sql = match dialect {
+      Dialect::Ansi => format!("INTERVAL '{}' {}", self.value, self.unit.emit()),
+      Dialect::Postgres => format!("INTERVAL '{} {}s'", self.value, self.unit.emit().to_lowercase()),
+      Dialect::MySql => format!("INTERVAL {} {}", self.value, self.unit.emit()),
+      Dialect::Sqlite => return self.write_modifier(buf, false),
+    };
+    buf.push_str(&sql);
   }
 }
 
-impl SubqueryExpression {
-  pub fn emit(&self) -> String {
-    format!("({})", self.stmt.to_sql())
+impl IntervalLiteral {
+  // sqlite only understands date modifiers, and those have no week unit
+  fn write_modifier(&self, buf: &mut String, negated: bool) {
+    let value = self.value.parse::<f64>().unwrap_or_default();
+    let value = if negated { -value } else { value };
+    let modifier = match self.unit {
+      IntervalUnit::Week => format!("'{:+} days'", value * 7.0),
+      _ => format!("'{:+} {}s'", value, self.unit.emit().to_lowercase()),
+    };
+    buf.push_str(&modifier);
   }
 }
 
-impl SelectExpression {
-  pub fn emit(&self) -> String {
-    let expr = self.expression.emit();
-    let alias = self.alias.as_ref().map_or(String::new(), |a| format!(" AS {}", a.lexeme.as_ref().unwrap()));
-    format!("{}{}", expr, alias)
+impl IntervalUnit {
+  pub fn emit(&self) -> &str {
+    match self {
+      IntervalUnit::Second => "SECOND",
+      IntervalUnit::Minute => "MINUTE",
+      IntervalUnit::Hour => "HOUR",
+      IntervalUnit::Day => "DAY",
+      IntervalUnit::Week => "WEEK",
+      IntervalUnit::Month => "MONTH",
+      IntervalUnit::Year => "YEAR",
+    }
+  }
+}
+
+impl ToSql for FunctionCallExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str(self.function_name.lexeme.as_ref().unwrap());
+    buf.push('(');
+    write_list(buf, &self.arguments, dialect, opts);
+    buf.push(')');
+  }
+}
+
+impl ToSql for CastExpression {
+  // only postgres has `::`, every dialect understands CAST
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    match dialect {
+      // `a + b::int` would cast only `b`
+      Dialect::Postgres if matches!(*self.expression, Expression::Condition(_)) => {
+        buf.push('(');
+        self.expression.write_sql(buf, dialect, opts);
+        buf.push_str(")::");
+      }
+      Dialect::Postgres => {
+        self.expression.write_sql(buf, dialect, opts);
+        buf.push_str("::");
+      }
+      _ => {
+        buf.push_str("CAST(");
+        self.expression.write_sql(buf, dialect, opts);
+        buf.push_str(" AS ");
+      }
+    }
+    buf.push_str(self.type_name());
+    if dialect != Dialect::Postgres {
+      buf.push(')');
+    }
+  }
+}
+
+impl ToSql for WindowFunctionExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    self.function.write_sql(buf, dialect, opts);
+    buf.push_str(" OVER ");
+    match &self.window_name {
+      Some(name) => buf.push_str(name.lexeme.as_ref().unwrap()),
+      None => self.spec.write_sql(buf, dialect, opts),
+    }
+  }
+}
+
+impl ToSql for CopyStatement {
+  // the same postgres syntax for every dialect, the checker warns outside postgres
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("COPY ");
+    buf.push_str(self.table.lexeme.as_ref().unwrap());
+    buf.push_str(match self.direction {
+      CopyDirection::To => " TO ",
+      CopyDirection::From => " FROM ",
+    });
+    match &self.target {
+      CopyTarget::File(file) => file.write_sql(buf, dialect, opts),
+      CopyTarget::Stdin => buf.push_str("STDIN"),
+      CopyTarget::Stdout => buf.push_str("STDOUT"),
+    }
+    let options = self.options.emit();
+    if !options.is_empty() {
+      buf.push_str(" WITH (");
+      buf.push_str(&options.join(", "));
+      buf.push(')');
+    }
+  }
+}
+
+impl ToSql for InsertStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("INSERT INTO ");
+    buf.push_str(self.table.lexeme.as_ref().unwrap());
+    if !self.columns.is_empty() {
+      buf.push_str(" (");
+      write_names(buf, &self.columns);
+      buf.push(')');
+    }
+    buf.push(' ');
+    match &self.source {
+      InsertSource::Values(rows) => {
+        buf.push_str("VALUES ");
+        for (index, row) in rows.iter().enumerate() {
+          if index > 0 {
+            buf.push_str(", ");
+          }
+          buf.push('(');
+          write_list(buf, row, dialect, opts);
+          buf.push(')');
+        }
+      }
+      InsertSource::Select(query) => write_select_first(buf, query, dialect, opts),
+    }
+  }
+}
+
+impl ToSql for TruncateStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, _: &EmitOptions) {
+    buf.push_str(if dialect.has_truncate() { "TRUNCATE TABLE " } else { "DELETE FROM " });
+    buf.push_str(self.table.lexeme.as_ref().unwrap());
+  }
+}
+
+impl ToSql for AlterTableStatement {
+  // mysql has RENAME COLUMN since 8.0, before it only CHANGE COLUMN, which restates the whole definition
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("ALTER TABLE ");
+    buf.push_str(self.table.lexeme.as_ref().unwrap());
+    match &self.action {
+      AlterAction::AddColumn(column) => {
+        buf.push_str(" ADD COLUMN ");
+        column.write_sql(buf, dialect, opts);
+      }
+      AlterAction::DropColumn(column) => {
+        buf.push_str(" DROP COLUMN ");
+        buf.push_str(column.lexeme.as_ref().unwrap());
+      }
+      AlterAction::RenameColumn { from, to } => {
+        buf.push_str(" RENAME COLUMN ");
+        buf.push_str(from.lexeme.as_ref().unwrap());
+        buf.push_str(" TO ");
+        buf.push_str(to.lexeme.as_ref().unwrap());
+      }
+      AlterAction::RenameTable(to) => {
+        buf.push_str(" RENAME TO ");
+        buf.push_str(to.lexeme.as_ref().unwrap());
+      }
+    }
+  }
+}
+
+impl ToSql for CreateIndexStatement {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str(if self.unique { "CREATE UNIQUE INDEX " } else { "CREATE INDEX " });
+    buf.push_str(self.name.lexeme.as_ref().unwrap());
+    buf.push_str(" ON ");
+    buf.push_str(self.table.lexeme.as_ref().unwrap());
+    buf.push_str(" (");
+    write_names(buf, &self.columns);
+    buf.push(')');
+  }
+}
+
+impl ToSql for DropIndexStatement {
+  // index names are per schema outside mysql, where they are per table and the table is needed
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, _: &EmitOptions) {
+    buf.push_str("DROP INDEX ");
+    buf.push_str(self.name.lexeme.as_ref().unwrap());
+    if let (Dialect::MySql, Some(table)) = (dialect, &self.table) {
+      buf.push_str(" ON ");
+      buf.push_str(table.lexeme.as_ref().unwrap());
+    }
+  }
+}
+
+impl ToSql for CommentOnStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("COMMENT ON ");
+    buf.push_str(self.object_type.emit());
+    buf.push(' ');
+    if let Some(table) = &self.table {
+      buf.push_str(table.lexeme.as_ref().unwrap());
+      buf.push('.');
+    }
+    buf.push_str(self.name.lexeme.as_ref().unwrap());
+    buf.push_str(" IS ");
+    self.comment.write_sql(buf, dialect, opts);
+  }
+}
+
+impl ToSql for PragmaStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("PRAGMA ");
+    buf.push_str(self.name());
+    match &self.argument {
+      Some(PragmaArgument::Value(value)) => {
+        buf.push_str(" = ");
+        value.write_sql(buf, dialect, opts);
+      }
+      Some(PragmaArgument::TableArg(table)) => {
+        buf.push('(');
+        buf.push_str(table.lexeme.as_ref().unwrap());
+        buf.push(')');
+      }
+      None => {}
+    }
+  }
+}
+
+impl ToSql for GrantStatement {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str("GRANT ");
+    write_privileges_on(buf, &self.privileges, &self.object);
+    buf.push_str(" TO ");
+    write_names(buf, &self.to);
+    if self.with_grant_option {
+      buf.push_str(" WITH GRANT OPTION");
+    }
+  }
+}
+
+impl ToSql for RevokeStatement {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str("REVOKE ");
+    write_privileges_on(buf, &self.privileges, &self.object);
+    buf.push_str(" FROM ");
+    write_names(buf, &self.from);
+  }
+}
+
+impl ToSql for WithStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str(if self.recursive { "WITH RECURSIVE " } else { "WITH " });
+    for (index, cte) in self.ctes.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(", ");
+      }
+      cte.write_sql(buf, dialect, opts);
+    }
+    buf.push(' ');
+    write_select_first(buf, &self.body, dialect, opts);
+  }
+}
+
+impl ToSql for CommonTableExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str(self.name());
+    if !self.columns.is_empty() {
+      buf.push_str(" (");
+      write_names(buf, &self.columns);
+      buf.push(')');
+    }
+    buf.push_str(" AS (");
+    write_select_first(buf, &self.query, dialect, opts);
+    for union in &self.unions {
+      buf.truncate(buf.trim_end_matches(' ').len());
+      buf.push_str(if union.all { " UNION ALL " } else { " UNION " });
+      union.query.write_sql(buf, dialect, opts);
+    }
+    buf.truncate(buf.trim_end_matches(' ').len());
+    buf.push(')');
+  }
+}
+
+impl ToSql for TransactionStatement {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, _: &EmitOptions) {
+    let keywords = match self.kind {
+      // START TRANSACTION is the standard spelling, postgres and sqlite know BEGIN best
+      TransactionKind::Begin if matches!(dialect, Dialect::Postgres | Dialect::Sqlite) => "BEGIN",
+      TransactionKind::Begin => "START TRANSACTION",
+      TransactionKind::Commit => "COMMIT",
+      TransactionKind::Rollback => "ROLLBACK",
+      TransactionKind::Savepoint => "SAVEPOINT",
+      TransactionKind::ReleaseSavepoint => "RELEASE SAVEPOINT",
+      TransactionKind::RollbackToSavepoint => "ROLLBACK TO SAVEPOINT",
+    };
+    buf.push_str(keywords);
+    if let Some(savepoint) = &self.savepoint {
+      buf.push(' ');
+      buf.push_str(savepoint.lexeme.as_ref().unwrap());
+    }
+  }
+}
+
+fn write_privileges_on(buf: &mut String, privileges: &[Privilege], object: &Token) {
+  buf.push_str(&privileges.iter().map(Privilege::emit).collect::<Vec<_>>().join(", "));
+  buf.push_str(" ON ");
+  buf.push_str(object.lexeme.as_ref().unwrap());
+}
+
+impl ToSql for ColumnDefinition {
+  fn write_sql(&self, buf: &mut String, _: Dialect, _: &EmitOptions) {
+    buf.push_str(self.name.lexeme.as_ref().unwrap());
+    buf.push(' ');
+    buf.push_str(self.data_type.lexeme.as_ref().unwrap());
+    if self.not_null {
+      buf.push_str(" NOT NULL");
+    }
+  }
+}
+
+impl CopyOptions {
+  pub fn emit(&self) -> Vec<String> {
+    let mut options = vec![];
+    if let Some(format) = &self.format {
+      options.push(format!("FORMAT {}", format));
+    }
+    if let Some(delimiter) = self.delimiter {
+      options.push(format!("DELIMITER '{}'", delimiter.to_string().replace('\'', "''")));
+    }
+    if self.header {
+      options.push("HEADER".to_string());
+    }
+    options
+  }
+}
+
+impl ToSql for WindowClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    write_window_definitions(buf, self.definitions.iter(), dialect, opts)
+  }
+}
+
+fn write_window_definitions<'a>(
+  buf: &mut String,
+  definitions: impl Iterator<Item = &'a WindowDefinition>,
+  dialect: Dialect,
+  opts: &EmitOptions,
+) {
+  buf.push_str("WINDOW ");
+  for (index, definition) in definitions.enumerate() {
+    if index > 0 {
+      buf.push_str(", ");
+    }
+    buf.push_str(definition.name());
+    buf.push_str(" AS ");
+    definition.spec.write_sql(buf, dialect, opts);
+  }
+}
+
+// sql wants the WINDOW clause after the rest of the query, wherever the stage was piped in
+fn hoist_windows(buf: &mut String, stmt: &Statement, dialect: Dialect, opts: &EmitOptions) {
+  let definitions = stmt.window_definitions();
+  if definitions.is_empty() || matches!(stmt, Statement::Window(_)) {
+    return;
+  }
+  separate(buf);
+  write_window_definitions(buf, definitions.into_iter(), dialect, opts)
+}
+
+impl ToSql for WindowSpec {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push('(');
+    if !self.partition_by.is_empty() {
+      buf.push_str("PARTITION BY ");
+      write_list(buf, &self.partition_by, dialect, opts);
+    }
+    if !self.order_by.is_empty() {
+      if !self.partition_by.is_empty() {
+        buf.push(' ');
+      }
+      buf.push_str("ORDER BY ");
+      write_list(buf, &self.order_by, dialect, opts);
+    }
+    buf.push(')');
+  }
+}
+
+impl ToSql for QualifyClause {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("QUALIFY ");
+    self.condition.write_sql(buf, dialect, opts);
+  }
+}
+
+impl QualifyClause {
+  // each window function becomes a column of an inner query the outer one filters on,
+  // which leaves those extra `qualify_n` columns in the result
+  pub fn write_with_base(&self, buf: &mut String, base: &Base, dialect: Dialect, opts: &EmitOptions) -> Range {
+    let mut condition = self.condition.clone();
+    let mut windows = vec![];
+    condition.extract_windows(&mut windows);
+    let columns = |buf: &mut String| {
+      buf.push('*');
+      for (name, window) in &windows {
+        buf.push_str(", ");
+        window.write_sql(buf, dialect, opts);
+        buf.push_str(" AS ");
+        buf.push_str(name);
+      }
+    };
+    buf.push_str("SELECT * FROM (");
+    write_select_from(buf, columns, base);
+    buf.push_str(") AS qualified WHERE ");
+    condition.write_sql(buf, dialect, opts);
+    self.get_range()
+  }
+}
+
+impl Expression {
+  // swaps every window function for a `qualify_n` column, collecting the functions in order
+  fn extract_windows(&mut self, windows: &mut Vec<(String, WindowFunctionExpression)>) {
+    match self {
+      Expression::Window(window) => {
+        let name = format!("qualify_{}", windows.len() + 1);
+        let column = Token::new(TokenType::Identifier, Some(name.clone()), window.get_range());
+        windows.push((name, window.clone()));
+        *self = Expression::create_column(None, column);
+      }
+      Expression::Condition(condition) => {
+        condition.left.extract_windows(windows);
+        condition.right.extract_windows(windows);
+      }
+      Expression::In(in_) => in_.expression.extract_windows(windows),
+      Expression::Not(not) => not.expression.extract_windows(windows),
+      Expression::Cast(cast) => cast.expression.extract_windows(windows),
+      Expression::Tuple(tuple) => tuple.items.iter_mut().for_each(|item| item.extract_windows(windows)),
+      Expression::FunctionCall(call) => {
+        call.arguments.iter_mut().for_each(|argument| argument.extract_windows(windows))
+      }
+      Expression::Column(_) | Expression::Literal(_) | Expression::Subquery(_) | Expression::CurrentTimestamp(_) => {}
+    }
+  }
+}
+
+impl ToSql for CurrentTimestampExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, _: &EmitOptions) {
+    let sql = match (&self.style, dialect) {
+      (TimestampStyle::Now | TimestampStyle::CurrentTimestamp, Dialect::MySql) => "NOW()",
+      (TimestampStyle::Now | TimestampStyle::CurrentTimestamp, _) => "CURRENT_TIMESTAMP",
+      (TimestampStyle::CurrentDate, Dialect::MySql) => "CURDATE()",
+      (TimestampStyle::CurrentDate, _) => "CURRENT_DATE",
+      (TimestampStyle::CurrentTime, Dialect::MySql) => "CURTIME()",
+      (TimestampStyle::CurrentTime, _) => "CURRENT_TIME",
+    };
+    buf.push_str(sql);
+  }
+}
+
+impl ToSql for SubqueryExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push('(');
+    write_select_first(buf, &self.stmt, dialect, opts);
+    buf.push(')');
+  }
+}
+
+// a database reads a query as sql, so the SELECT closing its FROM stages goes in front of them:
+// `FROM t |> WHERE x = 1 |> SELECT id |> LIMIT 3` is `SELECT id FROM t WHERE x = 1 LIMIT 3`,
+// and a chain that never selects reads every column, `FROM t |> WHERE x = 1` is `SELECT * FROM t WHERE x = 1`
+fn write_select_first(buf: &mut String, stmt: &Statement, dialect: Dialect, opts: &EmitOptions) {
+  let start = buf.len();
+  stmt.write_sql(buf, dialect, opts);
+  if buf[start..].starts_with("FROM") {
+    buf.insert_str(start, "SELECT * ");
+  }
+}
+
+impl ToSql for InExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    if let (Expression::Tuple(tuple), false) = (&*self.expression, dialect.has_row_value_list()) {
+      if !matches!(self.values.as_slice(), [Expression::Subquery(_)]) {
+        return self.write_row_equalities(buf, tuple, dialect, opts);
+      }
+    }
+    self.expression.write_sql(buf, dialect, opts);
+    buf.push_str(if self.negated { " NOT IN " } else { " IN " });
+    match self.values.as_slice() {
+      [Expression::Subquery(subquery)] => subquery.write_sql(buf, dialect, opts),
+      values => {
+        buf.push('(');
+        write_list(buf, values, dialect, opts);
+        buf.push(')');
+      }
+    }
+  }
+}
+
+impl InExpression {
+  // `(a, b) IN ((1, 2), (3, 4))` is `((a = 1 AND b = 2) OR (a = 3 AND b = 4))`
+  fn write_row_equalities(&self, buf: &mut String, tuple: &TupleExpression, dialect: Dialect, opts: &EmitOptions) {
+    let rows = self.values.iter().map(|value| {
+      let values = match value {
+        Expression::Tuple(row) => row.items.as_slice(),
+        value => std::slice::from_ref(value),
+      };
+      let equalities = tuple
+        .items
+        .iter()
+        .zip(values)
+        .map(|(item, value)| Expression::create_condition(item.clone(), Operator::Equal, value.clone()));
+      equalities.reduce(|left, right| Expression::create_condition(left, Operator::And, right))
+    });
+    let rows = rows.flatten().reduce(|left, right| Expression::create_condition(left, Operator::Or, right));
+    buf.push_str(if self.negated { "NOT (" } else { "(" });
+    match rows {
+      Some(rows) => rows.write_sql(buf, dialect, opts),
+      // nothing matches an empty list
+      None => buf.push_str("1 = 0"),
+    }
+    buf.push(')');
+  }
+}
+
+impl ToSql for TupleExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push('(');
+    write_list(buf, &self.items, dialect, opts);
+    buf.push(')');
+  }
+}
+
+impl ToSql for NotExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    buf.push_str("NOT (");
+    self.expression.write_sql(buf, dialect, opts);
+    buf.push(')');
+  }
+}
+
+impl ToSql for SelectExpression {
+  fn write_sql(&self, buf: &mut String, dialect: Dialect, opts: &EmitOptions) {
+    self.expression.write_sql(buf, dialect, opts);
+    write_alias(buf, &self.alias);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::Lexer;
+  use crate::parser::Parser;
+  use crate::utils::source::Source;
+
+  fn ansi(raw: &str) -> String {
+    let source = Source::new("test.sql", raw);
+    let mut lexer = Lexer::new(&source);
+    let (program, errors) = Parser::new(&mut lexer).parse();
+    assert!(errors.is_empty(), "`{}` does not parse", raw);
+    program.to_sql(Dialect::Ansi, &EmitOptions::default())
+  }
+
+  fn ansi_with_comments(raw: &str) -> String {
+    let source = Source::new("test.sql", raw);
+    let (program, _) = Parser::new(&mut Lexer::new(&source)).parse();
+    program.to_sql(Dialect::Ansi, &EmitOptions::default().with_comments(&program.comments))
+  }
+
+  // queries the inherent `emit` methods compiled before `ToSql`, with the sql they wrote
+  const EMITTED: &[(&str, &str)] = &[
+    (
+      "FROM sales |> AGGREGATE SUM(amount) AS total_sales GROUP BY customer_id;",
+      "SELECT SUM(amount) AS total_sales FROM sales GROUP BY customer_id",
+    ),
+    (
+      "FROM orders |> JOIN customers ON orders.customer_id = customers.customer_id \
+       |> WHERE orders.total_amount > (SELECT AVG(total_amount) FROM orders) \
+       |> AGGREGATE COUNT(order_id) AS num_orders GROUP BY customers.customer_name;",
+      "SELECT COUNT(order_id) AS num_orders FROM orders JOIN customers ON orders.customer_id = customers.customer_id \
+       WHERE orders.total_amount > (SELECT AVG(total_amount) FROM orders) GROUP BY customers.customer_name",
+    ),
+    (
+      "FROM orders |> JOIN customers ON orders.customer_id = customers.customer_id \
+       |> JOIN products ON orders.product_id = products.product_id |> WHERE products.category != 'discontinued' \
+       |> AGGREGATE SUM(orders.total_amount) AS total_sales GROUP BY customers.customer_name, products.category;",
+      "SELECT SUM(orders.total_amount) AS total_sales FROM orders \
+       JOIN customers ON orders.customer_id = customers.customer_id \
+       JOIN products ON orders.product_id = products.product_id WHERE products.category != 'discontinued' \
+       GROUP BY customers.customer_name, products.category",
+    ),
+  ];
+
+  #[test]
+  fn matches_the_emit_methods() {
+    for (raw, sql) in EMITTED {
+      assert_eq!(ansi(raw), *sql);
+    }
+  }
+
+  #[test]
+  fn piped_subqueries_select_first() {
+    let sql = ansi("FROM u |> WHERE y IN (FROM t |> WHERE x = 1 |> SELECT id) |> SELECT y");
+    assert_eq!(sql, "SELECT y FROM u WHERE y IN (SELECT id FROM t WHERE x = 1)");
+  }
+
+  #[test]
+  fn piped_subqueries_keep_limit_last() {
+    let sql = ansi("FROM u |> WHERE y IN (FROM t |> WHERE x = 1 |> SELECT id |> LIMIT 5) |> SELECT y");
+    assert_eq!(sql, "SELECT y FROM u WHERE y IN (SELECT id FROM t WHERE x = 1 LIMIT 5)");
+    let sql = ansi("FROM u |> WHERE y > (FROM t |> SELECT id |> ORDER BY id DESC |> LIMIT 1) |> SELECT y");
+    assert_eq!(sql, "SELECT y FROM u WHERE y > (SELECT id FROM t ORDER BY id DESC LIMIT 1)");
+  }
+
+  // a stage that cannot be appended to the query before it, with the sql reading that query as a derived table
+  const DERIVED: &[(&str, &str)] = &[
+    ("FROM t |> SELECT a |> WHERE a > 1", "SELECT * FROM (SELECT a FROM t) AS _p1 WHERE a > 1"),
+    ("FROM t |> SELECT a |> SELECT b", "SELECT b FROM (SELECT a FROM t) AS _p1"),
+    (
+      "FROM t |> AGGREGATE COUNT(id) AS n GROUP BY d |> WHERE n > 1",
+      "SELECT * FROM (SELECT COUNT(id) AS n FROM t GROUP BY d) AS _p1 WHERE n > 1",
+    ),
+    (
+      "FROM t |> SELECT a, b |> WHERE a > 1 |> SELECT b |> ORDER BY b",
+      "SELECT b FROM (SELECT a, b FROM t) AS _p1 WHERE a > 1 ORDER BY b ASC",
+    ),
+    ("FROM t |> SELECT a |> SELECT b |> SELECT c", "SELECT c FROM (SELECT b FROM (SELECT a FROM t) AS _p1) AS _p2"),
+    ("FROM t |> SELECT a |> AGGREGATE SUM(a) AS s", "SELECT SUM(a) AS s FROM (SELECT a FROM t) AS _p1"),
+  ];
+
+  #[test]
+  fn stages_after_a_query_read_it_as_a_derived_table() {
+    for (raw, sql) in DERIVED {
+      assert_eq!(ansi(raw), *sql, "`{}`", raw);
+      // a subquery only takes the GROUP BY of an aggregate as a stage of its own
+      let nested = ansi(&format!("FROM u |> WHERE y IN ({}) |> SELECT y", raw.replace(" GROUP BY", " |> GROUP BY")));
+      assert_eq!(nested, format!("SELECT y FROM u WHERE y IN ({})", sql), "`{}` in a subquery", raw);
+    }
+  }
+
+  #[test]
+  fn stages_after_a_query_keep_the_order_of_sql() {
+    let sql = ansi("FROM t |> AGGREGATE COUNT(id) AS n GROUP BY d |> ORDER BY n |> LIMIT 3");
+    assert_eq!(sql, "SELECT COUNT(id) AS n FROM t GROUP BY d ORDER BY n ASC LIMIT 3");
+    let sql = ansi("FROM t |> WHERE a > 1 |> SELECT a |> ORDER BY a |> LIMIT 2");
+    assert_eq!(sql, "SELECT a FROM t WHERE a > 1 ORDER BY a ASC LIMIT 2");
+  }
+
+  #[test]
+  fn keeps_trailing_comments_of_reordered_stages() {
+    let sql = ansi_with_comments("FROM t\n|> WHERE a > 1 -- filter\n|> SELECT a -- note\n|> ORDER BY a -- sorted\n");
+    assert_eq!(sql, "SELECT a FROM t WHERE a > 1 -- filter\n-- note\nORDER BY a ASC -- sorted\n");
+    let sql = ansi_with_comments("FROM t |> SELECT a -- note\n|> WHERE a > 1");
+    assert_eq!(sql, "SELECT * FROM (SELECT a FROM t -- note\n) AS _p1 WHERE a > 1");
+  }
+
+  #[test]
+  fn write_sql_appends_what_to_sql_returns() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground");
+    for entry in std::fs::read_dir(dir).unwrap() {
+      let raw = std::fs::read_to_string(entry.unwrap().path()).unwrap_or_default();
+      let source = Source::new("test.sql", &raw);
+      let (program, _) = Parser::new(&mut Lexer::new(&source)).parse();
+      let dialects = [Dialect::Ansi, Dialect::Postgres, Dialect::MySql, Dialect::Sqlite];
+      let commented = EmitOptions::default().with_comments(&program.comments);
+      for (dialect, opts) in
+        dialects.into_iter().flat_map(|dialect| [(dialect, EmitOptions::default()), (dialect, commented)])
+      {
+        let mut buf = String::from("-- before\n");
+        program.write_sql(&mut buf, dialect, &opts);
+        assert_eq!(buf, format!("-- before\n{}", program.to_sql(dialect, &opts)));
+        // one buffer for every statement holds each one's sql in turn
+        let mut buf = String::new();
+        program.statements.iter().for_each(|stmt| stmt.write_sql(&mut buf, dialect, &opts));
+        assert_eq!(buf, program.statements.iter().map(|stmt| stmt.to_sql(dialect, &opts)).collect::<String>());
+      }
+    }
   }
 }
@@ -0,0 +1,530 @@
+use super::{write_alias, write_names, Dialect, EmitOptions, ToSql};
+use crate::parser::ast::*;
+
+// top-level pipelines get a stage per line, nested ones stay on the line of their parentheses
+const LINE_STAGE: &str = "\n|> ";
+const INLINE_STAGE: &str = " |> ";
+
+impl Program {
+  // the program written back in the pipe language, the canonical form of a spipe file:
+  // `FROM t |> WHERE c |> SELECT a |> AGGREGATE SUM(b) AS total`, where the sql emitter would rewrite the stages.
+  // comments are left out, parsing the output gives the same statements again
+  pub fn pretty_print_spipe(&self) -> String {
+    let mut buf = String::new();
+    let mut end = 0;
+    for (position, query) in self.queries().into_iter().enumerate() {
+      if position > 0 {
+        buf.push_str("\n\n");
+      }
+      for (index, stmt) in query.iter().enumerate() {
+        if index > 0 {
+          buf.push('\n');
+        }
+        write_statement(&mut buf, stmt, LINE_STAGE);
+      }
+      end += query.len();
+      if self.terminated.get(end - 1).copied().unwrap_or_default() {
+        buf.push(';');
+      }
+    }
+    if !buf.is_empty() {
+      buf.push('\n');
+    }
+    buf
+  }
+}
+
+// written the same as ansi sql, the parts holding expressions are written here so they keep their parentheses
+fn write_sql(buf: &mut String, node: &impl ToSql) {
+  node.write_sql(buf, Dialect::Ansi, &EmitOptions::default());
+}
+
+fn write_statement(buf: &mut String, stmt: &Statement, stage: &str) {
+  match stmt {
+    Statement::Pipe(pipe) => {
+      write_statement(buf, &pipe.left, stage);
+      buf.push_str(stage);
+      write_statement(buf, &pipe.right, stage);
+    }
+    Statement::Select(select) => write_select(buf, select),
+    Statement::From(from) => write_from(buf, from),
+    Statement::Where(where_) => {
+      buf.push_str("WHERE ");
+      write_expression(buf, &where_.condition);
+    }
+    Statement::Limit(limit) => write_limit(buf, limit),
+    Statement::Aggregate(aggregate) => {
+      buf.push_str("AGGREGATE ");
+      write_aggregate(buf, aggregate);
+    }
+    Statement::Pivot(pivot) => {
+      buf.push_str("PIVOT (");
+      write_aggregate(buf, &pivot.aggregate);
+      buf.push_str(" FOR ");
+      buf.push_str(pivot.for_col.lexeme.as_ref().unwrap());
+      buf.push_str(" IN (");
+      write_operands(buf, &pivot.in_values);
+      buf.push_str("))");
+    }
+    Statement::Qualify(qualify) => {
+      buf.push_str("QUALIFY ");
+      write_expression(buf, &qualify.condition);
+    }
+    Statement::Window(window) => {
+      buf.push_str("WINDOW ");
+      for (index, definition) in window.definitions.iter().enumerate() {
+        if index > 0 {
+          buf.push_str(", ");
+        }
+        buf.push_str(definition.name());
+        buf.push_str(" AS ");
+        write_window_spec(buf, &definition.spec);
+      }
+    }
+    Statement::Insert(insert) => write_insert(buf, insert, stage),
+    Statement::Truncate(truncate) => {
+      buf.push_str("TRUNCATE TABLE ");
+      buf.push_str(truncate.table.lexeme.as_ref().unwrap());
+    }
+    // the table is dropped from the sql outside mysql, but it is part of the statement
+    Statement::DropIndex(drop) => {
+      buf.push_str("DROP INDEX ");
+      buf.push_str(drop.name.lexeme.as_ref().unwrap());
+      if let Some(table) = &drop.table {
+        buf.push_str(" ON ");
+        buf.push_str(table.lexeme.as_ref().unwrap());
+      }
+    }
+    Statement::Pragma(pragma) => {
+      buf.push_str("PRAGMA ");
+      buf.push_str(pragma.name());
+      match &pragma.argument {
+        Some(PragmaArgument::Value(value)) => {
+          buf.push_str(" = ");
+          write_operand(buf, value);
+        }
+        Some(PragmaArgument::TableArg(table)) => {
+          buf.push('(');
+          buf.push_str(table.lexeme.as_ref().unwrap());
+          buf.push(')');
+        }
+        None => {}
+      }
+    }
+    Statement::Transaction(transaction) => write_transaction(buf, transaction),
+    Statement::With(with) => write_with(buf, with, stage),
+    Statement::Expression(expression) => write_expression(buf, expression),
+    Statement::Join(join) => write_sql(buf, join),
+    Statement::GroupBy(group_by) => write_sql(buf, group_by),
+    Statement::Order(order) => write_sql(buf, order),
+    Statement::Unpivot(unpivot) => write_sql(buf, unpivot),
+    Statement::Copy(copy) => write_sql(buf, copy),
+    Statement::AlterTable(alter) => write_sql(buf, alter),
+    Statement::CreateIndex(create) => write_sql(buf, create),
+    Statement::CommentOn(comment) => write_sql(buf, comment),
+    Statement::Grant(grant) => write_sql(buf, grant),
+    Statement::Revoke(revoke) => write_sql(buf, revoke),
+  }
+}
+
+fn write_select(buf: &mut String, select: &SelectStatement) {
+  buf.push_str("SELECT ");
+  match &select.distinct {
+    Distinct::None => {}
+    Distinct::All => buf.push_str("DISTINCT "),
+    Distinct::On(expressions) => {
+      buf.push_str("DISTINCT ON (");
+      write_operands(buf, expressions);
+      buf.push_str(") ");
+    }
+  }
+  for (index, expression) in select.expressions.iter().enumerate() {
+    if index > 0 {
+      buf.push_str(", ");
+    }
+    write_operand(buf, &expression.expression);
+    write_alias(buf, &expression.alias);
+  }
+  if let Some(from) = &select.from {
+    buf.push(' ');
+    write_from(buf, from);
+  }
+}
+
+fn write_from(buf: &mut String, from: &FromClause) {
+  buf.push_str("FROM ");
+  buf.push_str(from.table.lexeme.as_ref().unwrap());
+  write_alias(buf, &from.alias);
+  if let Some(sample) = &from.sample {
+    buf.push_str(" TABLESAMPLE ");
+    buf.push_str(sample.method.emit(Dialect::Ansi));
+    buf.push('(');
+    write_operand(buf, &sample.size);
+    buf.push(')');
+  }
+}
+
+// the comma form is kept, it is how the limit was written
+fn write_limit(buf: &mut String, limit: &LimitClause) {
+  buf.push_str("LIMIT ");
+  buf.push_str(&limit.count.raw);
+  if let Some(offset) = &limit.offset {
+    buf.push_str(if limit.comma_offset { ", " } else { " OFFSET " });
+    buf.push_str(&offset.raw);
+  }
+}
+
+// SUM(amount) FILTER (WHERE paid) AS total, PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY salary ASC) AS median
+fn write_aggregate(buf: &mut String, aggregate: &AggregateClause) {
+  buf.push_str(aggregate.function.emit());
+  buf.push('(');
+  write_operand(buf, &aggregate.argument);
+  buf.push(')');
+  if let Some(within_group) = &aggregate.within_group {
+    buf.push_str(" WITHIN GROUP (");
+    write_sql(buf, &within_group.order);
+    buf.push(')');
+  }
+  if let Some(filter) = &aggregate.filter {
+    buf.push_str(" FILTER (WHERE ");
+    write_expression(buf, filter);
+    buf.push(')');
+  }
+  write_alias(buf, &aggregate.alias);
+}
+
+fn write_insert(buf: &mut String, insert: &InsertStatement, stage: &str) {
+  buf.push_str("INSERT INTO ");
+  buf.push_str(insert.table.lexeme.as_ref().unwrap());
+  if !insert.columns.is_empty() {
+    buf.push_str(" (");
+    write_names(buf, &insert.columns);
+    buf.push(')');
+  }
+  buf.push(' ');
+  match &insert.source {
+    InsertSource::Values(rows) => {
+      buf.push_str("VALUES ");
+      for (index, row) in rows.iter().enumerate() {
+        if index > 0 {
+          buf.push_str(", ");
+        }
+        buf.push('(');
+        write_operands(buf, row);
+        buf.push(')');
+      }
+    }
+    InsertSource::Select(query) => write_statement(buf, query, stage),
+  }
+}
+
+fn write_transaction(buf: &mut String, transaction: &TransactionStatement) {
+  buf.push_str(match transaction.kind {
+    TransactionKind::Begin => "BEGIN",
+    TransactionKind::Commit => "COMMIT",
+    TransactionKind::Rollback => "ROLLBACK",
+    TransactionKind::Savepoint => "SAVEPOINT",
+    TransactionKind::ReleaseSavepoint => "RELEASE SAVEPOINT",
+    TransactionKind::RollbackToSavepoint => "ROLLBACK TO SAVEPOINT",
+  });
+  if let Some(savepoint) = &transaction.savepoint {
+    buf.push(' ');
+    buf.push_str(savepoint.lexeme.as_ref().unwrap());
+  }
+}
+
+// each cte on the line of its name, the query reading them below
+fn write_with(buf: &mut String, with: &WithStatement, stage: &str) {
+  buf.push_str(if with.recursive { "WITH RECURSIVE " } else { "WITH " });
+  for (index, cte) in with.ctes.iter().enumerate() {
+    if index > 0 {
+      buf.push_str(",\n");
+    }
+    buf.push_str(cte.name());
+    if !cte.columns.is_empty() {
+      buf.push_str(" (");
+      write_names(buf, &cte.columns);
+      buf.push(')');
+    }
+    buf.push_str(" AS (");
+    write_statement(buf, &cte.query, INLINE_STAGE);
+    for union in &cte.unions {
+      buf.push_str(if union.all { " UNION ALL " } else { " UNION " });
+      write_statement(buf, &union.query, INLINE_STAGE);
+    }
+    buf.push(')');
+  }
+  buf.push_str(if stage == LINE_STAGE { "\n" } else { " " });
+  write_statement(buf, &with.body, stage);
+}
+
+fn write_window_spec(buf: &mut String, spec: &WindowSpec) {
+  buf.push('(');
+  if !spec.partition_by.is_empty() {
+    buf.push_str("PARTITION BY ");
+    write_operands(buf, &spec.partition_by);
+  }
+  if !spec.order_by.is_empty() {
+    if !spec.partition_by.is_empty() {
+      buf.push(' ');
+    }
+    buf.push_str("ORDER BY ");
+    for (index, column) in spec.order_by.iter().enumerate() {
+      if index > 0 {
+        buf.push_str(", ");
+      }
+      write_sql(buf, column);
+    }
+  }
+  buf.push(')');
+}
+
+// where a condition is expected: WHERE, QUALIFY, FILTER and inside parentheses
+fn write_expression(buf: &mut String, expression: &Expression) {
+  match expression {
+    Expression::Column(column) => write_sql(buf, column),
+    Expression::Literal(literal) => write_literal(buf, literal),
+    Expression::Condition(condition) => write_condition(buf, condition),
+    Expression::FunctionCall(call) => write_call(buf, call),
+    Expression::Subquery(subquery) => {
+      buf.push('(');
+      write_statement(buf, &subquery.stmt, INLINE_STAGE);
+      buf.push(')');
+    }
+    Expression::CurrentTimestamp(timestamp) => buf.push_str(match timestamp.style {
+      TimestampStyle::Now => "NOW()",
+      TimestampStyle::CurrentTimestamp => "CURRENT_TIMESTAMP",
+      TimestampStyle::CurrentDate => "CURRENT_DATE",
+      TimestampStyle::CurrentTime => "CURRENT_TIME",
+    }),
+    Expression::In(in_) => {
+      write_grouped(buf, &in_.expression, |operand| is_comparison(operand) || operand.is_predicate());
+      buf.push_str(if in_.negated { " NOT IN " } else { " IN " });
+      match in_.values.as_slice() {
+        [subquery @ Expression::Subquery(_)] => write_expression(buf, subquery),
+        values => {
+          buf.push('(');
+          write_operands(buf, values);
+          buf.push(')');
+        }
+      }
+    }
+    Expression::Not(not) => {
+      buf.push_str("NOT (");
+      write_expression(buf, &not.expression);
+      buf.push(')');
+    }
+    Expression::Window(window) => {
+      write_call(buf, &window.function);
+      buf.push_str(" OVER ");
+      match &window.window_name {
+        Some(name) => buf.push_str(name.lexeme.as_ref().unwrap()),
+        None => write_window_spec(buf, &window.spec),
+      }
+    }
+    Expression::Tuple(tuple) => {
+      buf.push('(');
+      for (index, item) in tuple.items.iter().enumerate() {
+        if index > 0 {
+          buf.push_str(", ");
+        }
+        write_expression(buf, item);
+      }
+      buf.push(')');
+    }
+    Expression::Cast(cast) => match cast.style {
+      CastStyle::CastFunction => {
+        buf.push_str("CAST(");
+        write_operand(buf, &cast.expression);
+        buf.push_str(" AS ");
+        buf.push_str(cast.type_name());
+        buf.push(')');
+      }
+      // `::` binds to a single operand, anything wider is parenthesized
+      CastStyle::DoubleColon => {
+        write_grouped(buf, &cast.expression, |operand| matches!(operand, Expression::Condition(_) | Expression::In(_)));
+        buf.push_str("::");
+        buf.push_str(cast.type_name());
+      }
+    },
+  }
+}
+
+// a select column, an argument or a value: comparisons, AND and OR only parse here inside parentheses
+fn write_operand(buf: &mut String, expression: &Expression) {
+  write_grouped(buf, expression, is_comparison);
+}
+
+fn write_operands(buf: &mut String, expressions: &[Expression]) {
+  for (index, expression) in expressions.iter().enumerate() {
+    if index > 0 {
+      buf.push_str(", ");
+    }
+    write_operand(buf, expression);
+  }
+}
+
+fn write_grouped(buf: &mut String, expression: &Expression, grouped: impl Fn(&Expression) -> bool) {
+  if grouped(expression) {
+    buf.push('(');
+    write_expression(buf, expression);
+    buf.push(')');
+  } else {
+    write_expression(buf, expression);
+  }
+}
+
+// a comparison or the AND and OR above them
+fn is_comparison(expression: &Expression) -> bool {
+  matches!(expression, Expression::Condition(condition) if condition.operator.precedence() <= 3)
+}
+
+// comparisons do not chain and IN or NOT IN end an operand, so both are parenthesized under an operator
+fn write_condition(buf: &mut String, condition: &ConditionExpression) {
+  let precedence = condition.operator.precedence();
+  let binds_looser = |operand: &Expression, equal: bool| match operand {
+    Expression::Condition(inner) => {
+      let inner = inner.operator.precedence();
+      inner < precedence || inner == precedence && (equal || precedence == 3)
+    }
+    Expression::In(_) | Expression::Not(_) => precedence > 2,
+    _ => false,
+  };
+  write_grouped(buf, &condition.left, |operand| binds_looser(operand, false));
+  buf.push(' ');
+  buf.push_str(condition.operator.emit());
+  buf.push(' ');
+  // the operators group from the left, so an equal one on the right was grouped on purpose
+  write_grouped(buf, &condition.right, |operand| binds_looser(operand, true));
+}
+
+fn write_call(buf: &mut String, call: &FunctionCallExpression) {
+  buf.push_str(call.function_name.lexeme.as_ref().unwrap());
+  buf.push('(');
+  write_operands(buf, &call.arguments);
+  buf.push(')');
+}
+
+// there is no escaping inside a string, one holding a `'` is written between `"`
+fn write_literal(buf: &mut String, literal: &Literal) {
+  match literal {
+    Literal::String(string) if string.value.contains('\'') => {
+      buf.push('"');
+      buf.push_str(&string.value);
+      buf.push('"');
+    }
+    Literal::Interval(interval) => {
+      buf.push_str("INTERVAL '");
+      buf.push_str(&interval.value);
+      buf.push_str("' ");
+      buf.push_str(interval.unit.emit());
+    }
+    _ => write_sql(buf, literal),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::Value;
+
+  use crate::lexer::Lexer;
+  use crate::parser::ast::Program;
+  use crate::parser::Parser;
+  use crate::utils::source::Source;
+
+  // where the generated chains read from
+  const SOURCES: &[&str] = &["FROM t", "FROM t AS x", "FROM t TABLESAMPLE SYSTEM(10)"];
+
+  // the stages a generated chain is built from, each one on its own and followed by every other
+  const STAGES: &[&str] = &[
+    "WHERE a = 1 AND (b > 2 OR NOT (c <= 3))",
+    "WHERE t.price * (1 + t.tax) - 2 / 3 % 4 > 10",
+    "WHERE id IN (1, 2) AND name NOT IN (SELECT name FROM banned)",
+    "WHERE (a, b) IN ((1, 'x'), (2, 'y'))",
+    "WHERE total > (FROM orders |> WHERE paid = TRUE |> SELECT AVG(total))",
+    "WHERE created_at > CURRENT_TIMESTAMP - INTERVAL '1' DAY AND x::int = 2",
+    "JOIN u AS v ON t.id = v.t_id",
+    "SELECT DISTINCT a AS x, CAST(b AS numeric(10, 2)) AS y, COUNT(id) AS n",
+    "SELECT a, (b > 1) AS big, 1.5 AS f",
+    "SELECT a, SUM(b) OVER w AS running",
+    "WINDOW w AS (PARTITION BY a ORDER BY b)",
+    "AGGREGATE SUM(price * quantity) AS total",
+    "AGGREGATE COUNT(id) FILTER (WHERE status = 'paid') AS paid",
+    "AGGREGATE PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY salary) AS median",
+    "QUALIFY ROW_NUMBER() OVER (PARTITION BY a ORDER BY b DESC) = 1",
+    "PIVOT (SUM(amount) FOR quarter IN ('q1', 'q2'))",
+    "UNPIVOT (amount FOR quarter IN (q1, q2))",
+    "ORDER BY a DESC, b",
+    "ORDER BY a LIMIT 10 OFFSET 5",
+    "LIMIT 3",
+  ];
+
+  fn parse(raw: &str) -> Program {
+    let source = Source::new("test.sql", raw);
+    let mut lexer = Lexer::new(&source);
+    let (program, errors) = Parser::new(&mut lexer).parse();
+    assert!(errors.is_empty(), "`{}` does not parse: {:?}", raw, errors);
+    program
+  }
+
+  // the program without what the printer does not keep: where each node was and the comments
+  fn shape(program: &Program) -> Value {
+    fn strip(value: &mut Value) {
+      match value {
+        Value::Object(object) => {
+          ["range", "comments", "anchor"].iter().for_each(|key| drop(object.remove(*key)));
+          object.values_mut().for_each(strip);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip),
+        _ => {}
+      }
+    }
+    let mut value = serde_json::to_value(program).unwrap();
+    strip(&mut value);
+    value
+  }
+
+  fn assert_round_trips(raw: &str) {
+    let program = parse(raw);
+    let printed = program.pretty_print_spipe();
+    assert_eq!(shape(&parse(&printed)), shape(&program), "`{}` printed as `{}`", raw, printed);
+  }
+
+  #[test]
+  fn generated_chains_round_trip() {
+    for source in SOURCES {
+      assert_round_trips(&format!("{};", source));
+      for first in STAGES {
+        assert_round_trips(&format!("{} |> {};", source, first));
+        STAGES.iter().for_each(|second| assert_round_trips(&format!("{} |> {} |> {};", source, first, second)));
+      }
+    }
+  }
+
+  #[test]
+  fn playground_files_round_trip() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground");
+    for entry in std::fs::read_dir(dir).unwrap() {
+      let path = entry.unwrap().path();
+      let raw = std::fs::read_to_string(&path).unwrap_or_default();
+      let source = Source::new("test.sql", &raw);
+      // the files showing off syntax errors have nothing to print
+      let (_, errors) = Parser::new(&mut Lexer::new(&source)).parse();
+      if path.extension().is_some_and(|extension| extension == "sql") && errors.is_empty() {
+        assert_round_trips(&raw);
+      }
+    }
+  }
+
+  #[test]
+  fn prints_one_stage_per_line() {
+    let raw =
+      "-- paid orders\nFROM orders |> WHERE id IN (FROM paid |> SELECT id) |> SELECT id, total;\n\nFROM t |> LIMIT 3";
+    let printed = parse(raw).pretty_print_spipe();
+    let expected = "FROM orders\n|> WHERE id IN (FROM paid |> SELECT id)\n|> SELECT id, total;\n\nFROM t\n|> LIMIT 3\n";
+    assert_eq!(printed, expected);
+    // the canonical form prints as itself
+    assert_eq!(parse(&printed).pretty_print_spipe(), printed);
+    assert_round_trips(raw);
+  }
+}
@@ -0,0 +1,262 @@
+use std::str::FromStr;
+
+use super::Dialect;
+use crate::parser::ast::*;
+
+// version of the database the sql is for, e.g. `3.44` or `16.2.1`
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TargetVersion(pub u32, pub u32, pub u32);
+
+impl FromStr for TargetVersion {
+  type Err = String;
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let parts = text.trim().split('.').map(|part| part.parse::<u32>()).collect::<Result<Vec<_>, _>>();
+    match parts.as_deref() {
+      Ok([major]) => Ok(TargetVersion(*major, 0, 0)),
+      Ok([major, minor]) => Ok(TargetVersion(*major, *minor, 0)),
+      Ok([major, minor, patch]) => Ok(TargetVersion(*major, *minor, *patch)),
+      _ => Err(format!("expected a version like `3.44` but got `{}`", text)),
+    }
+  }
+}
+
+// syntax that only some versions of a dialect understand
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Feature {
+  StringAgg,   // STRING_AGG(x, sep)
+  NamedWindow, // WINDOW w AS (...) and OVER w
+}
+
+impl Dialect {
+  // the first version with the feature, `None` when every version has it
+  pub fn introduced(&self, feature: Feature) -> Option<TargetVersion> {
+    match (self, feature) {
+      (Dialect::Sqlite, Feature::StringAgg) => Some(TargetVersion(3, 44, 0)),
+      (Dialect::Sqlite, Feature::NamedWindow) => Some(TargetVersion(3, 28, 0)),
+      (Dialect::MySql, Feature::NamedWindow) => Some(TargetVersion(8, 0, 0)),
+      _ => None,
+    }
+  }
+
+  // without a target version the newest one is assumed
+  pub fn supports(&self, feature: Feature, version: Option<TargetVersion>) -> bool {
+    match (self.introduced(feature), version) {
+      (Some(introduced), Some(version)) => version >= introduced,
+      _ => true,
+    }
+  }
+}
+
+impl Program {
+  // rewrites what the target cannot run into an equivalent it can
+  pub fn downgrade_for(&mut self, dialect: &Dialect, version: Option<TargetVersion>) {
+    let inline_windows = !dialect.supports(Feature::NamedWindow, version);
+    for stmt in self.statements.iter_mut() {
+      stmt.downgrade_for(dialect, version);
+      if inline_windows {
+        stmt.inline_windows();
+      }
+    }
+  }
+}
+
+impl Program {
+  // dialects without QUALIFY get it as a filtered subquery
+  pub fn rewrite_qualify(&mut self, dialect: &Dialect) {
+    if dialect.has_qualify() {
+      return;
+    }
+    for stmt in self.statements.iter_mut() {
+      // the window functions move into a subquery the hoisted WINDOW clause cannot reach
+      if stmt.rewrite_qualify() {
+        stmt.inline_windows();
+      }
+    }
+  }
+}
+
+impl Statement {
+  // whether the chain had a QUALIFY to rewrite
+  fn rewrite_qualify(&mut self) -> bool {
+    match self {
+      Statement::Qualify(qualify) => {
+        qualify.rewrite = true;
+        true
+      }
+      Statement::Pipe(pipe) => pipe.left.rewrite_qualify() | pipe.right.rewrite_qualify(),
+      // the query's windows are inlined on its own, its WINDOW stages never reach the INSERT
+      Statement::Insert(InsertStatement { source: InsertSource::Select(query), .. }) => {
+        if query.rewrite_qualify() {
+          query.inline_windows();
+        }
+        false
+      }
+      Statement::With(with) => {
+        for query in with.queries_mut() {
+          if query.rewrite_qualify() {
+            query.inline_windows();
+          }
+        }
+        false
+      }
+      _ => false,
+    }
+  }
+
+  // every OVER w takes the spec its WINDOW stage gave `w`, and those stages are dropped
+  fn inline_windows(&mut self) {
+    let definitions = self.window_definitions().into_iter().cloned().collect::<Vec<_>>();
+    if definitions.is_empty() {
+      return;
+    }
+    self.drop_window_stages();
+    self.resolve_windows(&definitions);
+  }
+
+  fn drop_window_stages(&mut self) {
+    let Statement::Pipe(pipe) = self else {
+      return;
+    };
+    pipe.left.drop_window_stages();
+    if matches!(*pipe.right, Statement::Window(_)) {
+      let left = (*pipe.left).clone();
+      *self = left;
+    }
+  }
+
+  // window functions are only allowed in the projection, QUALIFY and ORDER BY
+  fn resolve_windows(&mut self, definitions: &[WindowDefinition]) {
+    match self {
+      Statement::Select(select) => {
+        select.expressions.iter_mut().for_each(|expr| expr.expression.resolve_windows(definitions))
+      }
+      Statement::Qualify(qualify) => qualify.condition.resolve_windows(definitions),
+      Statement::Order(order) => order.columns.iter_mut().for_each(|order| order.column.resolve_windows(definitions)),
+      Statement::Pipe(pipe) => {
+        pipe.left.resolve_windows(definitions);
+        pipe.right.resolve_windows(definitions);
+      }
+      Statement::Expression(expression) => expression.resolve_windows(definitions),
+      _ => {}
+    }
+  }
+
+  fn downgrade_for(&mut self, dialect: &Dialect, version: Option<TargetVersion>) {
+    match self {
+      Statement::Select(select) => {
+        select.expressions.iter_mut().for_each(|expr| expr.expression.downgrade_for(dialect, version))
+      }
+      Statement::Join(join) => {
+        join.on.left.downgrade_for(dialect, version);
+        join.on.right.downgrade_for(dialect, version);
+      }
+      Statement::Where(where_clause) => where_clause.condition.downgrade_for(dialect, version),
+      Statement::Qualify(qualify) => qualify.condition.downgrade_for(dialect, version),
+      Statement::GroupBy(group_by) => {
+        group_by.expressions_mut().into_iter().for_each(|column| column.downgrade_for(dialect, version))
+      }
+      Statement::Order(order) => {
+        order.columns.iter_mut().for_each(|order| order.column.downgrade_for(dialect, version))
+      }
+      Statement::Aggregate(agg) => {
+        // sqlite's GROUP_CONCAT(x, sep) takes the same arguments as STRING_AGG
+        if agg.function == AggregateFn::StringAgg && !dialect.supports(Feature::StringAgg, version) {
+          agg.function = AggregateFn::GroupConcat;
+        }
+        agg.argument.downgrade_for(dialect, version);
+        if let Some(filter) = &mut agg.filter {
+          filter.downgrade_for(dialect, version);
+        }
+        if let Some(within_group) = &mut agg.within_group {
+          within_group.order.columns.iter_mut().for_each(|key| key.column.downgrade_for(dialect, version));
+        }
+      }
+      Statement::Pivot(pivot) => {
+        pivot.aggregate.argument.downgrade_for(dialect, version);
+        pivot.in_values.iter_mut().for_each(|value| value.downgrade_for(dialect, version));
+      }
+      Statement::Pipe(pipe) => {
+        pipe.left.downgrade_for(dialect, version);
+        pipe.right.downgrade_for(dialect, version);
+      }
+      Statement::Expression(expression) => expression.downgrade_for(dialect, version),
+      Statement::With(with) => with.queries_mut().for_each(|query| query.downgrade_for(dialect, version)),
+      Statement::Insert(insert) => match &mut insert.source {
+        InsertSource::Values(rows) => rows.iter_mut().flatten().for_each(|value| value.downgrade_for(dialect, version)),
+        InsertSource::Select(query) => query.downgrade_for(dialect, version),
+      },
+      Statement::From(_)
+      | Statement::Limit(_)
+      | Statement::Unpivot(_)
+      | Statement::Window(_)
+      | Statement::Copy(_)
+      | Statement::Truncate(_)
+      | Statement::AlterTable(_)
+      | Statement::CreateIndex(_)
+      | Statement::DropIndex(_)
+      | Statement::CommentOn(_)
+      | Statement::Pragma(_)
+      | Statement::Grant(_)
+      | Statement::Revoke(_)
+      | Statement::Transaction(_) => {}
+    }
+  }
+}
+
+impl Expression {
+  // a subquery names its own windows, so it is left alone
+  fn resolve_windows(&mut self, definitions: &[WindowDefinition]) {
+    match self {
+      Expression::Window(window) => {
+        let name = window.window_name.as_ref().and_then(|name| name.lexeme.as_deref());
+        if let Some(definition) = definitions.iter().find(|definition| Some(definition.name()) == name) {
+          window.spec = definition.spec.clone();
+          window.window_name = None;
+        }
+        window.function.arguments.iter_mut().for_each(|argument| argument.resolve_windows(definitions));
+      }
+      Expression::FunctionCall(call) => {
+        call.arguments.iter_mut().for_each(|argument| argument.resolve_windows(definitions))
+      }
+      Expression::Condition(condition) => {
+        condition.left.resolve_windows(definitions);
+        condition.right.resolve_windows(definitions);
+      }
+      Expression::In(in_) => in_.expression.resolve_windows(definitions),
+      Expression::Not(not) => not.expression.resolve_windows(definitions),
+      Expression::Cast(cast) => cast.expression.resolve_windows(definitions),
+      Expression::Tuple(tuple) => tuple.items.iter_mut().for_each(|item| item.resolve_windows(definitions)),
+      Expression::Column(_) | Expression::Literal(_) | Expression::CurrentTimestamp(_) | Expression::Subquery(_) => {}
+    }
+  }
+
+  fn downgrade_for(&mut self, dialect: &Dialect, version: Option<TargetVersion>) {
+    match self {
+      Expression::FunctionCall(call) => {
+        let is_string_agg =
+          call.function_name.lexeme.as_deref().is_some_and(|name| name.eq_ignore_ascii_case("STRING_AGG"));
+        if is_string_agg && !dialect.supports(Feature::StringAgg, version) {
+          call.function_name.lexeme = Some(AggregateFn::GroupConcat.emit().to_string());
+        }
+        call.arguments.iter_mut().for_each(|argument| argument.downgrade_for(dialect, version));
+      }
+      Expression::Condition(condition) => {
+        condition.left.downgrade_for(dialect, version);
+        condition.right.downgrade_for(dialect, version);
+      }
+      Expression::In(in_) => {
+        in_.expression.downgrade_for(dialect, version);
+        in_.values.iter_mut().for_each(|value| value.downgrade_for(dialect, version));
+      }
+      Expression::Not(not) => not.expression.downgrade_for(dialect, version),
+      Expression::Cast(cast) => cast.expression.downgrade_for(dialect, version),
+      Expression::Tuple(tuple) => tuple.items.iter_mut().for_each(|item| item.downgrade_for(dialect, version)),
+      Expression::Window(window) => {
+        window.function.arguments.iter_mut().for_each(|argument| argument.downgrade_for(dialect, version))
+      }
+      Expression::Subquery(subquery) => subquery.stmt.downgrade_for(dialect, version),
+      Expression::Column(_) | Expression::Literal(_) | Expression::CurrentTimestamp(_) => {}
+    }
+  }
+}
@@ -1,4 +1,10 @@
-use crate::lexer::TokenType;
+use crate::diagnostics::maneger::{Diagnostic, Severity};
+use crate::lexer::{Lexer, Token, TokenType};
+use crate::parser::ast::{
+  Expression, InsertSource, InsertStatement, Operator, SelectStatement, Statement, WhereClause,
+};
+use crate::parser::Parser;
+use crate::utils::source::Source;
 use std::fmt;
 use std::fmt::Display;
 
@@ -48,7 +54,11 @@ impl Display for TokenType {
       TokenType::LeftBracket => write!(f, "["),
       TokenType::RightBracket => write!(f, "]"),
       TokenType::Dot => write!(f, "."),
+      TokenType::Colon => write!(f, ":"),
+      TokenType::DoubleColon => write!(f, "::"),
       TokenType::Comment => write!(f, "--"),
+      TokenType::TrailingComment => write!(f, "--"),
+      TokenType::Error => write!(f, "error"),
       TokenType::EOF => write!(f, "EOF"),
       TokenType::Count => write!(f, "COUNT"),
       TokenType::Sum => write!(f, "SUM"),
@@ -67,6 +77,19 @@ impl Display for TokenType {
       TokenType::Table => write!(f, "TABLE"),
       TokenType::Alter => write!(f, "ALTER"),
       TokenType::Drop => write!(f, "DROP"),
+      TokenType::Truncate => write!(f, "TRUNCATE"),
+      TokenType::Index => write!(f, "INDEX"),
+      TokenType::Unique => write!(f, "UNIQUE"),
+      TokenType::CommentOn => write!(f, "COMMENT"),
+      TokenType::Pragma => write!(f, "PRAGMA"),
+      TokenType::Grant => write!(f, "GRANT"),
+      TokenType::Revoke => write!(f, "REVOKE"),
+      TokenType::Begin => write!(f, "BEGIN"),
+      TokenType::Commit => write!(f, "COMMIT"),
+      TokenType::Rollback => write!(f, "ROLLBACK"),
+      TokenType::Savepoint => write!(f, "SAVEPOINT"),
+      TokenType::Release => write!(f, "RELEASE"),
+      TokenType::Recursive => write!(f, "RECURSIVE"),
       TokenType::Null => write!(f, "NULL"),
       TokenType::Is => write!(f, "IS"),
       TokenType::Like => write!(f, "LIKE"),
@@ -80,6 +103,32 @@ impl Display for TokenType {
       TokenType::InOperator => write!(f, "IN"),
       TokenType::IsOperator => write!(f, "IS"),
       TokenType::Aggregate => write!(f, "AGGREGATE"),
+      TokenType::Interval => write!(f, "INTERVAL"),
+      TokenType::Filter => write!(f, "FILTER"),
+      TokenType::Within => write!(f, "WITHIN"),
+      TokenType::Rollup => write!(f, "ROLLUP"),
+      TokenType::Cube => write!(f, "CUBE"),
+      TokenType::Grouping => write!(f, "GROUPING"),
+      TokenType::Sets => write!(f, "SETS"),
+      TokenType::Pivot => write!(f, "PIVOT"),
+      TokenType::Unpivot => write!(f, "UNPIVOT"),
+      TokenType::For => write!(f, "FOR"),
+      TokenType::Over => write!(f, "OVER"),
+      TokenType::Partition => write!(f, "PARTITION"),
+      TokenType::Qualify => write!(f, "QUALIFY"),
+      TokenType::Window => write!(f, "WINDOW"),
+      TokenType::Cast => write!(f, "CAST"),
+      TokenType::Copy => write!(f, "COPY"),
+      TokenType::To => write!(f, "TO"),
+      TokenType::Stdin => write!(f, "STDIN"),
+      TokenType::Stdout => write!(f, "STDOUT"),
+      TokenType::TableSample => write!(f, "TABLESAMPLE"),
+      TokenType::Bernoulli => write!(f, "BERNOULLI"),
+      TokenType::System => write!(f, "SYSTEM"),
+      TokenType::Block => write!(f, "BLOCK"),
+      TokenType::CurrentTimestamp => write!(f, "CURRENT_TIMESTAMP"),
+      TokenType::CurrentDate => write!(f, "CURRENT_DATE"),
+      TokenType::CurrentTime => write!(f, "CURRENT_TIME"),
       TokenType::StdDev => write!(f, "STDDEV"),
       TokenType::StdDevPop => write!(f, "STDDEV_POP"),
       TokenType::StdDevSamp => write!(f, "STDDEV_SAMP"),
@@ -99,6 +148,490 @@ impl Display for TokenType {
       TokenType::BitOr => write!(f, "BIT_OR"),
       TokenType::BoolAnd => write!(f, "BOOL_AND"),
       TokenType::BoolOr => write!(f, "BOOL_OR"),
+      TokenType::PercentileCont => write!(f, "PERCENTILE_CONT"),
+      TokenType::PercentileDisc => write!(f, "PERCENTILE_DISC"),
+    }
+  }
+}
+
+// Formats the pipe language itself, moving only whitespace so the sql it compiles to stays the same:
+// FROM orders |>WHERE total>100      =>   FROM orders
+//   |> SELECT id ,total;                  |> WHERE total > 100
+//                                         |> SELECT id, total;
+// A subquery stays on one line, a CTE written over several lines gets a block of its own.
+pub fn format_source(source: &Source, style: &FormatStyle) -> Result<String, Vec<Diagnostic>> {
+  let mut lexer = Lexer::new(source);
+  let (program, diagnostics) = Parser::new(&mut lexer).parse();
+  let errors: Vec<Diagnostic> = diagnostics.into_iter().filter(|d| d.severity == Severity::Error).collect();
+  if !errors.is_empty() {
+    return Err(errors);
+  }
+  let mut breaks = vec![];
+  for statement in &program.statements {
+    breaks.push(statement_start(statement));
+    line_starts(statement, &mut breaks);
+  }
+  let tokens: Vec<Token> = Lexer::new(source).take_while(|token| !token.is_eof()).collect();
+  let blocks = blocks(&tokens, &breaks, source.raw);
+  let mut clauses = vec![];
+  program.statements.iter().for_each(|statement| laid_out_clauses(statement, &mut clauses));
+  let lists = match style.align_aliases {
+    true => clauses.iter().filter_map(|clause| select_list(clause, &tokens)).collect(),
+    false => vec![],
+  };
+  let wraps = match style.max_width {
+    Some(_) => clauses.iter().filter_map(|clause| wrap_group(clause, &tokens)).collect(),
+    None => vec![],
+  };
+  let (max_width, raw) = (style.max_width, source.raw);
+  let mut formatter = Formatter { raw, breaks, blocks, lists, wraps, max_width, ..Formatter::default() };
+  for (index, token) in tokens.iter().enumerate() {
+    formatter.write(token, &tokens[index + 1..]);
+  }
+  let formatted = formatter.finish();
+  // only the layout may change, a result that reads back as another program leaves the source as it is
+  let canonical = program.pretty_print_spipe();
+  let reformatted = Source::new(source.path, &formatted);
+  let mut lexer = Lexer::new(&reformatted);
+  if Parser::new(&mut lexer).parse().0.pretty_print_spipe() != canonical {
+    return Ok(source.raw.to_string());
+  }
+  Ok(formatted)
+}
+
+// the layout choices beyond moving whitespace around, all off by default
+#[derive(Debug, Default, Clone)]
+pub struct FormatStyle {
+  pub align_aliases: bool,      // one select column per line, their `AS` lined up
+  pub max_width: Option<usize>, // longer select lists and WHERE conditions are wrapped
+}
+
+// the range of a join starts at its table, the clause itself at JOIN
+fn statement_start(statement: &Statement) -> usize {
+  match statement {
+    Statement::Join(join) => join.range.start,
+    Statement::Pipe(pipe) => statement_start(&pipe.left),
+    _ => statement.get_range().start,
+  }
+}
+
+// where the queries nested in a statement start: each CTE, the query reading them and the rows of an INSERT
+fn line_starts(statement: &Statement, breaks: &mut Vec<usize>) {
+  let queries = match statement {
+    Statement::Pipe(pipe) => return line_starts(&pipe.left, breaks),
+    Statement::With(with) => {
+      let ctes =
+        with.ctes.iter().flat_map(|cte| std::iter::once(&cte.query).chain(cte.unions.iter().map(|union| &union.query)));
+      ctes.chain(std::iter::once(with.body.as_ref())).collect()
+    }
+    Statement::Insert(InsertStatement { source: InsertSource::Select(query), .. }) => vec![query.as_ref()],
+    _ => vec![],
+  };
+  for query in queries {
+    breaks.push(statement_start(query));
+    line_starts(query, breaks);
+  }
+}
+
+// the clauses of every query laid out over lines, the ones nested in expressions stay on one line
+fn laid_out_clauses<'a>(statement: &'a Statement, clauses: &mut Vec<&'a Statement>) {
+  let queries = match statement {
+    Statement::Pipe(pipe) => vec![pipe.left.as_ref(), pipe.right.as_ref()],
+    Statement::With(with) => {
+      let ctes =
+        with.ctes.iter().flat_map(|cte| std::iter::once(&cte.query).chain(cte.unions.iter().map(|union| &union.query)));
+      ctes.chain(std::iter::once(with.body.as_ref())).collect()
+    }
+    Statement::Insert(InsertStatement { source: InsertSource::Select(query), .. }) => vec![query.as_ref()],
+    _ => return clauses.push(statement),
+  };
+  queries.into_iter().for_each(|query| laid_out_clauses(query, clauses));
+}
+
+// a select list with at least two aliases to line up
+fn select_list(clause: &Statement, tokens: &[Token]) -> Option<SelectList> {
+  let Statement::Select(select) = clause else { return None };
+  let aliases: Vec<usize> =
+    select.expressions.iter().filter_map(|item| Some(item.alias.as_ref()?.range.start)).collect();
+  if aliases.len() < 2 {
+    return None;
+  }
+  Some(SelectList { items: select_items(select, tokens), aliases })
+}
+
+// a column's range starts inside the parentheses around it, its line starts at the first `(`
+fn select_items(select: &SelectStatement, tokens: &[Token]) -> Vec<usize> {
+  select.expressions.iter().map(|item| opening_paren(item.range.start, tokens)).collect()
+}
+
+fn opening_paren(start: usize, tokens: &[Token]) -> usize {
+  let mut index = tokens.iter().position(|token| token.range.start == start).unwrap_or_default();
+  while index > 0 && tokens[index - 1].kind == TokenType::LeftParen {
+    index -= 1;
+  }
+  tokens[index].range.start
+}
+
+// where a long select list may wrap, before each column after the first, and a WHERE before its AND and OR
+fn wrap_group(clause: &Statement, tokens: &[Token]) -> Option<WrapGroup> {
+  let (first, breaks) = match clause {
+    Statement::Select(select) => {
+      let items = select_items(select, tokens);
+      (*items.first()?, items[1..].to_vec())
+    }
+    Statement::Where(WhereClause { condition, .. }) => {
+      let mut junctions = vec![];
+      logical_operators(condition, tokens, &mut junctions);
+      (opening_paren(condition.get_range().start, tokens), junctions)
+    }
+    _ => return None,
+  };
+  (!breaks.is_empty()).then_some(WrapGroup { first, breaks })
+}
+
+// the AND and OR tokens joining a condition, those inside parentheses are skipped when writing
+fn logical_operators(condition: &Expression, tokens: &[Token], junctions: &mut Vec<usize>) {
+  let Expression::Condition(condition) = condition else { return };
+  if !matches!(condition.operator, Operator::And | Operator::Or) {
+    return;
+  }
+  logical_operators(&condition.left, tokens, junctions);
+  let (end, start) = (condition.left.get_range().end, condition.right.get_range().start);
+  let operator = tokens.iter().find(|token| {
+    matches!(token.kind, TokenType::And | TokenType::Or) && token.range.start >= end && token.range.end <= start
+  });
+  junctions.extend(operator.map(|operator| operator.range.start));
+  logical_operators(&condition.right, tokens, junctions);
+}
+
+// the `(` of a CTE whose `)` is on a later line, its queries are laid out like top level ones
+fn blocks(tokens: &[Token], breaks: &[usize], raw: &str) -> Vec<usize> {
+  let (mut blocks, mut open) = (vec![], vec![]);
+  for (index, token) in tokens.iter().enumerate() {
+    match token.kind {
+      TokenType::LeftParen => open.push(index),
+      TokenType::RightParen => {
+        let Some(start) = open.pop() else { continue };
+        let query = tokens[start + 1..].iter().find(|next| !next.is_comment());
+        let multiline = raw[tokens[start].range.end..token.range.start].contains('\n');
+        if multiline && query.is_some_and(|query| breaks.contains(&query.range.start)) {
+          blocks.push(tokens[start].range.start);
+        }
+      }
+      _ => {}
+    }
+  }
+  blocks
+}
+
+// how far the lines that carry on a stage are indented, past the `|> ` they follow
+const CONTINUATION: &str = "   ";
+const BLOCK_INDENT: &str = "  ";
+
+// where the columns of a select list and their aliases start
+#[derive(Default)]
+struct SelectList {
+  items: Vec<usize>,
+  aliases: Vec<usize>,
+}
+
+// where a clause's text starts and the tokens a line of it may be broken before
+struct WrapGroup {
+  first: usize,
+  breaks: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Formatter<'a> {
+  raw: &'a str,
+  breaks: Vec<usize>, // where a statement or a query inside one starts
+  blocks: Vec<usize>, // the `(` that open a block
+  text: String,
+  line: String,
+  parens: Vec<bool>,                   // the parentheses open at this point, whether each is a block
+  previous: Option<&'a Token>,         // the last token that is not a comment
+  end: usize,                          // where the last token ended in the source
+  lists: Vec<SelectList>,              // the select lists whose aliases line up
+  aligning: Option<(usize, usize)>,    // the list being written and the column its items start at
+  aliases: Vec<(usize, usize, usize)>, // each `AS` to line up: its list, its line and its column
+  max_width: Option<usize>,
+  wraps: Vec<WrapGroup>,                   // the clauses that may wrap
+  wrapping: Option<(usize, usize, usize)>, // the clause being written, the column it starts at and its parentheses
+  wrap_points: Vec<usize>,                 // where the current line may be broken
+}
+
+impl<'a> Formatter<'a> {
+  fn write(&mut self, token: &'a Token, rest: &[Token]) {
+    let written = self.raw[token.range.start..token.range.end].trim_end();
+    let blank_line = self.raw[self.end..token.range.start].matches('\n').count() > 1;
+    self.end = token.range.end;
+    match token.kind {
+      TokenType::Comment => {
+        self.break_line(blank_line);
+        // a comment before a statement or a stage lines up with it, one inside a stage with the stage's text
+        let next = rest.iter().find(|next| !next.is_comment());
+        let indent = match next {
+          Some(next) if self.aligned_item(next) => " ".repeat(self.aligning.unwrap_or_default().1),
+          next if next.is_none_or(|next| self.starts_line(next)) => self.indent(),
+          _ => self.indent() + CONTINUATION,
+        };
+        self.text.push_str(&format!("{}{}\n", indent, written));
+      }
+      TokenType::TrailingComment => {
+        self.line.push(' ');
+        self.line.push_str(written);
+        self.break_line(false);
+      }
+      _ => {
+        if token.kind == TokenType::RightParen && self.parens.pop() == Some(true) {
+          self.break_line(false);
+        }
+        let continued = !self.line.is_empty();
+        if self.starts_line(token) {
+          self.break_line(blank_line && self.parens.is_empty());
+        }
+        if self.aligned_item(token) {
+          self.break_line(false);
+          self.line.push_str(&" ".repeat(self.aligning.unwrap_or_default().1));
+        } else if self.line.is_empty() {
+          let indent = match self.starts_line(token) || token.kind == TokenType::RightParen {
+            true => self.indent(),
+            false => self.indent() + CONTINUATION,
+          };
+          self.line.push_str(&indent);
+        } else if self.spaced(token) {
+          if token.kind == TokenType::As {
+            self.mark_alias(rest);
+          }
+          self.line.push(' ');
+        }
+        if continued && self.wraps_before(token) {
+          self.wrap_points.push(self.line.trim_end().len());
+        }
+        self.start_list(token);
+        self.start_wrap(token);
+        self.line.push_str(written);
+        if token.kind == TokenType::LeftParen {
+          let block = self.blocks.contains(&token.range.start);
+          self.parens.push(block);
+          if block {
+            self.break_line(false);
+          }
+        }
+        self.previous = Some(token);
+      }
+    }
+  }
+
+  fn finish(mut self) -> String {
+    self.break_line(false);
+    if self.aliases.is_empty() {
+      return self.text;
+    }
+    // each `AS` is pushed to the widest column of its list
+    let mut lines: Vec<String> = self.text.lines().map(String::from).collect();
+    for &(list, line, column) in &self.aliases {
+      let widest = self.aliases.iter().filter(|alias| alias.0 == list).map(|alias| alias.2).max().unwrap_or(column);
+      lines[line].insert_str(column, &" ".repeat(widest - column));
+    }
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+  }
+
+  // the first token of a clause that may wrap sets where its continued lines start
+  fn start_wrap(&mut self, token: &Token) {
+    let laid_out = self.parens.last().is_none_or(|block| *block);
+    if let Some(group) = self.wraps.iter().position(|group| group.first == token.range.start) {
+      self.wrapping = laid_out.then_some((group, self.line.len(), self.parens.len()));
+    }
+  }
+
+  // a column or an AND/OR of the clause being written, outside any parentheses it opened
+  fn wraps_before(&self, token: &Token) -> bool {
+    let Some((group, _, parens)) = self.wrapping else {
+      return false;
+    };
+    parens == self.parens.len() && !self.aligned_item(token) && self.wraps[group].breaks.contains(&token.range.start)
+  }
+
+  // a line too long is filled greedily, each piece going on the line before while it fits
+  fn wrapped(&self) -> Vec<String> {
+    let fits = |line: &str| self.max_width.is_none_or(|width| line.chars().count() <= width);
+    let column = match self.wrapping {
+      Some((_, column, _)) if !self.wrap_points.is_empty() && !fits(self.line.trim_end()) => column,
+      _ => return vec![self.line.clone()],
+    };
+    let mut ends = self.wrap_points.clone();
+    ends.push(self.line.len());
+    let (mut lines, mut start) = (vec![String::new()], 0);
+    for end in ends {
+      let piece = &self.line[start..end];
+      let last = lines.last_mut().expect("there is always a line");
+      if start > 0 && !fits(&format!("{}{}", last, piece.trim_end())) {
+        lines.push(format!("{}{}", " ".repeat(column), piece.trim_start()));
+      } else {
+        last.push_str(piece);
+      }
+      start = end;
+    }
+    lines
+  }
+
+  // the first column of a list laid out over lines sets where the others start
+  fn start_list(&mut self, token: &Token) {
+    let laid_out = self.parens.last().is_none_or(|block| *block);
+    if let Some(list) = self.lists.iter().position(|list| list.items.first() == Some(&token.range.start)) {
+      self.aligning = laid_out.then_some((list, self.line.len()));
+    }
+  }
+
+  // a column after the first of the list being aligned starts a line of its own
+  fn aligned_item(&self, token: &Token) -> bool {
+    let Some((list, _)) = self.aligning else {
+      return false;
+    };
+    self.lists[list].items[1..].contains(&token.range.start)
+  }
+
+  // an `AS` naming a column of the list being aligned, remembered where it goes before its space
+  fn mark_alias(&mut self, rest: &[Token]) {
+    let (Some((list, _)), Some(alias)) = (self.aligning, rest.iter().find(|next| !next.is_comment())) else {
+      return;
+    };
+    if self.lists[list].aliases.contains(&alias.range.start) {
+      self.aliases.push((list, self.text.matches('\n').count(), self.line.len()));
+    }
+  }
+
+  // statements and the stages of their chains each get their own line, unless they are inside a subquery
+  fn starts_line(&self, token: &Token) -> bool {
+    if self.previous.is_none_or(|previous| previous.kind == TokenType::Semicolon) {
+      return true;
+    }
+    let laid_out = self.parens.last().is_none_or(|block| *block);
+    laid_out && (matches!(token.kind, TokenType::Pipe | TokenType::Union) || self.breaks.contains(&token.range.start))
+  }
+
+  fn indent(&self) -> String {
+    BLOCK_INDENT.repeat(self.parens.iter().filter(|block| **block).count())
+  }
+
+  // a blank line of the source is kept, but never at the start of the file
+  fn break_line(&mut self, blank_line: bool) {
+    if !self.line.trim().is_empty() {
+      for line in self.wrapped() {
+        self.text.push_str(line.trim_end());
+        self.text.push('\n');
+      }
+    }
+    self.line.clear();
+    self.wrap_points.clear();
+    if blank_line && !self.text.is_empty() && !self.text.ends_with("\n\n") {
+      self.text.push('\n');
+    }
+  }
+
+  fn spaced(&self, token: &Token) -> bool {
+    let Some(previous) = self.previous else {
+      return false;
+    };
+    if matches!(previous.kind, TokenType::LeftParen | TokenType::Dot | TokenType::DoubleColon)
+      || matches!(
+        token.kind,
+        TokenType::Comma | TokenType::Semicolon | TokenType::RightParen | TokenType::Dot | TokenType::DoubleColon
+      )
+    {
+      return false;
+    }
+    if token.kind != TokenType::LeftParen {
+      return true;
+    }
+    match previous.kind {
+      // a name before `(` is a call or a table's list of columns, which read differently, so it stays as written
+      TokenType::Identifier => previous.range.end < token.range.start,
+      TokenType::Cast
+      | TokenType::Rollup
+      | TokenType::Cube
+      | TokenType::Grouping
+      | TokenType::Bernoulli
+      | TokenType::System
+      | TokenType::Block => false,
+      _ => !previous.is_aggregate_function(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::emiter::{Dialect, EmitOptions, ToSql};
+
+  fn format(raw: &str, style: &FormatStyle) -> String {
+    format_source(&Source::new("test.sql", raw), style).unwrap_or_else(|_| panic!("`{}` does not parse", raw))
+  }
+
+  // the sql of every dialect, with and without the comments
+  fn emitted(raw: &str) -> Vec<String> {
+    let source = Source::new("test.sql", raw);
+    let (program, _) = Parser::new(&mut Lexer::new(&source)).parse();
+    let dialects = [Dialect::Ansi, Dialect::Postgres, Dialect::MySql, Dialect::Sqlite];
+    let commented = EmitOptions::default().with_comments(&program.comments);
+    let sql = dialects
+      .iter()
+      .flat_map(|dialect| [EmitOptions::default(), commented].map(|opts| program.to_sql(*dialect, &opts)));
+    sql.collect()
+  }
+
+  // the playground files that parse, the others are left as they are
+  fn playground() -> Vec<String> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground");
+    let files = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path());
+    let files = files.filter(|path| path.extension().is_some_and(|extension| extension == "sql"));
+    let raws = files.map(|path| std::fs::read_to_string(path).unwrap());
+    raws.filter(|raw| format_source(&Source::new("test.sql", raw), &FormatStyle::default()).is_ok()).collect()
+  }
+
+  #[test]
+  fn lays_out_one_stage_per_line() {
+    let raw = "FROM   orders |>WHERE total_amount>10 AND status IN ( 'paid','sent' ) -- paid\n    |> AGGREGATE SUM( total_amount*2 )AS spent GROUP BY customer_id ;\n";
+    let expected = "FROM orders\n|> WHERE total_amount > 10 AND status IN ('paid', 'sent') -- paid\n|> AGGREGATE SUM(total_amount * 2) AS spent\nGROUP BY customer_id;\n";
+    assert_eq!(format(raw, &FormatStyle::default()), expected);
+  }
+
+  #[test]
+  fn formatting_twice_changes_nothing() {
+    for raw in playground() {
+      let once = format(&raw, &FormatStyle::default());
+      assert_eq!(format(&once, &FormatStyle::default()), once, "{}", raw);
+    }
+  }
+
+  #[test]
+  fn formatting_keeps_the_emitted_sql() {
+    for raw in playground() {
+      assert_eq!(emitted(&format(&raw, &FormatStyle::default())), emitted(&raw), "{}", raw);
+    }
+  }
+
+  #[test]
+  fn wraps_past_max_width() {
+    let raw = "FROM orders\n|> WHERE total_amount > 100 AND status = 'paid'\n|> SELECT id, customer_id, total_amount, status, created_at;\n";
+    let narrow = FormatStyle { max_width: Some(40), ..FormatStyle::default() };
+    let expected = "FROM orders\n|> WHERE total_amount > 100\n         AND status = 'paid'\n|> SELECT id, customer_id, total_amount,\n          status, created_at;\n";
+    assert_eq!(format(raw, &narrow), expected);
+    assert!(expected.lines().all(|line| line.chars().count() <= 40));
+    let wide = FormatStyle { max_width: Some(200), ..FormatStyle::default() };
+    assert_eq!(format(raw, &wide), raw);
+  }
+
+  #[test]
+  fn wrapping_keeps_the_program() {
+    for width in [20, 40, 60] {
+      let style = FormatStyle { max_width: Some(width), ..FormatStyle::default() };
+      for raw in playground() {
+        let once = format(&raw, &style);
+        assert_eq!(format(&once, &style), once, "{}", raw);
+        assert_eq!(emitted(&once), emitted(&raw), "{}", raw);
+      }
     }
   }
 }
@@ -0,0 +1,47 @@
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+const QUERIES_DIR: &str = "queries";
+const SAMPLE_FILE: &str = "hello.sp";
+
+// one stage of every major form: FROM, WHERE, AGGREGATE and GROUP BY
+const SAMPLE_QUERY: &str = "-- total spent by each customer on paid orders
+FROM orders
+|> WHERE status = 'paid'
+|> AGGREGATE SUM(total_amount)
+GROUP BY customer_id;
+";
+
+// Scaffolds `queries/hello.sp` under `root`, asking on `input` before overwriting an existing sample.
+pub fn run_init(root: &Path, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+  let queries = root.join(QUERIES_DIR);
+  std::fs::create_dir_all(&queries)?;
+  let sample = queries.join(SAMPLE_FILE);
+  let sample_name = format!("{}/{}", QUERIES_DIR, SAMPLE_FILE);
+  if sample.exists() && !confirm(&format!("{} already exists, overwrite it?", sample_name), &mut input, &mut output)? {
+    writeln!(output, "kept the existing {}", sample_name)?;
+  } else {
+    std::fs::write(&sample, SAMPLE_QUERY)?;
+    writeln!(output, "created {}", sample_name)?;
+  }
+  write_getting_started(&sample_name, &mut output)
+}
+
+fn confirm(question: &str, input: &mut impl BufRead, output: &mut impl Write) -> io::Result<bool> {
+  write!(output, "{} [y/N] ", question)?;
+  output.flush()?;
+  let mut answer = String::new();
+  input.read_line(&mut answer)?;
+  Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn write_getting_started(sample_name: &str, output: &mut impl Write) -> io::Result<()> {
+  writeln!(output)?;
+  writeln!(output, "Getting started:")?;
+  writeln!(output, "  hoshi check {}      check the sample for errors", sample_name)?;
+  writeln!(output, "  hoshi compile {}    print the native sql", sample_name)?;
+  writeln!(output, "  hoshi repl                        try queries interactively, a blank line ends each one")?;
+  writeln!(output)?;
+  writeln!(output, "Each `|>` pipes the rows of one stage into the next, and a `;` ends a query.")?;
+  output.flush()
+}
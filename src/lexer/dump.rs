@@ -0,0 +1,77 @@
+use super::token::{Token, TokenType};
+use crate::utils::{
+  highlight_text_with_blue, highlight_text_with_cyan, highlight_text_with_green, highlight_text_with_red,
+  highlight_text_with_yellow,
+};
+
+// one token per line, its byte range, kind and text, the ranges padded so the kinds line up:
+// 0..4    From
+// 5..11   Identifier `orders`
+// 12..14  Pipe
+pub fn dump_tokens(tokens: &[Token], colored: bool) -> String {
+  let ranges: Vec<String> = tokens.iter().map(|token| format!("{}..{}", token.range.start, token.range.end)).collect();
+  let width = ranges.iter().map(String::len).max().unwrap_or_default();
+  let mut text = String::new();
+  for (token, range) in tokens.iter().zip(ranges) {
+    let kind = paint_kind(&token.kind, &format!("{:?}", token.kind), colored);
+    let line = match &token.lexeme {
+      Some(lexeme) => format!("{:<width$}  {} `{}`", range, kind, lexeme),
+      None => format!("{:<width$}  {}", range, kind),
+    };
+    text.push_str(line.trim_end());
+    text.push('\n');
+  }
+  text
+}
+
+// keywords in blue, literals in green, operators and punctuation in yellow, comments in cyan, errors in red
+fn paint_kind(kind: &TokenType, text: &str, colored: bool) -> String {
+  match kind {
+    TokenType::Identifier | TokenType::EOF => text.to_string(),
+    TokenType::String | TokenType::Number | TokenType::Boolean => highlight_text_with_green(text, colored),
+    TokenType::Comment | TokenType::TrailingComment => highlight_text_with_cyan(text, colored),
+    TokenType::Error => highlight_text_with_red(text, colored),
+    TokenType::Plus
+    | TokenType::Minus
+    | TokenType::Asterisk
+    | TokenType::Slash
+    | TokenType::Percent
+    | TokenType::Equal
+    | TokenType::NotEqual
+    | TokenType::LessThan
+    | TokenType::GreaterThan
+    | TokenType::LessThanOrEqual
+    | TokenType::GreaterThanOrEqual
+    | TokenType::Comma
+    | TokenType::Semicolon
+    | TokenType::LeftParen
+    | TokenType::RightParen
+    | TokenType::LeftBracket
+    | TokenType::RightBracket
+    | TokenType::Dot
+    | TokenType::Colon
+    | TokenType::DoubleColon
+    | TokenType::Pipe => highlight_text_with_yellow(text, colored),
+    _ => highlight_text_with_blue(text, colored),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+
+  use super::*;
+  use crate::lexer::Lexer;
+  use crate::utils::source::Source;
+
+  // tests/playground/tokens.txt is the listing of tokens.sql, which has a token of every kind
+  #[test]
+  fn matches_the_snapshot() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground");
+    let raw = std::fs::read_to_string(dir.join("tokens.sql")).unwrap();
+    let snapshot = std::fs::read_to_string(dir.join("tokens.txt")).unwrap();
+    let source = Source::new("tokens.sql", &raw);
+    let tokens = Lexer::new(&source).collect::<Vec<_>>();
+    assert_eq!(dump_tokens(&tokens, false), snapshot);
+  }
+}
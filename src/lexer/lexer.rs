@@ -2,7 +2,7 @@
 
 use crate::utils::range::Range;
 use crate::utils::source::Source;
-use crate::{diagnostics::report::report_and_exit, utils::match_number};
+use crate::{diagnostics::maneger::Diagnostic, utils::match_number};
 
 use super::token::{Token, TokenType};
 
@@ -11,11 +11,14 @@ pub struct Lexer<'a> {
   cursor: usize,
   cached: Option<Token>,
   range_start: usize,
+  newline_before: bool,
+  reached_eof: bool,
+  errors: Vec<Diagnostic>,
 }
 
 impl<'a> Lexer<'a> {
   pub fn new(source: &'a Source<'a>) -> Self {
-    Self { source, cursor: 0, cached: None, range_start: 0 }
+    Self { source, cursor: 0, cached: None, range_start: 0, newline_before: true, reached_eof: false, errors: vec![] }
   }
 
   pub fn peek_token(&mut self) -> Token {
@@ -32,10 +35,35 @@ impl<'a> Lexer<'a> {
     self.read_next_token()
   }
 
+  // where the next token is read from, for `restore` to come back to after a speculative parse
+  pub fn checkpoint(&self) -> usize {
+    match &self.cached {
+      Some(token) => token.range.start,
+      None => self.cursor,
+    }
+  }
+
+  // rewinds to a checkpoint, the tokens after it are read again and their errors reported once more
+  pub fn restore(&mut self, checkpoint: usize) {
+    self.cursor = checkpoint;
+    self.range_start = checkpoint;
+    self.cached = None;
+    self.reached_eof = false;
+    self.errors.retain(|error| error.range.start < checkpoint);
+    // the next comment trails code unless a newline or the start of the file is all that comes before it
+    let before = self.source.raw[..checkpoint].trim_end_matches(|c: char| c.is_whitespace() && c != '\n');
+    self.newline_before = before.is_empty() || before.ends_with('\n');
+  }
+
   pub fn get_source(&self) -> &Source<'a> {
     self.source
   }
 
+  // diagnostics behind every `Error` token produced so far
+  pub fn get_errors(&self) -> &[Diagnostic] {
+    &self.errors
+  }
+
   fn read_next_token(&mut self) -> Token {
     self.skip_whitespace();
     self.update_current_range();
@@ -43,6 +71,12 @@ impl<'a> Lexer<'a> {
       return Token::create_simple_token(TokenType::EOF, self.create_range());
     }
     let current_char = self.peek_one();
+    let token = self.read_token(current_char);
+    self.newline_before = false;
+    token
+  }
+
+  fn read_token(&mut self, current_char: char) -> Token {
     match current_char {
       '[' => self.read_simple_token(TokenType::LeftBracket),
       ']' => self.read_simple_token(TokenType::RightBracket),
@@ -57,6 +91,7 @@ impl<'a> Lexer<'a> {
       ',' => self.read_simple_token(TokenType::Comma),
       ';' => self.read_simple_token(TokenType::Semicolon),
       '.' => self.read_simple_token(TokenType::Dot),
+      ':' => self.read_check_ahead("::", TokenType::Colon, TokenType::DoubleColon),
       '<' => self.read_check_ahead("<=", TokenType::LessThan, TokenType::LessThanOrEqual),
       '>' => self.read_check_ahead(">=", TokenType::GreaterThan, TokenType::GreaterThanOrEqual),
       '0'..='9' => self.read_number(),
@@ -66,9 +101,10 @@ impl<'a> Lexer<'a> {
       '|' => self.read_pipe(),
       'a'..='z' | 'A'..='Z' | '_' => self.read_identifier(),
       _ => {
+        self.advance_one();
         let range = self.create_range();
         let message = format!("unexpected character '{}'", current_char);
-        report_and_exit(&message, &range, &self.source);
+        self.read_error("E0001", message, range)
       }
     }
   }
@@ -79,9 +115,10 @@ impl<'a> Lexer<'a> {
       let range = self.create_range();
       Token::new(TokenType::NotEqual, None, range)
     } else {
-      let range = self.create_range();
       let message = format!("expected `!=` but got `{}`", self.peek_many(2));
-      report_and_exit(&message, &range, &self.source);
+      self.advance_one();
+      let range = self.create_range();
+      self.read_error("E0002", message, range)
     }
   }
   fn read_pipe(&mut self) -> Token {
@@ -90,11 +127,17 @@ impl<'a> Lexer<'a> {
       let range = self.create_range();
       Token::new(TokenType::Pipe, None, range)
     } else {
-      let range = self.create_range();
       let message = format!("expected `|>` but got `{}`", self.peek_many(2));
-      report_and_exit(&message, &range, &self.source);
+      self.advance_one();
+      let range = self.create_range();
+      self.read_error("E0002", message, range)
     }
   }
+
+  fn read_error(&mut self, code: &'static str, message: String, range: Range) -> Token {
+    self.errors.push(Diagnostic::error(code, message.clone(), range.clone()));
+    Token::new(TokenType::Error, Some(message), range)
+  }
   fn read_simple_token(&mut self, kind: TokenType) -> Token {
     self.advance_one();
     let range = self.create_range();
@@ -112,10 +155,12 @@ impl<'a> Lexer<'a> {
 
   fn read_line_comment(&mut self) -> Token {
     if self.starts_with("--") {
-      self.consume_expect("--");
+      // a comment that shares its line with code annotates that code
+      let kind = if self.newline_before { TokenType::Comment } else { TokenType::TrailingComment };
+      self.advance_many(2);
       let text = self.read_while(|c| c != '\n');
       let range = self.create_range();
-      Token::new(TokenType::Comment, Some(text), range)
+      Token::new(kind, Some(text), range)
     } else {
       self.read_simple_token(TokenType::Minus)
     }
@@ -128,23 +173,39 @@ impl<'a> Lexer<'a> {
   }
 
   fn read_number(&mut self) -> Token {
-    let number = self.read_while(match_number);
+    // `12`, `1.5` and `1.` (a trailing dot is a valid sql decimal), but never a second dot
+    let mut number = self.read_while(|c| c.is_ascii_digit());
+    if self.starts_with(".") {
+      self.advance_one();
+      number.push('.');
+      number.push_str(&self.read_while(|c| c.is_ascii_digit()));
+    }
+    if self.starts_with(".") {
+      number.push_str(&self.read_while(match_number));
+      let range = self.create_range();
+      let message = format!("malformed number '{}', a number has at most one '.'", number);
+      return self.read_error("E0004", message, range);
+    }
     let range = self.create_range();
     Token::new(TokenType::Number, Some(number), range)
   }
 
   fn read_string_with_double_quote(&mut self) -> Token {
-    self.consume_expect("\"");
+    self.advance_one();
     let string = self.read_while(|c| c != '"' && c != '\n');
-    self.consume_expect_with_custom_error("\"", "unterminated string literal");
+    if let Some(error) = self.consume_expect_with_custom_error("\"", "unterminated string literal") {
+      return error;
+    }
     let range = self.create_range();
     Token::new(TokenType::String, Some(string), range)
   }
 
   fn read_string_with_single_quote(&mut self) -> Token {
-    self.consume_expect("'");
+    self.advance_one();
     let string = self.read_while(|c| c != '\'' && c != '\n');
-    self.consume_expect_with_custom_error("'", "unterminated string literal");
+    if let Some(error) = self.consume_expect_with_custom_error("'", "unterminated string literal") {
+      return error;
+    }
     let range = self.create_range();
     Token::new(TokenType::String, Some(string), range)
   }
@@ -172,24 +233,13 @@ impl<'a> Lexer<'a> {
     self.range_start = self.cursor;
   }
 
-  fn consume_expect(&mut self, text: &str) {
+  fn consume_expect_with_custom_error(&mut self, text: &str, error_message: &str) -> Option<Token> {
     if self.starts_with(text) {
       self.advance_many(text.len());
-    } else {
-      let range = self.create_range();
-      let got = self.peek_many(text.len());
-      let message = format!("expected `{}` but got `{}`", text, got);
-      report_and_exit(&message, &range, &self.source);
-    }
-  }
-
-  fn consume_expect_with_custom_error(&mut self, text: &str, error_message: &str) {
-    if self.starts_with(text) {
-      self.advance_many(text.len());
-    } else {
-      let range = self.create_range();
-      report_and_exit(&error_message, &range, &self.source);
+      return None;
     }
+    let range = self.create_range();
+    Some(self.read_error("E0003", error_message.to_string(), range))
   }
 
   pub fn is_end(&self) -> bool {
@@ -216,7 +266,236 @@ impl<'a> Lexer<'a> {
 
   fn skip_whitespace(&mut self) {
     while !self.is_end() && self.peek_one().is_whitespace() {
+      if self.peek_one() == '\n' {
+        self.newline_before = true;
+      }
       self.advance_one();
     }
   }
 }
+
+impl Iterator for Lexer<'_> {
+  type Item = Token;
+
+  // yields every token up to and including `EOF`
+  fn next(&mut self) -> Option<Token> {
+    if self.reached_eof {
+      return None;
+    }
+    let token = self.next_token();
+    self.reached_eof = token.is_eof();
+    Some(token)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::TokenType;
+
+  fn kinds(raw: &str) -> Vec<TokenType> {
+    let source = Source::new("test.sql", raw);
+    Lexer::new(&source).map(|token| token.kind).collect()
+  }
+
+  #[test]
+  fn iterates_like_next_token() {
+    let source = Source::new("test.sql", "FROM orders -- paid\n|> WHERE total > 1.5 |> SELECT id, 'x' ! ;");
+    let mut lexer = Lexer::new(&source);
+    let mut called = vec![];
+    loop {
+      let token = lexer.next_token();
+      called.push(token.clone());
+      if token.is_eof() {
+        break;
+      }
+    }
+    let mut iterated = Lexer::new(&source);
+    assert_eq!(iterated.by_ref().collect::<Vec<_>>(), called);
+    let codes = |lexer: &Lexer| lexer.get_errors().iter().map(|error| error.code).collect::<Vec<_>>();
+    assert_eq!(codes(&iterated), codes(&lexer));
+    assert_eq!(codes(&lexer), ["E0002"]);
+  }
+
+  // a bad character, an unterminated string and a malformed number, with what is read after each one
+  #[test]
+  fn keeps_lexing_after_errors() {
+    let source = Source::new("test.sql", "WHERE a # 1 @ b = 'open\n|> SELECT c 1.2.3 d");
+    let mut lexer = Lexer::new(&source);
+    let tokens = lexer.by_ref().map(|token| (token.kind, token.range.start..token.range.end)).collect::<Vec<_>>();
+    let expected = [
+      (TokenType::Where, 0..5),
+      (TokenType::Identifier, 6..7),
+      (TokenType::Error, 8..9),
+      (TokenType::Number, 10..11),
+      (TokenType::Error, 12..13),
+      (TokenType::Identifier, 14..15),
+      (TokenType::Equal, 16..17),
+      (TokenType::Error, 18..23),
+      (TokenType::Pipe, 24..26),
+      (TokenType::Select, 27..33),
+      (TokenType::Identifier, 34..35),
+      (TokenType::Error, 36..41),
+      (TokenType::Identifier, 42..43),
+      (TokenType::EOF, 43..43),
+    ];
+    assert_eq!(tokens, expected);
+    let errors = lexer.get_errors().iter().map(|error| (error.code, error.range.start..error.range.end));
+    assert_eq!(errors.collect::<Vec<_>>(), [("E0001", 8..9), ("E0001", 12..13), ("E0003", 18..23), ("E0004", 36..41)]);
+  }
+
+  // a speculative parse rewinds to a checkpoint, comments read again keep their kind and errors are not doubled
+  #[test]
+  fn restores_comments_and_errors() {
+    let source = Source::new("test.sql", "FROM orders # -- paid\n-- note\n|> SELECT id");
+    let mut lexer = Lexer::new(&source);
+    lexer.next_token();
+    lexer.next_token();
+    let before_error = lexer.checkpoint();
+    assert_eq!(lexer.next_token().kind, TokenType::Error);
+    assert_eq!(lexer.next_token().kind, TokenType::TrailingComment);
+    assert_eq!(lexer.peek_token().kind, TokenType::Comment);
+    let own_line = lexer.checkpoint();
+    assert_eq!(own_line, 22);
+    lexer.by_ref().for_each(drop);
+    assert_eq!(lexer.get_errors().len(), 1);
+
+    lexer.restore(own_line);
+    assert_eq!(lexer.next_token().kind, TokenType::Comment);
+    assert_eq!(lexer.get_errors().len(), 1);
+
+    lexer.restore(14);
+    assert_eq!(lexer.next_token().kind, TokenType::TrailingComment);
+    assert_eq!(lexer.get_errors().len(), 1);
+
+    lexer.restore(before_error);
+    assert!(lexer.get_errors().is_empty());
+    let kinds = lexer.by_ref().map(|token| token.kind).take(3).collect::<Vec<_>>();
+    assert_eq!(kinds, [TokenType::Error, TokenType::TrailingComment, TokenType::Comment]);
+    assert_eq!(lexer.get_errors().len(), 1);
+  }
+
+  #[test]
+  fn offset_is_a_keyword() {
+    let kinds = kinds("LIMIT 10 OFFSET 5");
+    let expected = [TokenType::Limit, TokenType::Number, TokenType::Offset, TokenType::Number, TokenType::EOF];
+    assert_eq!(kinds, expected);
+  }
+
+  // every keyword of `Token::create_identifier` with the kind it reads as
+  const KEYWORDS: &[(&str, TokenType)] = &[
+    ("SELECT", TokenType::Select),
+    ("FROM", TokenType::From),
+    ("WHERE", TokenType::Where),
+    ("ORDER", TokenType::Order),
+    ("BY", TokenType::By),
+    ("ASC", TokenType::Asc),
+    ("DESC", TokenType::Desc),
+    ("LIMIT", TokenType::Limit),
+    ("OFFSET", TokenType::Offset),
+    ("JOIN", TokenType::Join),
+    ("ON", TokenType::On),
+    ("GROUP", TokenType::Group),
+    ("HAVING", TokenType::Having),
+    ("AS", TokenType::As),
+    ("UNION", TokenType::Union),
+    ("WITH", TokenType::With),
+    ("CASE", TokenType::Case),
+    ("END", TokenType::End),
+    ("AGGREGATE", TokenType::Aggregate),
+    ("INTERVAL", TokenType::Interval),
+    ("FILTER", TokenType::Filter),
+    ("WITHIN", TokenType::Within),
+    ("ROLLUP", TokenType::Rollup),
+    ("CUBE", TokenType::Cube),
+    ("GROUPING", TokenType::Grouping),
+    ("SETS", TokenType::Sets),
+    ("PIVOT", TokenType::Pivot),
+    ("UNPIVOT", TokenType::Unpivot),
+    ("FOR", TokenType::For),
+    ("OVER", TokenType::Over),
+    ("PARTITION", TokenType::Partition),
+    ("QUALIFY", TokenType::Qualify),
+    ("WINDOW", TokenType::Window),
+    ("CAST", TokenType::Cast),
+    ("COPY", TokenType::Copy),
+    ("TO", TokenType::To),
+    ("STDIN", TokenType::Stdin),
+    ("STDOUT", TokenType::Stdout),
+    ("TABLESAMPLE", TokenType::TableSample),
+    ("BERNOULLI", TokenType::Bernoulli),
+    ("SYSTEM", TokenType::System),
+    ("BLOCK", TokenType::Block),
+    ("CURRENT_TIMESTAMP", TokenType::CurrentTimestamp),
+    ("CURRENT_DATE", TokenType::CurrentDate),
+    ("CURRENT_TIME", TokenType::CurrentTime),
+    ("AND", TokenType::And),
+    ("OR", TokenType::Or),
+    ("NOT", TokenType::Not),
+    ("IN", TokenType::In),
+    ("IS", TokenType::Is),
+    ("LIKE", TokenType::Like),
+    ("BETWEEN", TokenType::Between),
+    ("EXISTS", TokenType::Exists),
+    ("NULL", TokenType::Null),
+    ("DISTINCT", TokenType::Distinct),
+    ("INSERT", TokenType::Insert),
+    ("INTO", TokenType::Into),
+    ("VALUES", TokenType::Values),
+    ("UPDATE", TokenType::Update),
+    ("SET", TokenType::Set),
+    ("DELETE", TokenType::Delete),
+    ("CREATE", TokenType::Create),
+    ("TABLE", TokenType::Table),
+    ("ALTER", TokenType::Alter),
+    ("DROP", TokenType::Drop),
+    ("TRUNCATE", TokenType::Truncate),
+    ("INDEX", TokenType::Index),
+    ("UNIQUE", TokenType::Unique),
+    ("COMMENT", TokenType::CommentOn),
+    ("GRANT", TokenType::Grant),
+    ("REVOKE", TokenType::Revoke),
+    ("BEGIN", TokenType::Begin),
+    ("COMMIT", TokenType::Commit),
+    ("ROLLBACK", TokenType::Rollback),
+    ("SAVEPOINT", TokenType::Savepoint),
+    ("RELEASE", TokenType::Release),
+    ("RECURSIVE", TokenType::Recursive),
+    ("PRAGMA", TokenType::Pragma),
+    ("COUNT", TokenType::Count),
+    ("SUM", TokenType::Sum),
+    ("AVG", TokenType::Avg),
+    ("MIN", TokenType::Min),
+    ("MAX", TokenType::Max),
+    ("STDDEV", TokenType::StdDev),
+    ("STDDEV_POP", TokenType::StdDevPop),
+    ("STDDEV_SAMP", TokenType::StdDevSamp),
+    ("VAR_POP", TokenType::VarPop),
+    ("VAR_SAMP", TokenType::VarSamp),
+    ("VARIANCE", TokenType::Variance),
+    ("FIRST", TokenType::First),
+    ("LAST", TokenType::Last),
+    ("GROUP_CONCAT", TokenType::GroupConcat),
+    ("STRING_AGG", TokenType::StringAgg),
+    ("MEDIAN", TokenType::Median),
+    ("MODE", TokenType::Mode),
+    ("ARRAY_AGG", TokenType::ArrayAgg),
+    ("JSON_AGG", TokenType::JsonAgg),
+    ("JSON_OBJECT_AGG", TokenType::JsonObjectAgg),
+    ("BIT_AND", TokenType::BitAnd),
+    ("BIT_OR", TokenType::BitOr),
+    ("BOOL_AND", TokenType::BoolAnd),
+    ("BOOL_OR", TokenType::BoolOr),
+    ("PERCENTILE_CONT", TokenType::PercentileCont),
+    ("PERCENTILE_DISC", TokenType::PercentileDisc),
+  ];
+
+  #[test]
+  fn keywords_read_as_their_kind() {
+    for (keyword, kind) in KEYWORDS {
+      assert_eq!(kinds(keyword), [kind.clone(), TokenType::EOF], "{}", keyword);
+      // keywords are upper case, the same word in lower case is a name
+      assert_eq!(kinds(&keyword.to_lowercase()), [TokenType::Identifier, TokenType::EOF], "{}", keyword);
+    }
+  }
+}
@@ -1,8 +1,10 @@
 #![allow(dead_code)]
 
+mod dump;
 mod lexer;
 mod token;
 
+pub use dump::dump_tokens;
 pub use lexer::Lexer;
 
 pub use token::Token;
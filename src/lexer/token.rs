@@ -5,71 +5,114 @@ use crate::utils::range::Range;
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TokenType {
   //  (Keywords)
-  Select,    // SELECT
-  From,      // FROM
-  Where,     // WHERE
-  Order,     // ORDER
-  By,        // BY
-  Asc,       // ASC
-  Desc,      // DESC
-  Limit,     // LIMIT
-  Offset,    // OFFSET
-  Join,      // JOIN
-  On,        // ON
-  Group,     // GROUP
-  Having,    // HAVING
-  As,        // AS
-  Union,     // UNION
-  With,      // WITH
-  Case,      // CASE
-  End,       // END
-  And,       // AND
-  Or,        // OR
-  Not,       // NOT
-  Insert,    // INSERT
-  Into,      // INTO
-  Values,    // VALUES
-  Update,    // UPDATE
-  Set,       // SET
-  Delete,    // DELETE
-  Create,    // CREATE
-  Table,     // TABLE
-  Alter,     // ALTER
-  Drop,      // DROP
-  Distinct,  // DISTINCT
-  Null,      // NULL
-  Is,        // IS
-  Like,      // LIKE
-  In,        // IN
-  Exists,    // EXISTS
-  Between,   // BETWEEN
-  Aggregate, // AGGREGATE
+  Select,      // SELECT
+  From,        // FROM
+  Where,       // WHERE
+  Order,       // ORDER
+  By,          // BY
+  Asc,         // ASC
+  Desc,        // DESC
+  Limit,       // LIMIT
+  Offset,      // OFFSET
+  Join,        // JOIN
+  On,          // ON
+  Group,       // GROUP
+  Having,      // HAVING
+  As,          // AS
+  Union,       // UNION
+  With,        // WITH
+  Case,        // CASE
+  End,         // END
+  And,         // AND
+  Or,          // OR
+  Not,         // NOT
+  Insert,      // INSERT
+  Into,        // INTO
+  Values,      // VALUES
+  Update,      // UPDATE
+  Set,         // SET
+  Delete,      // DELETE
+  Create,      // CREATE
+  Table,       // TABLE
+  Alter,       // ALTER
+  Drop,        // DROP
+  Truncate,    // TRUNCATE
+  Index,       // INDEX
+  Unique,      // UNIQUE
+  CommentOn,   // COMMENT
+  Grant,       // GRANT
+  Revoke,      // REVOKE
+  Begin,       // BEGIN
+  Commit,      // COMMIT
+  Rollback,    // ROLLBACK
+  Savepoint,   // SAVEPOINT
+  Release,     // RELEASE
+  Recursive,   // RECURSIVE
+  Pragma,      // PRAGMA
+  Distinct,    // DISTINCT
+  Null,        // NULL
+  Is,          // IS
+  Like,        // LIKE
+  In,          // IN
+  Exists,      // EXISTS
+  Between,     // BETWEEN
+  Aggregate,   // AGGREGATE
+  Interval,    // INTERVAL
+  Filter,      // FILTER
+  Within,      // WITHIN, of WITHIN GROUP
+  Rollup,      // ROLLUP
+  Cube,        // CUBE
+  Grouping,    // GROUPING
+  Sets,        // SETS
+  Pivot,       // PIVOT
+  Unpivot,     // UNPIVOT
+  For,         // FOR
+  Over,        // OVER
+  Partition,   // PARTITION
+  Qualify,     // QUALIFY
+  Window,      // WINDOW
+  Cast,        // CAST
+  Copy,        // COPY
+  To,          // TO
+  Stdin,       // STDIN
+  Stdout,      // STDOUT
+  TableSample, // TABLESAMPLE
+  Bernoulli,   // BERNOULLI
+  System,      // SYSTEM
+  Block,       // BLOCK
+
+  // (Current Date and Time)
+  CurrentTimestamp, // CURRENT_TIMESTAMP
+  CurrentDate,      // CURRENT_DATE
+  CurrentTime,      // CURRENT_TIME
 
   // (Aggregation Functions)
-  Count,         // COUNT
-  Sum,           // SUM
-  Avg,           // AVG
-  Min,           // MIN
-  Max,           // MAX
-  StdDev,        // Standard Deviation
-  StdDevPop,     // Population Standard Deviation
-  StdDevSamp,    // Sample Standard Deviation
-  VarPop,        // Population Variance
-  VarSamp,       // Sample Variance
-  Variance,      // Variance
-  First,         // First Value in a Group
-  Last,          // Last Value in a Group
-  GroupConcat,   // Concatenates Group Values (MySQL)
-  StringAgg,     // Concatenates Strings with a Separator (PostgreSQL)
-  Median,        // Median Value (varies by SQL dialect)
-  Mode,          // Most Frequent Value
-  ArrayAgg,      // Aggregates values into an array
-  JsonAgg,       // Aggregates values into a JSON array (PostgreSQL)
-  JsonObjectAgg, // Aggregates key-value pairs into a JSON object (PostgreSQL)
-  BitAnd,        // Bitwise AND of all values (PostgreSQL)
-  BitOr,         // Bitwise OR of all values (PostgreSQL)
-  BoolAnd,       // Logical AND of all boolean values (PostgreSQL)
-  BoolOr,        // Log
+  Count,          // COUNT
+  Sum,            // SUM
+  Avg,            // AVG
+  Min,            // MIN
+  Max,            // MAX
+  StdDev,         // Standard Deviation
+  StdDevPop,      // Population Standard Deviation
+  StdDevSamp,     // Sample Standard Deviation
+  VarPop,         // Population Variance
+  VarSamp,        // Sample Variance
+  Variance,       // Variance
+  First,          // First Value in a Group
+  Last,           // Last Value in a Group
+  GroupConcat,    // Concatenates Group Values (MySQL)
+  StringAgg,      // Concatenates Strings with a Separator (PostgreSQL)
+  Median,         // Median Value (varies by SQL dialect)
+  Mode,           // Most Frequent Value
+  ArrayAgg,       // Aggregates values into an array
+  JsonAgg,        // Aggregates values into a JSON array (PostgreSQL)
+  JsonObjectAgg,  // Aggregates key-value pairs into a JSON object (PostgreSQL)
+  BitAnd,         // Bitwise AND of all values (PostgreSQL)
+  BitOr,          // Bitwise OR of all values (PostgreSQL)
+  BoolAnd,        // Logical AND of all boolean values (PostgreSQL)
+  BoolOr,         // Log
+  PercentileCont, // Interpolated percentile, WITHIN GROUP (ORDER BY ...)
+  PercentileDisc, // Percentile picked from the values, WITHIN GROUP (ORDER BY ...)
   //  (Literals)
   Identifier, // name of a column, table, or alias
   String,     // "string"
@@ -102,10 +145,16 @@ pub enum TokenType {
   LeftBracket,  // [
   RightBracket, // ]
   Dot,          // .
+  Colon,        // :
+  DoubleColon,  // :: (postgres cast)
   Pipe,         // |> (operador de pipe)
 
   //  (Comments)
-  Comment, // -- ou /* ... */
+  Comment,         // -- ou /* ... */
+  TrailingComment, // -- at the end of a line of code
+
+  // (Errors)
+  Error, // text the lexer could not read, the lexer keeps its diagnostic
 
   // (End of File)
   EOF, // end of file
@@ -128,48 +177,82 @@ impl Token {
   }
 
   pub fn is_operator(&self) -> bool {
-    match self.kind {
-      TokenType::Plus => true,
-      TokenType::Minus => true,
-      TokenType::Asterisk => true,
-      TokenType::Slash => true,
-      TokenType::Percent => true,
-      TokenType::Equal => true,
-      TokenType::NotEqual => true,
-      TokenType::LessThan => true,
-      TokenType::GreaterThan => true,
-      TokenType::LessThanOrEqual => true,
-      TokenType::GreaterThanOrEqual => true,
-      TokenType::And => true,
-      TokenType::Or => true,
-      _ => false,
-    }
+    matches!(
+      self.kind,
+      TokenType::Plus
+        | TokenType::Minus
+        | TokenType::Asterisk
+        | TokenType::Slash
+        | TokenType::Percent
+        | TokenType::Equal
+        | TokenType::NotEqual
+        | TokenType::LessThan
+        | TokenType::GreaterThan
+        | TokenType::LessThanOrEqual
+        | TokenType::GreaterThanOrEqual
+        | TokenType::And
+        | TokenType::Or
+    )
   }
 
   pub fn is_punctuation(&self) -> bool {
-    match self.kind {
-      TokenType::Comma => true,
-      TokenType::Semicolon => true,
-      TokenType::LeftParen => true,
-      TokenType::RightParen => true,
-      TokenType::LeftBracket => true,
-      TokenType::RightBracket => true,
-      TokenType::Dot => true,
-      _ => false,
-    }
+    matches!(
+      self.kind,
+      TokenType::Comma
+        | TokenType::Semicolon
+        | TokenType::LeftParen
+        | TokenType::RightParen
+        | TokenType::LeftBracket
+        | TokenType::RightBracket
+        | TokenType::Dot
+    )
+  }
+
+  pub fn is_aggregate_function(&self) -> bool {
+    matches!(
+      self.kind,
+      TokenType::Count
+        | TokenType::Sum
+        | TokenType::Avg
+        | TokenType::Min
+        | TokenType::Max
+        | TokenType::StdDev
+        | TokenType::StdDevPop
+        | TokenType::StdDevSamp
+        | TokenType::VarPop
+        | TokenType::VarSamp
+        | TokenType::Variance
+        | TokenType::First
+        | TokenType::Last
+        | TokenType::GroupConcat
+        | TokenType::StringAgg
+        | TokenType::Median
+        | TokenType::Mode
+        | TokenType::ArrayAgg
+        | TokenType::JsonAgg
+        | TokenType::JsonObjectAgg
+        | TokenType::BitAnd
+        | TokenType::BitOr
+        | TokenType::BoolAnd
+        | TokenType::BoolOr
+        | TokenType::PercentileCont
+        | TokenType::PercentileDisc
+    )
+  }
+
+  pub fn is_error(&self) -> bool {
+    matches!(self.kind, TokenType::Error)
   }
 
   pub fn is_eof(&self) -> bool {
-    match self.kind {
-      TokenType::EOF => true,
-      _ => false,
-    }
+    matches!(self.kind, TokenType::EOF)
   }
   pub fn is_comment(&self) -> bool {
-    match self.kind {
-      TokenType::Comment => true,
-      _ => false,
-    }
+    matches!(self.kind, TokenType::Comment | TokenType::TrailingComment)
+  }
+
+  pub fn is_trailing_comment(&self) -> bool {
+    matches!(self.kind, TokenType::TrailingComment)
   }
 
   pub fn create_identifier(range: Range, text: String) -> Token {
@@ -193,34 +276,93 @@ impl Token {
       "CASE" => Token::new(TokenType::Case, None, range),
       "END" => Token::new(TokenType::End, None, range),
       "AGGREGATE" => Token::new(TokenType::Aggregate, None, range),
+      "INTERVAL" => Token::new(TokenType::Interval, None, range),
+      "FILTER" => Token::new(TokenType::Filter, None, range),
+      "WITHIN" => Token::new(TokenType::Within, None, range),
+      "ROLLUP" => Token::new(TokenType::Rollup, None, range),
+      "CUBE" => Token::new(TokenType::Cube, None, range),
+      "GROUPING" => Token::new(TokenType::Grouping, None, range),
+      "SETS" => Token::new(TokenType::Sets, None, range),
+      "PIVOT" => Token::new(TokenType::Pivot, None, range),
+      "UNPIVOT" => Token::new(TokenType::Unpivot, None, range),
+      "FOR" => Token::new(TokenType::For, None, range),
+      "OVER" => Token::new(TokenType::Over, None, range),
+      "PARTITION" => Token::new(TokenType::Partition, None, range),
+      "QUALIFY" => Token::new(TokenType::Qualify, None, range),
+      "WINDOW" => Token::new(TokenType::Window, None, range),
+      "CAST" => Token::new(TokenType::Cast, None, range),
+      "COPY" => Token::new(TokenType::Copy, None, range),
+      "TO" => Token::new(TokenType::To, None, range),
+      "STDIN" => Token::new(TokenType::Stdin, None, range),
+      "STDOUT" => Token::new(TokenType::Stdout, None, range),
+      "TABLESAMPLE" => Token::new(TokenType::TableSample, None, range),
+      "BERNOULLI" => Token::new(TokenType::Bernoulli, None, range),
+      "SYSTEM" => Token::new(TokenType::System, None, range),
+      "BLOCK" => Token::new(TokenType::Block, None, range),
+      "CURRENT_TIMESTAMP" => Token::new(TokenType::CurrentTimestamp, None, range),
+      "CURRENT_DATE" => Token::new(TokenType::CurrentDate, None, range),
+      "CURRENT_TIME" => Token::new(TokenType::CurrentTime, None, range),
       "AND" => Token::new(TokenType::And, None, range),
       "OR" => Token::new(TokenType::Or, None, range),
+      "NOT" => Token::new(TokenType::Not, None, range),
+      "IN" => Token::new(TokenType::In, None, range),
+      "IS" => Token::new(TokenType::Is, None, range),
+      "LIKE" => Token::new(TokenType::Like, None, range),
+      "BETWEEN" => Token::new(TokenType::Between, None, range),
+      "EXISTS" => Token::new(TokenType::Exists, None, range),
+      "NULL" => Token::new(TokenType::Null, None, range),
+      "DISTINCT" => Token::new(TokenType::Distinct, None, range),
+      "INSERT" => Token::new(TokenType::Insert, None, range),
+      "INTO" => Token::new(TokenType::Into, None, range),
+      "VALUES" => Token::new(TokenType::Values, None, range),
+      "UPDATE" => Token::new(TokenType::Update, None, range),
+      "SET" => Token::new(TokenType::Set, None, range),
+      "DELETE" => Token::new(TokenType::Delete, None, range),
+      "CREATE" => Token::new(TokenType::Create, None, range),
+      "TABLE" => Token::new(TokenType::Table, None, range),
+      "ALTER" => Token::new(TokenType::Alter, None, range),
+      "DROP" => Token::new(TokenType::Drop, None, range),
+      "TRUNCATE" => Token::new(TokenType::Truncate, None, range),
+      "INDEX" => Token::new(TokenType::Index, None, range),
+      "UNIQUE" => Token::new(TokenType::Unique, None, range),
+      "COMMENT" => Token::new(TokenType::CommentOn, None, range),
+      "GRANT" => Token::new(TokenType::Grant, None, range),
+      "REVOKE" => Token::new(TokenType::Revoke, None, range),
+      "BEGIN" => Token::new(TokenType::Begin, None, range),
+      "COMMIT" => Token::new(TokenType::Commit, None, range),
+      "ROLLBACK" => Token::new(TokenType::Rollback, None, range),
+      "SAVEPOINT" => Token::new(TokenType::Savepoint, None, range),
+      "RELEASE" => Token::new(TokenType::Release, None, range),
+      "RECURSIVE" => Token::new(TokenType::Recursive, None, range),
+      "PRAGMA" => Token::new(TokenType::Pragma, None, range),
       "true" => Token::new(TokenType::Boolean, Some("true".to_string()), range),
       "false" => Token::new(TokenType::Boolean, Some("false".to_string()), range),
       "COUNT" => Token::new(TokenType::Count, None, range),
       "SUM" => Token::new(TokenType::Sum, None, range),
-      // "AVG" => Token::new(TokenType::Avg, None, range),
-      // "MIN" => Token::new(TokenType::Min, None, range),
-      // "MAX" => Token::new(TokenType::Max, None, range),
-      // "STDDEV" => Token::new(TokenType::StdDev, None, range),
-      // "STDDEV_POP" => Token::new(TokenType::StdDevPop, None, range),
-      // "STDDEV_SAMP" => Token::new(TokenType::StdDevSamp, None, range),
-      // "VAR_POP" => Token::new(TokenType::VarPop, None, range),
-      // "VAR_SAMP" => Token::new(TokenType::VarSamp, None, range),
-      // "VARIANCE" => Token::new(TokenType::Variance, None, range),
-      // "FIRST" => Token::new(TokenType::First, None, range),
-      // "LAST" => Token::new(TokenType::Last, None, range),
-      // "GROUP_CONCAT" => Token::new(TokenType::GroupConcat, None, range),
-      // "STRING_AGG" => Token::new(TokenType::StringAgg, None, range),
-      // "MEDIAN" => Token::new(TokenType::Median, None, range),
-      // "MODE" => Token::new(TokenType::Mode, None, range),
-      // "ARRAY_AGG" => Token::new(TokenType::ArrayAgg, None, range),
-      // "JSON_AGG" => Token::new(TokenType::JsonAgg, None, range),
-      // "JSON_OBJECT_AGG" => Token::new(TokenType::JsonObjectAgg, None, range),
-      // "BIT_AND" => Token::new(TokenType::BitAnd, None, range),
-      // "BIT_OR" => Token::new(TokenType::BitOr, None, range),
-      // "BOOL_AND" => Token::new(TokenType::BoolAnd, None, range),
-      // "BOOL_OR" => Token::new(TokenType::BoolOr, None, range),
+      "AVG" => Token::new(TokenType::Avg, None, range),
+      "MIN" => Token::new(TokenType::Min, None, range),
+      "MAX" => Token::new(TokenType::Max, None, range),
+      "STDDEV" => Token::new(TokenType::StdDev, None, range),
+      "STDDEV_POP" => Token::new(TokenType::StdDevPop, None, range),
+      "STDDEV_SAMP" => Token::new(TokenType::StdDevSamp, None, range),
+      "VAR_POP" => Token::new(TokenType::VarPop, None, range),
+      "VAR_SAMP" => Token::new(TokenType::VarSamp, None, range),
+      "VARIANCE" => Token::new(TokenType::Variance, None, range),
+      "FIRST" => Token::new(TokenType::First, None, range),
+      "LAST" => Token::new(TokenType::Last, None, range),
+      "GROUP_CONCAT" => Token::new(TokenType::GroupConcat, None, range),
+      "STRING_AGG" => Token::new(TokenType::StringAgg, None, range),
+      "MEDIAN" => Token::new(TokenType::Median, None, range),
+      "MODE" => Token::new(TokenType::Mode, None, range),
+      "ARRAY_AGG" => Token::new(TokenType::ArrayAgg, None, range),
+      "JSON_AGG" => Token::new(TokenType::JsonAgg, None, range),
+      "JSON_OBJECT_AGG" => Token::new(TokenType::JsonObjectAgg, None, range),
+      "BIT_AND" => Token::new(TokenType::BitAnd, None, range),
+      "BIT_OR" => Token::new(TokenType::BitOr, None, range),
+      "BOOL_AND" => Token::new(TokenType::BoolAnd, None, range),
+      "BOOL_OR" => Token::new(TokenType::BoolOr, None, range),
+      "PERCENTILE_CONT" => Token::new(TokenType::PercentileCont, None, range),
+      "PERCENTILE_DISC" => Token::new(TokenType::PercentileDisc, None, range),
       _ => Token::new(TokenType::Identifier, Some(text), range),
     }
   }
@@ -1,61 +1,631 @@
-use checker::Checker;
-use lexer::Lexer;
+#![allow(clippy::module_inception, clippy::upper_case_acronyms)]
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use checker::Schema;
+use cli::{
+  AstFormat, AstOptions, CheckOptions, CompileOptions, EmitKind, FixMode, FmtOptions, RunOptions, TokensOptions,
+};
+use compiler::compile;
+use config::Config;
+use csv_table::CsvTable;
+use diagnostics::codes::describe;
+use diagnostics::explain::explain;
+use diagnostics::fix::apply_fixes;
+use diagnostics::maneger::{DiagnosticsManager, ReportFilter, ReportFormat, Severity};
+use diagnostics::report::{report_file_status, report_files_summary};
+use emiter::{EmitOptions, TargetVersion, ToSql};
+use format::format_source;
+use lexer::{dump_tokens, Lexer, Token};
+use parser::ast::Program;
+use parser::tree::to_tree;
 use parser::Parser;
-use utils::source::Source;
+use stages::explain_pipes;
+use stats::Stats;
+use utils::files::{expand_paths, SourceFile};
+use utils::source::{Source, DEFAULT_TAB_WIDTH};
+use utils::ColorMode;
 
 mod checker;
 mod cli;
+mod compiler;
+mod config;
+mod csv_table;
 mod diagnostics;
 mod emiter;
 mod format;
+mod init;
 mod lexer;
 mod parser;
+mod repl;
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+mod runner;
+mod stages;
+mod stats;
 mod utils;
+const STDIN: &str = "-";
+
 fn main() {
   let matches = cli::command_line();
   match matches.subcommand() {
     Some(("compile", matches)) => {
-      let path_name = matches.get_one::<String>("file").unwrap();
-      run_compile(path_name);
+      let paths = matches.get_many::<String>("file").unwrap().map(String::as_str).collect::<Vec<_>>();
+      let options = CompileOptions::from_matches(matches);
+      let config = load_config(options.config.as_deref(), &paths);
+      let config = config.with_allowed(&options.allow).with_dialect(options.dialect).with_strict(options.strict);
+      run_compile(&paths, &options, &config);
     }
     Some(("check", matches)) => {
-      let path_name = matches.get_one::<String>("file").unwrap();
-      run_check(path_name);
+      let paths = matches.get_many::<String>("file").unwrap().map(String::as_str).collect::<Vec<_>>();
+      let options = CheckOptions::from_matches(matches);
+      let config = load_config(options.config.as_deref(), &paths);
+      let config = config.with_allowed(&options.allow).with_dialect(options.dialect).with_strict(options.strict);
+      run_check(&paths, &options, &config);
     }
     Some(("run", matches)) => {
       let path_name = matches.get_one::<String>("file").unwrap();
-      run_execute(path_name);
+      let options = RunOptions::from_matches(matches);
+      let config = load_config(options.config.as_deref(), &[path_name]);
+      run_execute(path_name, &options, &config);
+    }
+    Some(("fmt", matches)) => {
+      let paths = matches.get_many::<String>("file").unwrap().map(String::as_str).collect::<Vec<_>>();
+      run_fmt(&paths, &FmtOptions::from_matches(matches));
+    }
+    Some(("ast", matches)) => run_ast(matches.get_one::<String>("file").unwrap(), &AstOptions::from_matches(matches)),
+    Some(("tokens", matches)) => {
+      run_tokens(matches.get_one::<String>("file").unwrap(), &TokensOptions::from_matches(matches))
     }
+    Some(("explain", matches)) => run_explain(matches.get_one::<String>("code").unwrap()),
+    Some(("repl", matches)) => run_repl(cli::color_mode(matches)),
+    Some(("init", _)) => run_init(),
     _ => {}
   }
 }
 
 fn load_file(path_name: &str) -> String {
-  let raw = std::fs::read_to_string(path_name).expect(format!("ERROR: cannot open file '{}'", path_name).as_str());
-  raw
+  read_file(path_name).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  })
+}
+
+// `-` reads the program from stdin
+fn write_file(path_name: &str, contents: &str) {
+  std::fs::write(path_name, contents).unwrap_or_else(|_| {
+    eprintln!("ERROR: cannot write file '{}'", path_name);
+    std::process::exit(2);
+  })
+}
+
+fn read_file(path_name: &str) -> Result<String, String> {
+  if path_name == STDIN {
+    let mut raw = String::new();
+    std::io::stdin().read_to_string(&mut raw).map_err(|err| format!("cannot read stdin: {}", err))?;
+    return Ok(raw);
+  }
+  std::fs::read_to_string(path_name).map_err(|_| format!("cannot open file '{}'", path_name))
+}
+
+// the name diagnostics give a file, stdin goes by `--stdin-filename` or `<stdin>`
+fn display_name<'a>(path_name: &'a str, stdin_filename: Option<&'a str>) -> &'a str {
+  match path_name {
+    STDIN => stdin_filename.unwrap_or("<stdin>"),
+    _ => path_name,
+  }
+}
+
+fn load_schema(path_name: &str) -> Result<Schema, String> {
+  let raw = read_file(path_name)?;
+  Schema::from_json(&raw).map_err(|err| format!("invalid schema '{}': {}", path_name, err))
+}
+
+// the settings of `--config`, else of the nearest spipe.toml or .spipe.toml, or the defaults without one
+fn load_config(explicit: Option<&str>, paths: &[&str]) -> Config {
+  let current_dir = std::env::current_dir().expect("ERROR: cannot read the current directory");
+  let config = match explicit {
+    Some(path) => Config::load(Path::new(path)).map(Some),
+    // a file outside the project still gets the config of the directory it is compiled from
+    None => match Config::discover(&config_search_start(paths, &current_dir)) {
+      Ok(None) => Config::discover(&current_dir),
+      found => found,
+    },
+  };
+  let config = config.unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  });
+  config.unwrap_or_default()
+}
+
+// searched for from the first file named, several files share its config; stdin starts from here
+fn config_search_start(paths: &[&str], current_dir: &Path) -> PathBuf {
+  let Some(first) = paths.first().filter(|path| **path != STDIN).map(|path| current_dir.join(path)) else {
+    return current_dir.to_path_buf();
+  };
+  match first.is_dir() {
+    true => first,
+    false => first.parent().unwrap_or(current_dir).to_path_buf(),
+  }
+}
+
+// the programs of `--include`, parsed once for every file; one that does not parse stops the command
+fn load_includes(paths: &[String], format: &ReportFormat, color: ColorMode, filter: &ReportFilter) -> Vec<Program> {
+  let mut programs = vec![];
+  for path_name in paths {
+    let raw = load_file(path_name);
+    let source = Source::new(path_name, &raw);
+    let mut lexer = Lexer::new(&source);
+    let (program, errors) = Parser::new(&mut lexer).parse();
+    if errors.iter().any(|error| error.severity == Severity::Error) {
+      let mut diagnostics = DiagnosticsManager::new();
+      errors.into_iter().for_each(|error| diagnostics.add(error));
+      diagnostics.report_as(&source, format, color, filter);
+      eprintln!("ERROR: cannot include '{}', it does not parse", path_name);
+      std::process::exit(2);
+    }
+    programs.push(program);
+  }
+  programs
+}
+
+// a `--schema` flag wins over the config's schema
+fn resolve_schema(flag: Option<&str>, config: &Config) -> Result<Option<Schema>, String> {
+  let config_schema = config.schema.as_ref().map(|schema| schema.display().to_string());
+  flag.map(str::to_string).or(config_schema).as_deref().map(load_schema).transpose()
 }
-fn run_compile(path_name: &str) {
+
+// stdout is kept for the sql, so diagnostics go to stderr or to `out`
+fn report_diagnostics(
+  diagnostics: &DiagnosticsManager,
+  source: &Source,
+  format: &ReportFormat,
+  color: ColorMode,
+  filter: &ReportFilter,
+  out: Option<&str>,
+) {
+  let Some(out) = out else {
+    diagnostics.report_as(source, format, color, filter);
+    return;
+  };
+  // still written with `--quiet`, it is asked for by name
+  let report = match format {
+    ReportFormat::Sarif => diagnostics.report_sarif(source, filter),
+    ReportFormat::Lsp => diagnostics.report_lsp(source, filter),
+    _ => diagnostics.report_json(source, filter),
+  };
+  write_file(out, &report);
+  diagnostics.report_summary(color, filter);
+}
+
+// rewrites what the dialect, or the version of it, cannot run
+fn lower(program: &mut Program, config: &Config, target_version: Option<TargetVersion>) {
+  program.downgrade_for(&config.dialect, target_version);
+  if !config.keep_qualify {
+    program.rewrite_qualify(&config.dialect);
+  }
+}
+
+fn to_native(program: &mut Program, options: &CompileOptions, config: &Config) -> String {
+  let dialect = config.dialect;
+  lower(program, config, options.target_version);
+  let opts = EmitOptions::default();
+  let opts = if options.keep_comments { opts.with_comments(&program.comments) } else { opts };
+  program.to_sql(dialect, &opts)
+}
+
+// each file is compiled on its own, one that fails does not stop the others; exits like `check`
+fn run_compile(paths: &[&str], options: &CompileOptions, config: &Config) {
+  let files = source_files(paths, &options.extensions);
+  if files.len() > 1 && (options.output.is_some() || options.diagnostics_out.is_some()) {
+    eprintln!("ERROR: --output and --diagnostics-out take a single file, --output-dir takes several");
+    std::process::exit(2);
+  }
+  let schema = resolve_schema(options.schema.as_deref(), config).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  });
+  let included = load_includes(&options.includes, &options.format, options.color, &options.filter);
+  // an `--output-dir` flag wins over the config's
+  let config = Config { output_dir: options.output_dir.clone().or(config.output_dir.clone()), ..config.clone() };
+  let several = files.len() > 1;
+  let report = (&options.format, &options.filter, options.color);
+  run_files("compiled", &files, report, options.deny_warnings, |file| {
+    compile_file(file, options, &config, (schema.as_ref(), &included), several)
+  });
+}
+
+fn compile_file(
+  file: &SourceFile,
+  options: &CompileOptions,
+  config: &Config,
+  (schema, included): (Option<&Schema>, &[Program]),
+  several: bool,
+) -> Result<DiagnosticsManager, String> {
+  let path_name = file.path.as_str();
+  let raw = read_file(path_name)?;
+  let name = display_name(path_name, options.stdin_filename.as_deref());
+  let source = Source::new(name, &raw).with_tab_width(config.tab_width.unwrap_or(DEFAULT_TAB_WIDTH));
+  let (program, mut diagnostics) = compile(&source, schema, included, config);
+  // println!("{:#?}", program);
+  diagnostics.with_error_limit(options.error_limit);
+  let out = options.diagnostics_out.as_deref();
+  report_diagnostics(&diagnostics, &source, &options.format, options.color, &options.filter, out);
+  if options.report_suppressed {
+    diagnostics.report_suppressed(options.color, &options.filter);
+  }
+  if diagnostics.fails(options.deny_warnings) {
+    return Ok(diagnostics);
+  }
+  let mut program = program.unwrap();
+  if options.count_only {
+    let stats = Stats::of(&program);
+    match options.format {
+      ReportFormat::Human | ReportFormat::Github => print!("{}", stats.to_text()),
+      ReportFormat::Json | ReportFormat::Sarif | ReportFormat::Lsp => println!("{}", stats.to_json()),
+    }
+    return Ok(diagnostics);
+  }
+  if options.explain_pipe {
+    lower(&mut program, config, options.target_version);
+    print!("{}", explain_pipes(&program, &raw, config.dialect));
+    return Ok(diagnostics);
+  }
+  let native = match options.emit {
+    EmitKind::Ast => program.to_json(options.pretty),
+    EmitKind::Sql => to_native(&mut program, options, config),
+  };
+  // what comes from stdin goes back to stdout unless `--output` names a file
+  let output = options.output.clone().or_else(|| config.output_for(&file.relative).filter(|_| path_name != STDIN));
+  match (&output, options.dry_run) {
+    (Some(output), true) => {
+      println!("--- Would write to {} ---", output);
+      println!("{}", native);
+    }
+    (Some(output), false) => {
+      // `--output` names one file the user may not mean to lose, `--output-dir` regenerates its files
+      if options.output.is_some() && !options.force && std::path::Path::new(output).exists() {
+        return Err(format!("'{}' already exists, pass --force to overwrite it", output));
+      }
+      if let Some(dir) = std::path::Path::new(output).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|_| format!("cannot create directory '{}'", dir.display()))?;
+      }
+      std::fs::write(output, format!("{}\n", native)).map_err(|_| format!("cannot write file '{}'", output))?;
+    }
+    (None, true) => {}
+    (None, false) if native.is_empty() => {}
+    // the sql of several files is told apart by a comment naming each
+    (None, false) if several => println!("-- {}\n{}", name, native),
+    (None, false) => println!("{}", native),
+  }
+  Ok(diagnostics)
+}
+
+// each file is reported on its own, then the totals; exits 1 when a file fails the check
+// and 2 when one could not be checked at all
+fn run_check(paths: &[&str], options: &CheckOptions, config: &Config) {
+  let files = source_files(paths, &options.extensions);
+  if files.len() > 1 && options.diagnostics_out.is_some() {
+    eprintln!("ERROR: --diagnostics-out takes the diagnostics of a single file");
+    std::process::exit(2);
+  }
+  let schema = resolve_schema(options.schema.as_deref(), config).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  });
+  let included = load_includes(&options.includes, &options.format, options.color, &options.filter);
+  let report = (&options.format, &options.filter, options.color);
+  run_files("checked", &files, report, options.deny_warnings, |file| {
+    check_file(&file.path, options, config, (schema.as_ref(), &included))
+  });
+}
+
+// the files of the command line, with the directories searched
+fn source_files(paths: &[&str], extensions: &[String]) -> Vec<SourceFile> {
+  expand_paths(paths, extensions).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  })
+}
+
+// runs `each` on every file, then with several reports how each one went and the totals;
+// exits 1 when a file fails and 2 when one could not be read at all
+fn run_files(
+  verb: &str,
+  files: &[SourceFile],
+  (format, filter, color): (&ReportFormat, &ReportFilter, ColorMode),
+  deny_warnings: bool,
+  mut each: impl FnMut(&SourceFile) -> Result<DiagnosticsManager, String>,
+) {
+  let (mut failed, mut errors, mut warnings, mut broken) = (0, 0, 0, false);
+  let summarize = files.len() > 1 && !filter.quiet && *format == ReportFormat::Human;
+  for file in files {
+    let file_failed = match each(file) {
+      Ok(diagnostics) => {
+        let (file_errors, file_warnings) = diagnostics.counts(filter);
+        errors += file_errors;
+        warnings += file_warnings;
+        diagnostics.fails(deny_warnings)
+      }
+      Err(err) => {
+        eprintln!("ERROR: {}", err);
+        broken = true;
+        true
+      }
+    };
+    failed += usize::from(file_failed);
+    if summarize {
+      report_file_status(&file.path, file_failed, color.is_colored());
+    }
+  }
+  if summarize {
+    report_files_summary(verb, files.len(), failed, errors, warnings, color.is_colored());
+  }
+  if broken {
+    std::process::exit(2);
+  }
+  if failed > 0 {
+    std::process::exit(1);
+  }
+}
+
+fn check_file(
+  path_name: &str,
+  options: &CheckOptions,
+  config: &Config,
+  (schema, included): (Option<&Schema>, &[Program]),
+) -> Result<DiagnosticsManager, String> {
+  let raw = read_file(path_name)?;
+  let name = display_name(path_name, options.stdin_filename.as_deref());
+  let source = Source::new(name, &raw).with_tab_width(config.tab_width.unwrap_or(DEFAULT_TAB_WIDTH));
+  // a panic is a bug in hoshi rather than in the file, the other files are still checked
+  let compiled = std::panic::catch_unwind(|| compile(&source, schema, included, config));
+  let (_, mut diagnostics) = compiled.map_err(|_| format!("internal error while checking '{}'", name))?;
+  diagnostics.with_error_limit(options.error_limit);
+  let out = options.diagnostics_out.as_deref();
+  report_diagnostics(&diagnostics, &source, &options.format, options.color, &options.filter, out);
+  if options.report_suppressed {
+    diagnostics.report_suppressed(options.color, &options.filter);
+  }
+  // stdin has no file to write the fixes back to, so they are printed
+  let fix = match options.fix {
+    FixMode::Write if path_name == STDIN => FixMode::DryRun,
+    fix => fix,
+  };
+  if fix != FixMode::Off {
+    run_fix(path_name, &raw, &diagnostics.diagnostics, fix);
+  }
+  Ok(diagnostics)
+}
+
+fn run_fix(path_name: &str, raw: &str, diagnostics: &[diagnostics::maneger::Diagnostic], fix: FixMode) {
+  let (fixed, skipped) = apply_fixes(raw, diagnostics);
+  for suggestion in skipped {
+    eprintln!("skipped the fix `{}`, it overlaps another one; run --fix again", suggestion.describe());
+  }
+  if fix == FixMode::DryRun {
+    print!("{}", fixed);
+    return;
+  }
+  if fixed == raw {
+    return;
+  }
+  let backup = format!("{}.bak", path_name);
+  write_file(&backup, raw);
+  write_file(path_name, &fixed);
+  println!("fixed '{}', the original is in '{}'", path_name, backup);
+}
+
+// stdin has no file to write back to, so it is printed; exits 1 when `--check` finds a file to
+// format and 2 when one does not parse, which is left as it is
+fn run_fmt(paths: &[&str], options: &FmtOptions) {
+  let (mut unformatted, mut broken) = (0, false);
+  for file in source_files(paths, &options.extensions) {
+    let raw = load_file(&file.path);
+    let name = display_name(&file.path, options.stdin_filename.as_deref());
+    let source = Source::new(name, &raw);
+    let formatted = match format_source(&source, &options.style) {
+      Ok(formatted) => formatted,
+      Err(errors) => {
+        let mut diagnostics = DiagnosticsManager::new();
+        errors.into_iter().for_each(|error| diagnostics.add(error));
+        diagnostics.report_as(&source, &ReportFormat::Human, options.color, &ReportFilter::default());
+        broken = true;
+        continue;
+      }
+    };
+    if options.check {
+      if formatted != raw {
+        println!("would format '{}'", name);
+        unformatted += 1;
+      }
+    } else if options.stdout || file.path == STDIN {
+      print!("{}", formatted);
+    } else if formatted != raw {
+      write_file(&file.path, &formatted);
+      println!("formatted '{}'", name);
+    }
+  }
+  if broken {
+    std::process::exit(2);
+  }
+  if unformatted > 0 {
+    std::process::exit(1);
+  }
+}
+
+// only parses, so a program the checker would reject still prints; syntax errors are reported
+// with whatever did parse and exit 1
+fn run_ast(path_name: &str, options: &AstOptions) {
   let raw = load_file(path_name);
-  let source = Source::new(path_name, &raw);
+  let name = display_name(path_name, options.stdin_filename.as_deref());
+  let source = Source::new(name, &raw);
   let mut lexer = Lexer::new(&source);
-  let mut parser = Parser::new(&mut lexer);
-  let program = parser.parse();
-  // println!("{:#?}", program);
-  let mut checker = Checker::new();
-  checker.check(&program);
-  checker.report(&source);
-  if checker.contains_error() {
+  let (program, errors) = Parser::new(&mut lexer).parse();
+  let mut diagnostics = DiagnosticsManager::new();
+  errors.into_iter().for_each(|error| diagnostics.add(error));
+  diagnostics.report_as(&source, &ReportFormat::Human, options.color, &ReportFilter::default());
+  let printed = match options.statement {
+    Some(number) => {
+      let Some(statement) = program.statements.get(number - 1) else {
+        eprintln!(
+          "ERROR: '{}' has {} top-level statements, there is no statement {}",
+          name,
+          program.statements.len(),
+          number
+        );
+        std::process::exit(2);
+      };
+      match options.format {
+        AstFormat::Tree => to_tree(statement),
+        AstFormat::Json => serde_json::to_string_pretty(statement).expect("statements always serialize"),
+        AstFormat::Spipe => Program::new(vec![statement.clone()], vec![false]).pretty_print_spipe(),
+      }
+    }
+    None => match options.format {
+      AstFormat::Tree => to_tree(&program),
+      AstFormat::Json => program.to_json(true),
+      AstFormat::Spipe => program.pretty_print_spipe(),
+    },
+  };
+  println!("{}", printed.trim_end());
+  if diagnostics.contains_error() {
     std::process::exit(1);
   }
-  let native = program.to_sql();
-  println!("{}", native);
+}
 
-  // println!("{:#?}", program);
+// the lexer on its own, reading on past text it cannot make sense of; those error tokens are listed
+// like any other, reported on stderr and exit 1
+fn run_tokens(path_name: &str, options: &TokensOptions) {
+  let raw = load_file(path_name);
+  let name = display_name(path_name, options.stdin_filename.as_deref());
+  let source = Source::new(name, &raw);
+  let mut lexer = Lexer::new(&source);
+  let tokens: Vec<Token> = lexer.by_ref().collect();
+  let mut diagnostics = DiagnosticsManager::new();
+  lexer.get_errors().iter().for_each(|error| diagnostics.add(error.clone()));
+  diagnostics.report_as(&source, &ReportFormat::Human, options.color, &ReportFilter::default());
+  if options.json {
+    println!("{}", serde_json::to_string_pretty(&tokens).expect("tokens always serialize"));
+  } else {
+    print!("{}", dump_tokens(&tokens, options.color.is_colored()));
+  }
+  if diagnostics.contains_error() {
+    std::process::exit(1);
+  }
+}
+
+// the queries are emitted for the database they run on, whatever dialect the config targets
+fn run_execute(path_name: &str, options: &RunOptions, config: &Config) {
+  let config = Config { dialect: options.dialect(), ..config.clone() };
+  let raw = load_file(path_name);
+  let name = display_name(path_name, options.stdin_filename.as_deref());
+  let source = Source::new(name, &raw).with_tab_width(config.tab_width.unwrap_or(DEFAULT_TAB_WIDTH));
+  let schema = resolve_schema(options.schema.as_deref(), &config).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  });
+  let tables = read_tables(options);
+  let schema = with_tables(schema, &tables, options);
+  let (program, diagnostics) = compile(&source, schema.as_ref(), &[], &config);
+  let filter = ReportFilter::default();
+  report_diagnostics(&diagnostics, &source, &ReportFormat::Human, options.color, &filter, None);
+  if diagnostics.fails(false) {
+    std::process::exit(1);
+  }
+  let mut program = program.unwrap();
+  lower(&mut program, &config, None);
+  let queries = program.queries_sql(config.dialect, &EmitOptions::default());
+  if options.dry_run {
+    queries.iter().for_each(|(sql, _)| println!("{};", sql));
+    return;
+  }
+  execute(&queries, &tables, &source, options);
 }
-fn run_check(path_name: &str) {
-  println!("{:?}", path_name);
+
+fn read_tables(options: &RunOptions) -> Vec<CsvTable> {
+  let tables = options.tables.iter().map(|(name, path)| {
+    let (table, notices) = CsvTable::read(name, path, options.header).unwrap_or_else(|err| {
+      eprintln!("ERROR: {}", err);
+      std::process::exit(2);
+    });
+    notices.iter().for_each(|notice| eprintln!("note: {}", notice));
+    table
+  });
+  tables.collect()
+}
+
+// the loaded tables let the checker know their columns, and an in-memory database holds nothing else
+fn with_tables(schema: Option<Schema>, tables: &[CsvTable], options: &RunOptions) -> Option<Schema> {
+  if tables.is_empty() {
+    return schema;
+  }
+  let mut schema = match schema {
+    Some(schema) => schema,
+    None if options.db == ":memory:" => Schema { tables: HashMap::new() },
+    None => return None,
+  };
+  csv_table::extend_schema(&mut schema, tables);
+  Some(schema)
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "mysql"))]
+fn execute(queries: &[(String, utils::range::Range)], tables: &[CsvTable], source: &Source, options: &RunOptions) {
+  let mut runner = runner::Runner::open(options).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  });
+  runner.load(tables).unwrap_or_else(|err| {
+    eprintln!("ERROR: {}", err);
+    std::process::exit(2);
+  });
+  if let Err(error) = runner.run(queries, options.transaction) {
+    let mut diagnostics = DiagnosticsManager::new();
+    diagnostics.add(error);
+    diagnostics.report(source, options.color, &ReportFilter::default());
+    std::process::exit(1);
+  }
+}
+
+#[cfg(not(any(feature = "sqlite", feature = "postgres", feature = "mysql")))]
+fn execute(_: &[(String, utils::range::Range)], _: &[CsvTable], _: &Source, options: &RunOptions) {
+  let name = options.dialect().name().to_string();
+  eprintln!("ERROR: this hoshi was built without {0}, build it with `--features {0}` to run against it", name);
+  std::process::exit(2);
+}
+
+fn run_explain(code: &str) {
+  let Some(explanation) = explain(code) else {
+    eprintln!("ERROR: unknown code '{}'", code);
+    std::process::exit(1);
+  };
+  let code = explanation.code;
+  println!("{}: {}", code, describe(code).unwrap_or_default());
+  println!();
+  println!("{}", explanation.text);
+  println!();
+  println!("For example:");
+  println!();
+  println!("{}", indent(explanation.example));
+  println!();
+  println!("How to fix it:");
+  println!();
+  println!("{}", indent(explanation.fix));
+}
+
+fn indent(sql: &str) -> String {
+  sql.lines().map(|line| format!("    {}", line)).collect::<Vec<_>>().join("\n")
+}
+
+fn run_init() {
+  let stdin = std::io::stdin();
+  let root = std::path::Path::new(".");
+  init::run_init(root, stdin.lock(), std::io::stdout()).expect("ERROR: cannot create the project files");
 }
 
-fn run_execute(path_name: &str) {
-  println!("{:?}", path_name);
+fn run_repl(color: ColorMode) {
+  let stdin = std::io::stdin();
+  repl::run_repl(stdin.lock(), std::io::stdout(), color).expect("ERROR: cannot read from stdin");
 }
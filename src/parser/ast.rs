@@ -8,16 +8,74 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Program {
   pub statements: Vec<Statement>,
+  pub terminated: Vec<bool>, // whether each statement is closed by a `;`
+  pub comments: Vec<Comment>,
 }
 
 impl Program {
-  pub fn new(statements: Vec<Statement>) -> Self {
-    Program { statements }
+  pub fn new(statements: Vec<Statement>, terminated: Vec<bool>) -> Self {
+    Program { statements, terminated, comments: vec![] }
   }
+
+  // every node is tagged with its `kind`, e.g. `{"kind":"Select",...}`
+  pub fn to_json(&self, pretty: bool) -> String {
+    let json = if pretty { serde_json::to_string_pretty(self) } else { serde_json::to_string(self) };
+    json.expect("programs always serialize")
+  }
+
+  // statements up to a `;` form one query, e.g. `FROM t |> AGGREGATE ... GROUP BY c;`
+  pub fn queries(&self) -> Vec<&[Statement]> {
+    let mut queries = vec![];
+    let mut start = 0;
+    for (index, terminated) in self.terminated.iter().enumerate() {
+      if *terminated {
+        queries.push(&self.statements[start..=index]);
+        start = index + 1;
+      }
+    }
+    if start < self.statements.len() {
+      queries.push(&self.statements[start..]);
+    }
+    queries
+  }
+
+  pub fn with_comments(&mut self, comments: Vec<Comment>) {
+    self.comments = comments;
+  }
+}
+
+// Comentários (Comment)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+  pub text: String,
+  pub placement: CommentPlacement,
+  pub anchor: Option<Range>, // range of the statement a trailing comment annotates
+  pub range: Range,
+}
+
+impl Comment {
+  pub fn new(text: String, placement: CommentPlacement, range: Range) -> Self {
+    Comment { text, placement, anchor: None, range }
+  }
+
+  pub fn is_trailing(&self) -> bool {
+    self.placement == CommentPlacement::Trailing
+  }
+
+  pub fn is_anchored_to(&self, range: &Range) -> bool {
+    self.anchor.as_ref() == Some(range)
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CommentPlacement {
+  Leading,  // -- on its own line, before a statement
+  Trailing, // -- at the end of a statement's line
 }
 
 // Declarações (Statements)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
 pub enum Statement {
   Select(SelectStatement),
   From(FromClause),
@@ -28,11 +86,62 @@ pub enum Statement {
   Limit(LimitClause),
   Pipe(PipeStatement),
   Aggregate(AggregateClause),
+  Pivot(PivotClause),
+  Unpivot(UnpivotClause),
+  Qualify(QualifyClause),
+  Window(WindowClause),
+  Copy(CopyStatement),
+  Insert(InsertStatement),
+  Truncate(TruncateStatement),
+  AlterTable(AlterTableStatement),
+  CreateIndex(CreateIndexStatement),
+  DropIndex(DropIndexStatement),
+  CommentOn(CommentOnStatement),
+  Pragma(PragmaStatement),
+  Grant(GrantStatement),
+  Revoke(RevokeStatement),
+  Transaction(TransactionStatement),
+  With(WithStatement),
+  #[serde(untagged)] // the expression carries its own `kind`
   Expression(Expression),
 }
 
 impl Statement {
-  pub fn create_select(distinct: bool, expressions: Vec<SelectExpression>, range: Range) -> Self {
+  // the windows named by the WINDOW stages of a pipe chain, in order
+  pub fn window_definitions(&self) -> Vec<&WindowDefinition> {
+    match self {
+      Statement::Window(window) => window.definitions.iter().collect(),
+      Statement::Pipe(pipe) => {
+        let mut definitions = pipe.left.window_definitions();
+        definitions.extend(pipe.right.window_definitions());
+        definitions
+      }
+      Statement::Insert(InsertStatement { source: InsertSource::Select(query), .. }) => query.window_definitions(),
+      _ => vec![],
+    }
+  }
+
+  // whether a FROM or JOIN of the pipe chain reads `table`
+  pub fn reads_table(&self, table: &str) -> bool {
+    let named = |token: &Token| token.lexeme.as_deref() == Some(table);
+    match self {
+      Statement::Select(select) => select.from.as_ref().is_some_and(|from| named(&from.table)),
+      Statement::From(from) => named(&from.table),
+      Statement::Join(join) => named(&join.table),
+      Statement::Pipe(pipe) => pipe.left.reads_table(table) || pipe.right.reads_table(table),
+      _ => false,
+    }
+  }
+
+  pub fn has_where(&self) -> bool {
+    match self {
+      Statement::Where(_) => true,
+      Statement::Pipe(pipe) => pipe.left.has_where() || pipe.right.has_where(),
+      _ => false,
+    }
+  }
+
+  pub fn create_select(distinct: Distinct, expressions: Vec<SelectExpression>, range: Range) -> Self {
     Statement::Select(SelectStatement::new(distinct, expressions, range))
   }
 
@@ -64,6 +173,34 @@ impl Statement {
     Statement::Pipe(PipeStatement::new(left, right))
   }
 
+  // the clauses of a pipe chain in source order, e.g. `FROM t |> WHERE ...` gives [FROM, WHERE]
+  pub fn clauses(&self) -> Vec<&Statement> {
+    match self {
+      Statement::Pipe(pipe) => {
+        let mut clauses = pipe.left.clauses();
+        clauses.extend(pipe.right.clauses());
+        clauses
+      }
+      // the rows an INSERT reads come from its query
+      Statement::Insert(InsertStatement { source: InsertSource::Select(query), .. }) => query.clauses(),
+      _ => vec![self],
+    }
+  }
+
+  // how many columns the last projecting stage of a chain returns, unknown after a PIVOT or UNPIVOT
+  pub fn projected_width(&self) -> Option<(usize, Range)> {
+    let clauses = self.clauses();
+    let projection = clauses.into_iter().rev().find(|clause| {
+      matches!(clause, Statement::Select(_) | Statement::Aggregate(_) | Statement::Pivot(_) | Statement::Unpivot(_))
+    })?;
+    match projection {
+      Statement::Select(select) => Some((select.expressions.len(), select.get_range())),
+      // the GROUP BY columns are not selected, see `AggregateClause::write_with_base`
+      Statement::Aggregate(aggregate) => Some((1, aggregate.get_range())),
+      _ => None,
+    }
+  }
+
   pub fn get_range(&self) -> Range {
     match self {
       Statement::Select(select) => select.get_range(),
@@ -76,6 +213,22 @@ impl Statement {
       Statement::Pipe(pipe) => pipe.get_range(),
       Statement::Expression(expression) => expression.get_range(),
       Statement::Aggregate(aggregate) => aggregate.get_range(),
+      Statement::Pivot(pivot) => pivot.get_range(),
+      Statement::Unpivot(unpivot) => unpivot.get_range(),
+      Statement::Qualify(qualify) => qualify.get_range(),
+      Statement::Window(window) => window.get_range(),
+      Statement::Copy(copy) => copy.get_range(),
+      Statement::Insert(insert) => insert.get_range(),
+      Statement::Truncate(truncate) => truncate.get_range(),
+      Statement::AlterTable(alter) => alter.get_range(),
+      Statement::CreateIndex(index) => index.get_range(),
+      Statement::DropIndex(index) => index.get_range(),
+      Statement::CommentOn(comment) => comment.get_range(),
+      Statement::Pragma(pragma) => pragma.get_range(),
+      Statement::Grant(grant) => grant.get_range(),
+      Statement::Revoke(revoke) => revoke.get_range(),
+      Statement::Transaction(transaction) => transaction.get_range(),
+      Statement::With(with) => with.get_range(),
     }
   }
 }
@@ -104,17 +257,85 @@ impl PipeStatement {
 pub struct AggregateClause {
   pub function: AggregateFn,
   pub argument: Expression,
+  pub filter: Option<Expression>, // FILTER (WHERE ...)
+  pub within_group: Option<WithinGroupClause>,
   pub alias: Option<Token>,
   pub range: Range,
 }
 
 impl AggregateClause {
   pub fn new(function: AggregateFn, argument: Expression, alias: Option<Token>, range: Range) -> Self {
-    AggregateClause { function, argument, alias, range }
+    AggregateClause { function, argument, filter: None, within_group: None, alias, range }
+  }
+
+  pub fn with_filter(&mut self, filter: Expression) {
+    self.filter = Some(filter);
+  }
+
+  pub fn with_within_group(&mut self, within_group: WithinGroupClause) {
+    self.within_group = Some(within_group);
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Cláusula WITHIN GROUP (WithinGroupClause)
+// PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY salary), the order the percentile is taken in
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WithinGroupClause {
+  pub order: OrderClause,
+  pub range: Range,
+}
+
+impl WithinGroupClause {
+  pub fn new(order: OrderClause, range: Range) -> Self {
+    WithinGroupClause { order, range }
   }
 
   pub fn get_range(&self) -> Range {
-    return self.range.clone();
+    self.range.clone()
+  }
+}
+
+// Declaração PIVOT (PivotClause)
+// PIVOT (SUM(sales) FOR quarter IN ('Q1', 'Q2')) turns each listed value of `quarter` into a column
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PivotClause {
+  pub aggregate: AggregateClause,
+  pub for_col: Token,
+  pub in_values: Vec<Expression>,
+  pub range: Range,
+}
+
+impl PivotClause {
+  pub fn new(aggregate: AggregateClause, for_col: Token, in_values: Vec<Expression>, range: Range) -> Self {
+    PivotClause { aggregate, for_col, in_values, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração UNPIVOT (UnpivotClause)
+// UNPIVOT (sales FOR quarter IN (q1, q2)) turns the listed columns into `quarter` and `sales` rows
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UnpivotClause {
+  pub value_col: Token,
+  pub for_col: Token,
+  pub in_columns: Vec<Token>,
+  pub range: Range,
+}
+
+impl UnpivotClause {
+  pub fn new(value_col: Token, for_col: Token, in_columns: Vec<Token>, range: Range) -> Self {
+    UnpivotClause { value_col, for_col, in_columns, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
   }
 }
 
@@ -125,38 +346,79 @@ pub enum AggregateFn {
   Avg,
   Min,
   Max,
-  StdDev,        // Standard Deviation
-  StdDevPop,     // Population Standard Deviation
-  StdDevSamp,    // Sample Standard Deviation
-  VarPop,        // Population Variance
-  VarSamp,       // Sample Variance
-  Variance,      // Variance
-  First,         // First Value in a Group
-  Last,          // Last Value in a Group
-  GroupConcat,   // Concatenates Group Values (MySQL)
-  StringAgg,     // Concatenates Strings with a Separator (PostgreSQL)
-  Median,        // Median Value (varies by SQL dialect)
-  Mode,          // Most Frequent Value
-  ArrayAgg,      // Aggregates values into an array
-  JsonAgg,       // Aggregates values into a JSON array (PostgreSQL)
-  JsonObjectAgg, // Aggregates key-value pairs into a JSON object (PostgreSQL)
-  BitAnd,        // Bitwise AND of all values (PostgreSQL)
-  BitOr,         // Bitwise OR of all values (PostgreSQL)
-  BoolAnd,       // Logical AND of all boolean values (PostgreSQL)
-  BoolOr,        // Logical OR of all boolean values (PostgreSQL)
+  StdDev,         // Standard Deviation
+  StdDevPop,      // Population Standard Deviation
+  StdDevSamp,     // Sample Standard Deviation
+  VarPop,         // Population Variance
+  VarSamp,        // Sample Variance
+  Variance,       // Variance
+  First,          // First Value in a Group
+  Last,           // Last Value in a Group
+  GroupConcat,    // Concatenates Group Values (MySQL)
+  StringAgg,      // Concatenates Strings with a Separator (PostgreSQL)
+  Median,         // Median Value (varies by SQL dialect)
+  Mode,           // Most Frequent Value
+  ArrayAgg,       // Aggregates values into an array
+  JsonAgg,        // Aggregates values into a JSON array (PostgreSQL)
+  JsonObjectAgg,  // Aggregates key-value pairs into a JSON object (PostgreSQL)
+  BitAnd,         // Bitwise AND of all values (PostgreSQL)
+  BitOr,          // Bitwise OR of all values (PostgreSQL)
+  BoolAnd,        // Logical AND of all boolean values (PostgreSQL)
+  BoolOr,         // Logical OR of all boolean values (PostgreSQL)
+  PercentileCont, // Interpolated percentile, WITHIN GROUP (ORDER BY ...)
+  PercentileDisc, // Percentile picked from the values, WITHIN GROUP (ORDER BY ...)
+}
+
+impl AggregateFn {
+  pub fn from_name(name: &str) -> Option<AggregateFn> {
+    match name {
+      "COUNT" => Some(AggregateFn::Count),
+      "SUM" => Some(AggregateFn::Sum),
+      "AVG" => Some(AggregateFn::Avg),
+      "MIN" => Some(AggregateFn::Min),
+      "MAX" => Some(AggregateFn::Max),
+      "STDDEV" => Some(AggregateFn::StdDev),
+      "STDDEV_POP" => Some(AggregateFn::StdDevPop),
+      "STDDEV_SAMP" => Some(AggregateFn::StdDevSamp),
+      "VAR_POP" => Some(AggregateFn::VarPop),
+      "VAR_SAMP" => Some(AggregateFn::VarSamp),
+      "VARIANCE" => Some(AggregateFn::Variance),
+      "FIRST" => Some(AggregateFn::First),
+      "LAST" => Some(AggregateFn::Last),
+      "GROUP_CONCAT" => Some(AggregateFn::GroupConcat),
+      "STRING_AGG" => Some(AggregateFn::StringAgg),
+      "MEDIAN" => Some(AggregateFn::Median),
+      "MODE" => Some(AggregateFn::Mode),
+      "ARRAY_AGG" => Some(AggregateFn::ArrayAgg),
+      "JSON_AGG" => Some(AggregateFn::JsonAgg),
+      "JSON_OBJECT_AGG" => Some(AggregateFn::JsonObjectAgg),
+      "BIT_AND" => Some(AggregateFn::BitAnd),
+      "BIT_OR" => Some(AggregateFn::BitOr),
+      "BOOL_AND" => Some(AggregateFn::BoolAnd),
+      "BOOL_OR" => Some(AggregateFn::BoolOr),
+      "PERCENTILE_CONT" => Some(AggregateFn::PercentileCont),
+      "PERCENTILE_DISC" => Some(AggregateFn::PercentileDisc),
+      _ => None,
+    }
+  }
+
+  // the ordered-set aggregates, which take the order of their values from WITHIN GROUP
+  pub fn is_ordered_set(&self) -> bool {
+    matches!(self, AggregateFn::PercentileCont | AggregateFn::PercentileDisc)
+  }
 }
 
 // Cláusula SELECT (SelectStatement)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SelectStatement {
-  pub distinct: bool,
+  pub distinct: Distinct,
   pub expressions: Vec<SelectExpression>,
   pub from: Option<FromClause>,
   pub range: Range,
 }
 
 impl SelectStatement {
-  pub fn new(distinct: bool, expressions: Vec<SelectExpression>, range: Range) -> Self {
+  pub fn new(distinct: Distinct, expressions: Vec<SelectExpression>, range: Range) -> Self {
     SelectStatement { distinct, expressions, range, from: None }
   }
 
@@ -170,6 +432,13 @@ impl SelectStatement {
   }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Distinct {
+  None,
+  All,                 // SELECT DISTINCT, every selected column together
+  On(Vec<Expression>), // SELECT DISTINCT ON (a, b), postgres keeps the first row of each (a, b)
+}
+
 // Expressão de Seleção (SelectExpression)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SelectExpression {
@@ -184,8 +453,8 @@ impl SelectExpression {
   }
 
   pub fn get_range(&self) -> Range {
-    let right = self.alias.clone().and_then(|alias| Some(alias.range)).unwrap_or(self.range.clone());
-    range_from(&right, &self.expression.get_range())
+    let right = self.alias.clone().map(|alias| alias.range).unwrap_or(self.range.clone());
+    range_from(&self.expression.get_range(), &right)
   }
 }
 
@@ -193,30 +462,75 @@ impl SelectExpression {
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct FromClause {
   pub table: Token,
+  pub alias: Option<Token>,
+  pub sample: Option<TableSampleClause>,
   pub range: Range,
 }
 
 impl FromClause {
   pub fn new(table: Token, range: Range) -> Self {
-    FromClause { table, range }
+    FromClause { table, alias: None, sample: None, range }
+  }
+
+  pub fn with_alias(&mut self, alias: Token) {
+    self.range = range_from(&self.range, &alias.range);
+    self.alias = Some(alias);
+  }
+
+  pub fn with_sample(&mut self, sample: TableSampleClause) {
+    self.range = range_from(&self.range, &sample.range);
+    self.sample = Some(sample);
   }
 
   pub fn get_range(&self) -> Range {
-    range_from(&self.range, &self.table.range)
+    let right = self.alias.as_ref().map_or(&self.table.range, |alias| &alias.range);
+    let right = self.sample.as_ref().map_or(right, |sample| &sample.range);
+    range_from(&self.range, right)
   }
 }
 
+// Cláusula TABLESAMPLE (TableSampleClause)
+// reads roughly `size` percent of the rows, e.g. TABLESAMPLE BERNOULLI(10)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TableSampleClause {
+  pub method: SampleMethod,
+  pub size: Expression,
+  pub range: Range,
+}
+
+impl TableSampleClause {
+  pub fn new(method: SampleMethod, size: Expression, range: Range) -> Self {
+    TableSampleClause { method, size, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SampleMethod {
+  Bernoulli, // each row on its own
+  System,    // whole storage pages
+  Block,     // snowflake's name for SYSTEM
+}
+
 // Cláusula JOIN (JoinClause)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct JoinClause {
   pub table: Token,
+  pub alias: Option<Token>,
   pub on: ConditionExpression,
   pub range: Range,
 }
 
 impl JoinClause {
   pub fn new(table: Token, on: ConditionExpression, range: Range) -> Self {
-    JoinClause { table, on, range }
+    JoinClause { table, alias: None, on, range }
+  }
+
+  pub fn with_alias(&mut self, alias: Token) {
+    self.alias = Some(alias);
   }
 
   pub fn get_range(&self) -> Range {
@@ -241,23 +555,541 @@ impl WhereClause {
   }
 }
 
+// Cláusula QUALIFY (QualifyClause)
+// filters on window function results, e.g. QUALIFY ROW_NUMBER() OVER (...) = 1
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct QualifyClause {
+  pub condition: Expression,
+  pub rewrite: bool, // emitted as a filtered subquery, for dialects without QUALIFY
+  pub range: Range,
+}
+
+impl QualifyClause {
+  pub fn new(condition: Expression, range: Range) -> Self {
+    QualifyClause { condition, rewrite: false, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração COPY (CopyStatement)
+// postgres bulk I/O, e.g. COPY orders TO '/tmp/orders.csv' WITH (FORMAT csv, HEADER)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CopyStatement {
+  pub table: Token,
+  pub direction: CopyDirection,
+  pub target: CopyTarget,
+  pub options: CopyOptions,
+  pub range: Range,
+}
+
+impl CopyStatement {
+  pub fn new(table: Token, direction: CopyDirection, target: CopyTarget, range: Range) -> Self {
+    CopyStatement { table, direction, target, options: CopyOptions::default(), range }
+  }
+
+  pub fn with_options(&mut self, options: CopyOptions, end: &Range) {
+    self.range = range_from(&self.range, end);
+    self.options = options;
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CopyDirection {
+  To,   // table rows out to the target
+  From, // rows from the target into the table
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CopyTarget {
+  File(StringLiteral),
+  Stdin,
+  Stdout,
+}
+
+// WITH (FORMAT csv, DELIMITER ',', HEADER), everything left out keeps the postgres default
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CopyOptions {
+  pub format: Option<String>,
+  pub delimiter: Option<char>,
+  pub header: bool,
+}
+
+// Declaração INSERT (InsertStatement)
+// INSERT INTO summary (dept, total) VALUES ('a', 1), or the rows of a query, piped or not
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InsertStatement {
+  pub table: Token,
+  pub columns: Vec<Token>, // empty when the rows fill every column of the table
+  pub source: InsertSource,
+  pub range: Range,
+}
+
+impl InsertStatement {
+  pub fn new(table: Token, columns: Vec<Token>, source: InsertSource, range: Range) -> Self {
+    InsertStatement { table, columns, source, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum InsertSource {
+  Values(Vec<Vec<Expression>>),
+  Select(Box<Statement>),
+}
+
+impl InsertSource {
+  // how many columns each row fills and where the row is, nothing for a query whose width is unknown
+  pub fn row_widths(&self) -> Vec<(usize, Range)> {
+    match self {
+      InsertSource::Values(rows) => rows.iter().map(|row| (row.len(), range_from_expressions(row))).collect(),
+      InsertSource::Select(query) => query.projected_width().into_iter().collect(),
+    }
+  }
+}
+
+fn range_from_expressions(expressions: &[Expression]) -> Range {
+  let first = expressions.first().map(|expression| expression.get_range()).unwrap_or_default();
+  let last = expressions.last().map(|expression| expression.get_range()).unwrap_or_default();
+  range_from(&first, &last)
+}
+
+// Declaração TRUNCATE (TruncateStatement)
+// TRUNCATE TABLE orders, every row goes at once with no WHERE to narrow it
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TruncateStatement {
+  pub table: Token,
+  pub range: Range,
+}
+
+impl TruncateStatement {
+  pub fn new(table: Token, range: Range) -> Self {
+    TruncateStatement { table, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração ALTER TABLE (AlterTableStatement)
+// ALTER TABLE orders ADD COLUMN note text, one change per statement
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AlterTableStatement {
+  pub table: Token,
+  pub action: AlterAction,
+  pub range: Range,
+}
+
+impl AlterTableStatement {
+  pub fn new(table: Token, action: AlterAction, range: Range) -> Self {
+    AlterTableStatement { table, action, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AlterAction {
+  AddColumn(ColumnDefinition),
+  DropColumn(Token),
+  RenameColumn { from: Token, to: Token },
+  RenameTable(Token),
+}
+
+// a column as ADD COLUMN declares it, e.g. `note varchar(200) NOT NULL`
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ColumnDefinition {
+  pub name: Token,
+  pub data_type: Token, // kept as written, like the type of a cast
+  pub not_null: bool,
+  pub range: Range,
+}
+
+impl ColumnDefinition {
+  pub fn new(name: Token, data_type: Token, not_null: bool, range: Range) -> Self {
+    ColumnDefinition { name, data_type, not_null, range }
+  }
+}
+
+// Declaração CREATE INDEX (CreateIndexStatement)
+// CREATE UNIQUE INDEX orders_customer ON orders (customer_id, created_at)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CreateIndexStatement {
+  pub unique: bool,
+  pub name: Token,
+  pub table: Token,
+  pub columns: Vec<Token>,
+  pub range: Range,
+}
+
+impl CreateIndexStatement {
+  pub fn new(unique: bool, name: Token, table: Token, columns: Vec<Token>, range: Range) -> Self {
+    CreateIndexStatement { unique, name, table, columns, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração DROP INDEX (DropIndexStatement)
+// DROP INDEX orders_customer, with `ON orders` for mysql, which finds indexes through their table
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DropIndexStatement {
+  pub name: Token,
+  pub table: Option<Token>,
+  pub range: Range,
+}
+
+impl DropIndexStatement {
+  pub fn new(name: Token, table: Option<Token>, range: Range) -> Self {
+    DropIndexStatement { name, table, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração COMMENT ON (CommentOnStatement)
+// COMMENT ON TABLE users IS 'user accounts', a column is named through its table as in users.email
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CommentOnStatement {
+  pub object_type: CommentObjectType,
+  pub table: Option<Token>,
+  pub name: Token,
+  pub comment: StringLiteral,
+  pub range: Range,
+}
+
+impl CommentOnStatement {
+  pub fn new(
+    object_type: CommentObjectType,
+    table: Option<Token>,
+    name: Token,
+    comment: StringLiteral,
+    range: Range,
+  ) -> Self {
+    CommentOnStatement { object_type, table, name, comment, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração PRAGMA (PragmaStatement)
+// PRAGMA foreign_keys = ON | PRAGMA table_info(users) | PRAGMA journal_mode, sqlite only
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PragmaStatement {
+  pub name: Token,
+  pub argument: Option<PragmaArgument>,
+  pub range: Range,
+}
+
+impl PragmaStatement {
+  pub fn new(name: Token, argument: Option<PragmaArgument>, range: Range) -> Self {
+    PragmaStatement { name, argument, range }
+  }
+
+  pub fn name(&self) -> &str {
+    self.name.lexeme.as_deref().unwrap_or_default()
+  }
+
+  // the argument of the pragmas that describe a table, `index_info(name)` takes an index instead
+  pub fn table(&self) -> Option<&Token> {
+    match &self.argument {
+      Some(PragmaArgument::TableArg(table)) if TABLE_PRAGMAS.contains(&self.name()) => Some(table),
+      _ => None,
+    }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+const TABLE_PRAGMAS: [&str; 4] = ["table_info", "table_xinfo", "index_list", "foreign_key_list"];
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PragmaArgument {
+  Value(Expression), // `= ON`, `= 5000`, `= 'wal'`
+  TableArg(Token),   // `(users)`
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CommentObjectType {
+  Table,
+  Column,
+  Index,
+}
+
+impl CommentObjectType {
+  pub fn emit(&self) -> &'static str {
+    match self {
+      CommentObjectType::Table => "TABLE",
+      CommentObjectType::Column => "COLUMN",
+      CommentObjectType::Index => "INDEX",
+    }
+  }
+}
+
+// Declaração GRANT (GrantStatement)
+// GRANT SELECT, INSERT ON orders TO analyst, reporting WITH GRANT OPTION
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GrantStatement {
+  pub privileges: Vec<Privilege>,
+  pub object: Token,
+  pub to: Vec<Token>,
+  pub with_grant_option: bool,
+  pub range: Range,
+}
+
+impl GrantStatement {
+  pub fn new(privileges: Vec<Privilege>, object: Token, to: Vec<Token>, with_grant_option: bool, range: Range) -> Self {
+    GrantStatement { privileges, object, to, with_grant_option, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Declaração REVOKE (RevokeStatement)
+// REVOKE INSERT ON orders FROM analyst
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RevokeStatement {
+  pub privileges: Vec<Privilege>,
+  pub object: Token,
+  pub from: Vec<Token>,
+  pub range: Range,
+}
+
+impl RevokeStatement {
+  pub fn new(privileges: Vec<Privilege>, object: Token, from: Vec<Token>, range: Range) -> Self {
+    RevokeStatement { privileges, object, from, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Privilege {
+  Select,
+  Insert,
+  Update,
+  Delete,
+  Truncate,
+  All, // ALL [PRIVILEGES]
+}
+
+impl Privilege {
+  pub fn emit(&self) -> &'static str {
+    match self {
+      Privilege::Select => "SELECT",
+      Privilege::Insert => "INSERT",
+      Privilege::Update => "UPDATE",
+      Privilege::Delete => "DELETE",
+      Privilege::Truncate => "TRUNCATE",
+      Privilege::All => "ALL PRIVILEGES",
+    }
+  }
+}
+
+// Declaração de transação (TransactionStatement)
+// BEGIN, COMMIT, ROLLBACK, SAVEPOINT s, RELEASE SAVEPOINT s, ROLLBACK TO SAVEPOINT s
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionStatement {
+  pub kind: TransactionKind,
+  pub savepoint: Option<Token>, // only for the savepoint kinds
+  pub range: Range,
+}
+
+impl TransactionStatement {
+  pub fn new(kind: TransactionKind, savepoint: Option<Token>, range: Range) -> Self {
+    TransactionStatement { kind, savepoint, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TransactionKind {
+  Begin,
+  Commit,
+  Rollback,
+  Savepoint,
+  ReleaseSavepoint,
+  RollbackToSavepoint,
+}
+
+// Declaração WITH (WithStatement)
+// WITH RECURSIVE tree AS (base UNION ALL step), totals AS (...) FROM tree |> ...
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WithStatement {
+  pub recursive: bool,
+  pub ctes: Vec<CommonTableExpression>,
+  pub body: Box<Statement>, // the query that reads them
+  pub range: Range,
+}
+
+impl WithStatement {
+  pub fn new(recursive: bool, ctes: Vec<CommonTableExpression>, body: Statement, range: Range) -> Self {
+    WithStatement { recursive, ctes, body: Box::new(body), range }
+  }
+
+  // the queries of every cte, then the body
+  pub fn queries(&self) -> impl Iterator<Item = &Statement> {
+    self.ctes.iter().flat_map(CommonTableExpression::branches).chain(std::iter::once(self.body.as_ref()))
+  }
+
+  pub fn queries_mut(&mut self) -> impl Iterator<Item = &mut Statement> {
+    let ctes = self
+      .ctes
+      .iter_mut()
+      .flat_map(|cte| std::iter::once(&mut cte.query).chain(cte.unions.iter_mut().map(|union| &mut union.query)));
+    ctes.chain(std::iter::once(self.body.as_mut()))
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// tree (id, depth) AS (SELECT ... UNION ALL SELECT ...)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CommonTableExpression {
+  pub name: Token,
+  pub columns: Vec<Token>,
+  pub query: Statement,
+  pub unions: Vec<UnionBranch>, // the queries stacked under the first one
+  pub range: Range,
+}
+
+impl CommonTableExpression {
+  pub fn new(name: Token, columns: Vec<Token>, query: Statement, unions: Vec<UnionBranch>, range: Range) -> Self {
+    CommonTableExpression { name, columns, query, unions, range }
+  }
+
+  pub fn name(&self) -> &str {
+    self.name.lexeme.as_deref().unwrap_or_default()
+  }
+
+  // the first query and the ones UNION stacks under it
+  pub fn branches(&self) -> impl Iterator<Item = &Statement> {
+    std::iter::once(&self.query).chain(self.unions.iter().map(|union| &union.query))
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// UNION [ALL] query
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct UnionBranch {
+  pub all: bool,
+  pub query: Statement,
+}
+
+// Cláusula WINDOW (WindowClause)
+// names window specs once for any stage to use, e.g. WINDOW w AS (PARTITION BY dept ORDER BY salary)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WindowClause {
+  pub definitions: Vec<WindowDefinition>,
+  pub range: Range,
+}
+
+impl WindowClause {
+  pub fn new(definitions: Vec<WindowDefinition>, range: Range) -> Self {
+    WindowClause { definitions, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WindowDefinition {
+  pub name: Token,
+  pub spec: WindowSpec,
+  pub range: Range,
+}
+
+impl WindowDefinition {
+  pub fn new(name: Token, spec: WindowSpec, range: Range) -> Self {
+    WindowDefinition { name, spec, range }
+  }
+
+  pub fn name(&self) -> &str {
+    self.name.lexeme.as_deref().unwrap_or_default()
+  }
+}
+
 // Cláusula GROUP BY (GroupByClause)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GroupByClause {
-  pub columns: Vec<Expression>,
+  pub columns: Vec<Expression>, // the ROLLUP or CUBE arguments too, empty for GROUPING SETS
+  pub modifier: GroupByModifier,
   pub range: Range,
 }
 
 impl GroupByClause {
   pub fn new(columns: Vec<Expression>, range: Range) -> Self {
-    GroupByClause { columns, range }
+    GroupByClause { columns, modifier: GroupByModifier::None, range }
+  }
+
+  pub fn with_modifier(&mut self, modifier: GroupByModifier) {
+    self.modifier = modifier;
+  }
+
+  // every grouped expression, whichever set it belongs to
+  pub fn expressions(&self) -> Vec<&Expression> {
+    let mut expressions = self.columns.iter().collect::<Vec<_>>();
+    if let GroupByModifier::GroupingSets(sets) = &self.modifier {
+      expressions.extend(sets.iter().flatten());
+    }
+    expressions
+  }
+
+  pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+    let mut expressions = self.columns.iter_mut().collect::<Vec<_>>();
+    if let GroupByModifier::GroupingSets(sets) = &mut self.modifier {
+      expressions.extend(sets.iter_mut().flatten());
+    }
+    expressions
   }
 
   pub fn get_range(&self) -> Range {
-    return self.range.clone();
+    self.range.clone()
   }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GroupByModifier {
+  None,
+  Rollup,                             // ROLLUP(a, b), the prefixes of the columns
+  Cube,                               // CUBE(a, b), every subset of the columns
+  GroupingSets(Vec<Vec<Expression>>), // GROUPING SETS ((a, b), (a), ())
+}
+
 // Cláusula ORDER BY (OrderClause)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct OrderClause {
@@ -271,7 +1103,7 @@ impl OrderClause {
   }
 
   pub fn get_range(&self) -> Range {
-    return self.range.clone();
+    self.range.clone()
   }
 }
 
@@ -280,27 +1112,66 @@ impl OrderClause {
 pub struct LimitClause {
   pub count: NumberLiteral,
   pub offset: Option<NumberLiteral>,
+  pub comma_offset: bool, // written as `LIMIT count, offset` rather than `LIMIT count OFFSET offset`
   pub range: Range,
 }
 
 impl LimitClause {
   pub fn new(count: NumberLiteral, offset: Option<NumberLiteral>, range: Range) -> Self {
-    LimitClause { count, offset, range }
+    LimitClause { count, offset, comma_offset: false, range }
+  }
+
+  pub fn with_comma_offset(&mut self) {
+    self.comma_offset = true;
   }
 
   pub fn get_range(&self) -> Range {
-    return self.range.clone();
+    self.range.clone()
   }
 }
 
 // Expressões (Expression)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
 pub enum Expression {
   Column(ColumnExpression),
-  Literal(Literal),
   Condition(ConditionExpression),
-  FunctionCall(FunctionCallExpression), // COUNT, SUM, etc.
-  Subquery(SubqueryExpression),         // todo: is correct?
+  FunctionCall(FunctionCallExpression),         // COUNT, SUM, etc.
+  Subquery(SubqueryExpression),                 // todo: is correct?
+  CurrentTimestamp(CurrentTimestampExpression), // NOW(), CURRENT_TIMESTAMP, etc.
+  In(InExpression),                             // x [NOT] IN (...)
+  Not(NotExpression),                           // NOT (...)
+  Window(WindowFunctionExpression),             // ROW_NUMBER() OVER (...)
+  Cast(CastExpression),                         // CAST(x AS date), x::date
+  Tuple(TupleExpression),                       // (a, b), a row value
+  #[serde(untagged)] // the literal carries its own `kind`, untagged variants go last
+  Literal(Literal),
+}
+
+// Expressão de Data Atual (CurrentTimestampExpression)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CurrentTimestampExpression {
+  pub style: TimestampStyle,
+  pub range: Range,
+}
+
+impl CurrentTimestampExpression {
+  pub fn new(style: TimestampStyle, range: Range) -> Self {
+    CurrentTimestampExpression { style, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Estilos de Data Atual (TimestampStyle)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TimestampStyle {
+  Now,              // NOW()
+  CurrentTimestamp, // CURRENT_TIMESTAMP
+  CurrentDate,      // CURRENT_DATE
+  CurrentTime,      // CURRENT_TIME
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
@@ -320,6 +1191,59 @@ impl SubqueryExpression {
   }
 }
 
+// Expressão IN (InExpression)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InExpression {
+  pub expression: Box<Expression>,
+  pub negated: bool,
+  pub values: Vec<Expression>, // a single subquery for `x IN (SELECT ...)`
+  pub range: Range,
+}
+
+impl InExpression {
+  pub fn new(expression: Expression, negated: bool, values: Vec<Expression>, range: Range) -> Self {
+    InExpression { expression: Box::new(expression), negated, values, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Expressão NOT (NotExpression)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NotExpression {
+  pub expression: Box<Expression>,
+  pub range: Range,
+}
+
+impl NotExpression {
+  pub fn new(expression: Expression, range: Range) -> Self {
+    NotExpression { expression: Box::new(expression), range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// Expressão de Tupla (TupleExpression)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TupleExpression {
+  pub items: Vec<Expression>,
+  pub range: Range,
+}
+
+impl TupleExpression {
+  pub fn new(items: Vec<Expression>, range: Range) -> Self {
+    TupleExpression { items, range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
 impl Expression {
   pub fn create_column(table: Option<Token>, column: Token) -> Self {
     let range = range_from(&table.clone().unwrap_or(column.clone()).range, &column.range);
@@ -342,6 +1266,74 @@ impl Expression {
     Expression::Subquery(SubqueryExpression::new(stmt, range))
   }
 
+  pub fn create_current_timestamp(style: TimestampStyle, range: Range) -> Self {
+    Expression::CurrentTimestamp(CurrentTimestampExpression::new(style, range))
+  }
+
+  pub fn create_in(expression: Expression, negated: bool, values: Vec<Expression>, range: Range) -> Self {
+    Expression::In(InExpression::new(expression, negated, values, range))
+  }
+
+  pub fn create_not(expression: Expression, range: Range) -> Self {
+    Expression::Not(NotExpression::new(expression, range))
+  }
+
+  pub fn create_tuple(items: Vec<Expression>, range: Range) -> Self {
+    Expression::Tuple(TupleExpression::new(items, range))
+  }
+
+  // `x IN (...)` and `NOT ...` are conditions without a comparison operator
+  pub fn is_predicate(&self) -> bool {
+    matches!(self, Expression::In(_) | Expression::Not(_))
+  }
+
+  // aggregates inside subqueries belong to the subquery
+  pub fn contains_aggregate(&self) -> bool {
+    match self {
+      Expression::FunctionCall(call) => {
+        call.is_aggregate() || call.arguments.iter().any(|arg| arg.contains_aggregate())
+      }
+      Expression::Condition(condition) => condition.left.contains_aggregate() || condition.right.contains_aggregate(),
+      Expression::In(in_) => {
+        in_.expression.contains_aggregate() || in_.values.iter().any(|value| value.contains_aggregate())
+      }
+      Expression::Not(not) => not.expression.contains_aggregate(),
+      Expression::Cast(cast) => cast.expression.contains_aggregate(),
+      Expression::Tuple(tuple) => tuple.items.iter().any(|item| item.contains_aggregate()),
+      // a window aggregate keeps every row, so it never needs a GROUP BY
+      Expression::Window(_)
+      | Expression::Column(_)
+      | Expression::Literal(_)
+      | Expression::Subquery(_)
+      | Expression::CurrentTimestamp(_) => false,
+    }
+  }
+
+  // columns that are not wrapped in an aggregate, e.g. `name` in `name, COUNT(id)`
+  pub fn plain_columns(&self) -> Vec<&ColumnExpression> {
+    match self {
+      Expression::Column(column) => vec![column],
+      Expression::FunctionCall(call) if call.is_aggregate() => vec![],
+      Expression::FunctionCall(call) => call.arguments.iter().flat_map(|arg| arg.plain_columns()).collect(),
+      Expression::Condition(condition) => {
+        let mut columns = condition.left.plain_columns();
+        columns.extend(condition.right.plain_columns());
+        columns
+      }
+      Expression::In(in_) => {
+        let mut columns = in_.expression.plain_columns();
+        columns.extend(in_.values.iter().flat_map(|value| value.plain_columns()));
+        columns
+      }
+      Expression::Not(not) => not.expression.plain_columns(),
+      Expression::Cast(cast) => cast.expression.plain_columns(),
+      Expression::Tuple(tuple) => tuple.items.iter().flat_map(|item| item.plain_columns()).collect(),
+      Expression::Literal(_) | Expression::Subquery(_) | Expression::CurrentTimestamp(_) | Expression::Window(_) => {
+        vec![]
+      }
+    }
+  }
+
   pub fn get_range(&self) -> Range {
     match self {
       Expression::Column(column) => column.get_range(),
@@ -349,10 +1341,45 @@ impl Expression {
       Expression::Condition(condition) => condition.get_range(),
       Expression::FunctionCall(function_call) => function_call.get_range(),
       Expression::Subquery(select) => select.get_range(),
+      Expression::CurrentTimestamp(timestamp) => timestamp.get_range(),
+      Expression::In(in_) => in_.get_range(),
+      Expression::Not(not) => not.get_range(),
+      Expression::Window(window) => window.get_range(),
+      Expression::Cast(cast) => cast.get_range(),
+      Expression::Tuple(tuple) => tuple.get_range(),
     }
   }
 }
 
+// Expressão de Conversão (CastExpression)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CastExpression {
+  pub expression: Box<Expression>,
+  pub data_type: Token, // the type as written, with any parameters, e.g. `numeric(10, 2)`
+  pub style: CastStyle,
+  pub range: Range,
+}
+
+impl CastExpression {
+  pub fn new(expression: Expression, data_type: Token, style: CastStyle, range: Range) -> Self {
+    CastExpression { expression: Box::new(expression), data_type, style, range }
+  }
+
+  pub fn type_name(&self) -> &str {
+    self.data_type.lexeme.as_deref().unwrap_or_default()
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CastStyle {
+  CastFunction, // CAST(x AS date)
+  DoubleColon,  // x::date
+}
+
 // Expressão de Função (FunctionCallExpression)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct FunctionCallExpression {
@@ -367,9 +1394,50 @@ impl FunctionCallExpression {
   }
 
   pub fn get_range(&self) -> Range {
-    let first = self.arguments.first().unwrap().get_range();
-    let last = self.arguments.last().unwrap().get_range();
-    range_from(&first, &last)
+    self.range.clone()
+  }
+
+  pub fn is_aggregate(&self) -> bool {
+    let name = self.function_name.lexeme.as_deref().unwrap_or_default();
+    AggregateFn::from_name(&name.to_uppercase()).is_some()
+  }
+}
+
+// Funções de Janela (WindowFunctionExpression)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WindowFunctionExpression {
+  pub function: FunctionCallExpression,
+  pub spec: WindowSpec,
+  pub window_name: Option<Token>, // OVER w, a window named by a WINDOW stage, the spec is empty then
+  pub range: Range,
+}
+
+impl WindowFunctionExpression {
+  pub fn new(function: FunctionCallExpression, spec: WindowSpec, range: Range) -> Self {
+    WindowFunctionExpression { function, spec, window_name: None, range }
+  }
+
+  pub fn named(function: FunctionCallExpression, name: Token, range: Range) -> Self {
+    let spec = WindowSpec::new(vec![], vec![], name.range.clone());
+    WindowFunctionExpression { function, spec, window_name: Some(name), range }
+  }
+
+  pub fn get_range(&self) -> Range {
+    self.range.clone()
+  }
+}
+
+// OVER (PARTITION BY a ORDER BY b DESC), both parts optional
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WindowSpec {
+  pub partition_by: Vec<Expression>,
+  pub order_by: Vec<OrderColumn>,
+  pub range: Range,
+}
+
+impl WindowSpec {
+  pub fn new(partition_by: Vec<Expression>, order_by: Vec<OrderColumn>, range: Range) -> Self {
+    WindowSpec { partition_by, order_by, range }
   }
 }
 
@@ -386,9 +1454,18 @@ impl ColumnExpression {
     ColumnExpression { table, column, range }
   }
 
+  // `a` in `a.b`
+  pub fn qualifier(&self) -> Option<&str> {
+    self.table.as_ref().map(|table| table.lexeme.as_deref().unwrap())
+  }
+
+  pub fn name(&self) -> &str {
+    self.column.lexeme.as_deref().unwrap()
+  }
+
   pub fn get_range(&self) -> Range {
-    let right = self.table.clone().and_then(|table| Some(table.range)).unwrap_or(self.column.range.clone());
-    range_from(&self.column.range, &right)
+    let left = self.table.as_ref().map_or(&self.column.range, |table| &table.range);
+    range_from(left, &self.column.range)
   }
 }
 
@@ -414,10 +1491,12 @@ impl ConditionExpression {
 
 // Literais (Literal)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
 pub enum Literal {
   String(StringLiteral),
   Number(NumberLiteral),
   Boolean(BooleanLiteral),
+  Interval(IntervalLiteral),
 }
 
 impl Literal {
@@ -438,6 +1517,7 @@ impl Literal {
       Literal::String(string) => string.range.clone(),
       Literal::Number(number) => number.range.clone(),
       Literal::Boolean(boolean) => boolean.range.clone(),
+      Literal::Interval(interval) => interval.range.clone(),
     }
   }
 }
@@ -481,6 +1561,54 @@ impl BooleanLiteral {
   }
 }
 
+// Literais de Intervalo (IntervalLiteral)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct IntervalLiteral {
+  pub value: String,
+  pub unit: IntervalUnit,
+  pub range: Range,
+}
+
+impl IntervalLiteral {
+  pub fn new(value: String, unit: IntervalUnit, range: Range) -> Self {
+    IntervalLiteral { value, unit, range }
+  }
+
+  pub fn is_numeric(&self) -> bool {
+    self.value.parse::<f64>().is_ok()
+  }
+}
+
+// Unidades de Intervalo (IntervalUnit)
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum IntervalUnit {
+  Second, // SECOND
+  Minute, // MINUTE
+  Hour,   // HOUR
+  Day,    // DAY
+  Week,   // WEEK
+  Month,  // MONTH
+  Year,   // YEAR
+}
+
+impl IntervalUnit {
+  // accepts both `DAY` and `days`, the way engines read them inside interval strings
+  pub fn from_name(name: &str) -> Option<Self> {
+    let name = name.to_uppercase();
+    let unit = match name.strip_suffix('S').unwrap_or(&name) {
+      "SECOND" => IntervalUnit::Second,
+      "MINUTE" => IntervalUnit::Minute,
+      "HOUR" => IntervalUnit::Hour,
+      "DAY" => IntervalUnit::Day,
+      "WEEK" => IntervalUnit::Week,
+      "MONTH" => IntervalUnit::Month,
+      "YEAR" => IntervalUnit::Year,
+      _ => return None,
+    };
+    Some(unit)
+  }
+}
+
 // Operadores (Operator)
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Operator {
@@ -492,6 +1620,11 @@ pub enum Operator {
   GreaterThanOrEqual, // >=
   And,                // AND
   Or,                 // OR
+  Plus,               // +
+  Minus,              // -
+  Multiply,           // *
+  Divide,             // /
+  Modulo,             // %
 }
 
 // Colunas de Ordenação (OrderColumn)
@@ -507,7 +1640,7 @@ impl OrderColumn {
   }
 
   pub fn get_range(&self) -> Range {
-    return self.column.get_range();
+    self.column.get_range()
   }
 }
 
@@ -517,3 +1650,49 @@ pub enum OrderDirection {
   Asc,  // ASC
   Desc, // DESC
 }
+
+#[cfg(test)]
+mod tests {
+  use serde_json::Value;
+
+  use super::*;
+  use crate::lexer::Lexer;
+  use crate::parser::Parser;
+  use crate::utils::source::Source;
+
+  fn parse(raw: &str) -> Program {
+    let source = Source::new("test.sql", raw);
+    let (program, errors) = Parser::new(&mut Lexer::new(&source)).parse();
+    assert!(errors.is_empty(), "`{}` does not parse", raw);
+    program
+  }
+
+  #[test]
+  fn nodes_are_tagged_with_their_kind() {
+    let program = parse("FROM orders |> WHERE total_amount > 100 |> SELECT id, 'big' AS size;");
+    let json = serde_json::to_value(&program).unwrap();
+    let pipe = &json["statements"][0];
+    assert_eq!(pipe["kind"], "Pipe");
+    assert_eq!(pipe["left"]["left"]["kind"], "From");
+    assert_eq!(pipe["left"]["left"]["table"]["lexeme"], "orders");
+    let condition = &pipe["left"]["right"]["condition"];
+    assert_eq!(
+      [&condition["kind"], &condition["left"]["kind"], &condition["right"]["kind"]],
+      ["Condition", "Column", "Number"]
+    );
+    let select = &pipe["right"];
+    assert_eq!(select["kind"], "Select");
+    let kinds = select["expressions"].as_array().unwrap().iter().map(|item| &item["expression"]["kind"]);
+    assert_eq!(kinds.collect::<Vec<_>>(), ["Column", "String"]);
+  }
+
+  #[test]
+  fn json_reads_back_to_the_same_program() {
+    let program =
+      parse("WITH paid AS (FROM orders |> WHERE status = 'paid') FROM paid |> AGGREGATE SUM(total) AS spent;");
+    let json = serde_json::to_string(&program).unwrap();
+    assert_eq!(serde_json::from_str::<Program>(&json).unwrap(), program);
+    let value = serde_json::from_str::<Value>(&json).unwrap();
+    assert_eq!(serde_json::from_value::<Program>(value).unwrap(), program);
+  }
+}
@@ -2,4 +2,5 @@
 
 pub mod ast;
 mod parser;
+pub mod tree;
 pub use parser::Parser;
@@ -1,62 +1,137 @@
 use super::ast::*;
-use crate::diagnostics::report::report_and_exit;
+use crate::diagnostics::maneger::{Diagnostic, Replacement};
 use crate::lexer::{Lexer, Token, TokenType};
 use crate::utils::range::{range_from, Range};
 use crate::utils::source::Source;
 
+pub type ParseResult<T> = Result<T, Diagnostic>;
+
+// tokens a statement can start or end at, where parsing resumes after an error
+const SYNC_TOKENS: [TokenType; 10] = [
+  TokenType::Semicolon,
+  TokenType::Pipe,
+  TokenType::Select,
+  TokenType::From,
+  TokenType::Join,
+  TokenType::Where,
+  TokenType::Group,
+  TokenType::Order,
+  TokenType::Limit,
+  TokenType::Aggregate,
+];
+
 pub struct Parser<'a> {
   lexer: &'a mut Lexer<'a>,
+  comments: Vec<Comment>,
+  pending_comments: Vec<Comment>,
+  diagnostics: Vec<Diagnostic>,
+  last_range: Range, // of the last consumed token
 }
 
 impl<'a> Parser<'a> {
   pub fn new(lexer: &'a mut Lexer<'a>) -> Self {
-    Self { lexer }
+    Self { lexer, comments: vec![], pending_comments: vec![], diagnostics: vec![], last_range: Range::default() }
   }
 
-  pub fn parse(&mut self) -> Program {
-    self.parse_program()
+  // Parses the whole source, skipping statements that do not parse.
+  // The syntax errors and warnings come back alongside whatever could be parsed.
+  pub fn parse(&mut self) -> (Program, Vec<Diagnostic>) {
+    let program = self.parse_program();
+    (program, std::mem::take(&mut self.diagnostics))
   }
 
   fn parse_program(&mut self) -> Program {
     let mut statements = vec![];
+    let mut terminated = vec![];
     while !self.is_end() {
-      let statement = self.parse_statement();
-      statements.push(statement);
-      self.match_token_and_consume(TokenType::Semicolon);
+      match self.parse_statement() {
+        Ok(statement) => {
+          statements.push(statement);
+          terminated.push(self.match_token_and_consume(TokenType::Semicolon).is_some());
+        }
+        Err(error) => {
+          self.recover(error);
+          // the rest of a broken chain still parses, it just loses its head
+          self.match_token_and_consume(TokenType::Pipe);
+          // a `;` after the broken statement still ends the query it belonged to
+          if let (Some(_), Some(last)) = (self.match_token_and_consume(TokenType::Semicolon), terminated.last_mut()) {
+            *last = true;
+          }
+        }
+      }
     }
-    Program::new(statements)
+    let mut program = Program::new(statements, terminated);
+    let mut comments = std::mem::take(&mut self.comments);
+    comments.append(&mut self.pending_comments);
+    comments.sort_by_key(|comment| comment.range.start);
+    program.with_comments(comments);
+    program
   }
 
-  fn parse_statement(&mut self) -> Statement {
-    self.skip_comments();
-    let mut statement = self.parse_primary_statement();
+  fn parse_statement(&mut self) -> ParseResult<Statement> {
+    let mut statement = self.parse_commented_statement()?;
 
     while self.match_token_and_consume(TokenType::Pipe).is_some() {
-      let next_statement = self.parse_primary_statement();
-      statement = Statement::Pipe(PipeStatement::new(statement, next_statement));
+      match self.parse_commented_statement() {
+        Ok(next_statement) => statement = Statement::Pipe(PipeStatement::new(statement, next_statement)),
+        Err(error) => self.recover(error),
+      }
     }
 
-    statement
+    Ok(statement)
   }
 
-  fn parse_primary_statement(&mut self) -> Statement {
-    let token = self.lexer.peek_token();
-    match token.kind {
-      TokenType::Select => Statement::Select(self.parse_select_statement()),
-      TokenType::From => Statement::From(self.parse_from_clause()),
-      TokenType::Join => Statement::Join(self.parse_join_clause()),
-      TokenType::Where => Statement::Where(self.parse_where_clause()),
-      TokenType::Group => Statement::GroupBy(self.parse_group_by_clause()),
-      TokenType::Order => Statement::Order(self.parse_order_clause()),
-      TokenType::Limit => Statement::Limit(self.parse_limit_clause()),
-      TokenType::Aggregate => Statement::Aggregate(self.parse_aggregate_clause()),
-      _ => self.report_unexpected_token(token),
+  fn parse_commented_statement(&mut self) -> ParseResult<Statement> {
+    self.collect_comments();
+    self.comments.append(&mut self.pending_comments);
+    let statement = self.parse_primary_statement()?;
+    self.collect_comments();
+    let range = statement.get_range();
+    while self.pending_comments.first().is_some_and(|comment| comment.is_trailing()) {
+      let mut comment = self.pending_comments.remove(0);
+      comment.anchor = Some(range.clone());
+      self.comments.push(comment);
     }
+    Ok(statement)
+  }
+
+  fn parse_primary_statement(&mut self) -> ParseResult<Statement> {
+    let token = self.peek_token();
+    let statement = match token.kind {
+      TokenType::Select => Statement::Select(self.parse_select_statement()?),
+      TokenType::From => Statement::From(self.parse_from_clause()?),
+      TokenType::Join => Statement::Join(self.parse_join_clause()?),
+      TokenType::Where => Statement::Where(self.parse_where_clause()?),
+      TokenType::Group => Statement::GroupBy(self.parse_group_by_clause()?),
+      TokenType::Order => Statement::Order(self.parse_order_clause()?),
+      TokenType::Limit => Statement::Limit(self.parse_limit_clause()?),
+      TokenType::Aggregate => Statement::Aggregate(self.parse_aggregate_clause()?),
+      TokenType::Pivot => Statement::Pivot(self.parse_pivot_clause()?),
+      TokenType::Unpivot => Statement::Unpivot(self.parse_unpivot_clause()?),
+      TokenType::Qualify => Statement::Qualify(self.parse_qualify_clause()?),
+      TokenType::Window => Statement::Window(self.parse_window_clause()?),
+      TokenType::Copy => Statement::Copy(self.parse_copy_statement()?),
+      TokenType::Insert => Statement::Insert(self.parse_insert_statement()?),
+      TokenType::Truncate => Statement::Truncate(self.parse_truncate_statement()?),
+      TokenType::Alter => Statement::AlterTable(self.parse_alter_table_statement()?),
+      TokenType::Create => Statement::CreateIndex(self.parse_create_index_statement()?),
+      TokenType::Drop => Statement::DropIndex(self.parse_drop_index_statement()?),
+      TokenType::CommentOn => Statement::CommentOn(self.parse_comment_on_statement()?),
+      TokenType::Pragma => Statement::Pragma(self.parse_pragma_statement()?),
+      TokenType::Grant => Statement::Grant(self.parse_grant_statement()?),
+      TokenType::Revoke => Statement::Revoke(self.parse_revoke_statement()?),
+      TokenType::With => Statement::With(self.parse_with_statement()?),
+      TokenType::Begin | TokenType::Commit | TokenType::Rollback | TokenType::Savepoint | TokenType::Release => {
+        Statement::Transaction(self.parse_transaction_statement()?)
+      }
+      _ => return Err(self.unexpected_token(token)),
+    };
+    Ok(statement)
   }
 
-  fn parse_aggregate_function(&mut self) -> AggregateFn {
+  fn parse_aggregate_function(&mut self) -> ParseResult<AggregateFn> {
     let token = self.consume_token();
-    match token.kind {
+    let function = match token.kind {
       TokenType::Count => AggregateFn::Count,
       TokenType::Sum => AggregateFn::Sum,
       TokenType::Avg => AggregateFn::Avg,
@@ -70,71 +145,164 @@ impl<'a> Parser<'a> {
       TokenType::Variance => AggregateFn::Variance,
       TokenType::First => AggregateFn::First,
       TokenType::Last => AggregateFn::Last,
+      TokenType::GroupConcat => AggregateFn::GroupConcat,
       TokenType::StringAgg => AggregateFn::StringAgg,
       TokenType::Median => AggregateFn::Median,
       TokenType::Mode => AggregateFn::Mode,
       TokenType::ArrayAgg => AggregateFn::ArrayAgg,
       TokenType::JsonAgg => AggregateFn::JsonAgg,
+      TokenType::JsonObjectAgg => AggregateFn::JsonObjectAgg,
       TokenType::BitAnd => AggregateFn::BitAnd,
       TokenType::BitOr => AggregateFn::BitOr,
       TokenType::BoolAnd => AggregateFn::BoolAnd,
       TokenType::BoolOr => AggregateFn::BoolOr,
-      _ => {
-        let message = format!("unexpected token '{}'", token.kind.to_string());
-        self.report_error(message, token);
-      }
-    }
+      TokenType::PercentileCont => AggregateFn::PercentileCont,
+      TokenType::PercentileDisc => AggregateFn::PercentileDisc,
+      _ => return Err(self.unexpected_token(token)),
+    };
+    Ok(function)
   }
 
-  fn parse_aggregate_clause(&mut self) -> AggregateClause {
-    let aggregate_range = self.consume_expect_token(TokenType::Aggregate).range;
-    let function = self.parse_aggregate_function();
-    self.consume_expect_token(TokenType::LeftParen);
-    let argument = self.parse_expression();
-    self.consume_expect_token(TokenType::RightParen);
+  fn parse_aggregate_clause(&mut self) -> ParseResult<AggregateClause> {
+    let aggregate_range = self.consume_expect_token(TokenType::Aggregate)?.range;
+    let function = self.parse_aggregate_function()?;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let argument = self.parse_expression()?;
+    let mut range = self.consume_expect_token(TokenType::RightParen)?.range;
+    let mut within_group = None;
+    // WITHIN GROUP (ORDER BY key), which the percentiles cannot do without
+    if function.is_ordered_set() {
+      let within_range = self.consume_expect_token(TokenType::Within)?.range;
+      self.consume_expect_token(TokenType::Group)?;
+      self.consume_expect_token(TokenType::LeftParen)?;
+      let order = self.parse_order_clause()?;
+      range = self.consume_expect_token(TokenType::RightParen)?.range;
+      within_group = Some(WithinGroupClause::new(order, range_from(&within_range, &range)));
+    }
+    let mut filter = None;
+    // FILTER (WHERE condition)
+    if self.match_token_and_consume(TokenType::Filter).is_some() {
+      self.consume_expect_token(TokenType::LeftParen)?;
+      self.consume_expect_token(TokenType::Where)?;
+      filter = Some(self.parse_boolean_expression()?);
+      range = self.consume_expect_token(TokenType::RightParen)?.range;
+    }
     let alias = if self.match_token_and_consume(TokenType::As).is_some() {
-      Some(self.consume_expect_token(TokenType::Identifier))
+      Some(self.parse_identifier_or_keyword_as_identifier()?)
     } else {
       None
     };
 
-    let mut range = argument.get_range();
     if let Some(alias) = &alias {
-      range = range_from(&range, &alias.range);
+      range = alias.range.clone();
     }
 
     let range = range_from(&aggregate_range, &range);
-    AggregateClause::new(function, argument, alias, range)
+    let mut aggregate = AggregateClause::new(function, argument, alias, range);
+    if let Some(filter) = filter {
+      aggregate.with_filter(filter);
+    }
+    if let Some(within_group) = within_group {
+      aggregate.with_within_group(within_group);
+    }
+    Ok(aggregate)
+  }
+
+  // PIVOT (SUM(sales) FOR quarter IN ('Q1', 'Q2'))
+  fn parse_pivot_clause(&mut self) -> ParseResult<PivotClause> {
+    let pivot_range = self.consume_expect_token(TokenType::Pivot)?.range;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let function_token = self.peek_token();
+    let function_range = function_token.range.clone();
+    let function = self.parse_aggregate_function()?;
+    // a percentile's WITHIN GROUP has no place in the CASE each pivoted column becomes
+    if function.is_ordered_set() {
+      return Err(self.unexpected_token(function_token));
+    }
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let argument = self.parse_expression()?;
+    let argument_end = self.consume_expect_token(TokenType::RightParen)?.range;
+    let aggregate = AggregateClause::new(function, argument, None, range_from(&function_range, &argument_end));
+    self.consume_expect_token(TokenType::For)?;
+    let for_col = self.consume_expect_token(TokenType::Identifier)?;
+    self.consume_expect_token(TokenType::In)?;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut in_values = vec![self.parse_expression()?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      in_values.push(self.parse_expression()?);
+    }
+    self.consume_expect_token(TokenType::RightParen)?;
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(PivotClause::new(aggregate, for_col, in_values, range_from(&pivot_range, &end)))
   }
 
-  fn parse_select_statement(&mut self) -> SelectStatement {
-    let select_range = self.consume_expect_token(TokenType::Select).range;
+  // UNPIVOT (sales FOR quarter IN (q1, q2))
+  fn parse_unpivot_clause(&mut self) -> ParseResult<UnpivotClause> {
+    let unpivot_range = self.consume_expect_token(TokenType::Unpivot)?.range;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let value_col = self.consume_expect_token(TokenType::Identifier)?;
+    self.consume_expect_token(TokenType::For)?;
+    let for_col = self.consume_expect_token(TokenType::Identifier)?;
+    self.consume_expect_token(TokenType::In)?;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut in_columns = vec![self.consume_expect_token(TokenType::Identifier)?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      in_columns.push(self.consume_expect_token(TokenType::Identifier)?);
+    }
+    self.consume_expect_token(TokenType::RightParen)?;
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(UnpivotClause::new(value_col, for_col, in_columns, range_from(&unpivot_range, &end)))
+  }
+
+  fn parse_select_statement(&mut self) -> ParseResult<SelectStatement> {
+    let select_range = self.consume_expect_token(TokenType::Select)?.range;
     let mut expressions = vec![];
     let mut last_range = select_range.clone();
-    let is_distinct = self.match_token_and_consume(TokenType::Distinct).is_some();
-    while !self.match_token(&TokenType::From) && !self.is_end() {
-      let expression = self.parse_select_expression();
+    let distinct = self.parse_distinct()?;
+    // a `)` closes a subquery whose last clause is this SELECT, a UNION stacks the next query of a cte
+    let ends = [TokenType::From, TokenType::Semicolon, TokenType::Pipe, TokenType::RightParen, TokenType::Union];
+    while !self.match_any_token(&ends) && !self.is_end() {
+      let expression = self.parse_select_expression()?;
       expressions.push(expression);
-      self.match_token_and_consume(TokenType::Comma);
+      if let Some(comma) = self.match_token_and_consume(TokenType::Comma) {
+        self.check_trailing_comma(&comma, &ends);
+      }
     }
 
     if !expressions.is_empty() {
       last_range = expressions.last().unwrap().get_range();
     }
     let range = range_from(&select_range, &last_range);
-    let mut select_statement = SelectStatement::new(is_distinct, expressions, range);
+    let mut select_statement = SelectStatement::new(distinct, expressions, range);
 
     if self.match_token(&TokenType::From) {
-      let from = self.parse_from_clause();
+      let from = self.parse_from_clause()?;
       select_statement.with_from_clause(from);
     }
-    select_statement
+    Ok(select_statement)
+  }
+
+  // DISTINCT | DISTINCT ON (a, b)
+  fn parse_distinct(&mut self) -> ParseResult<Distinct> {
+    if self.match_token_and_consume(TokenType::Distinct).is_none() {
+      return Ok(Distinct::None);
+    }
+    if self.match_token_and_consume(TokenType::On).is_none() {
+      return Ok(Distinct::All);
+    }
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut expressions = vec![self.parse_expression()?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      expressions.push(self.parse_expression()?);
+    }
+    self.consume_expect_token(TokenType::RightParen)?;
+    Ok(Distinct::On(expressions))
   }
 
-  fn parse_select_expression(&mut self) -> SelectExpression {
-    let expression = self.parse_expression();
+  fn parse_select_expression(&mut self) -> ParseResult<SelectExpression> {
+    let expression = self.parse_expression()?;
     let alias = if self.match_token_and_consume(TokenType::As).is_some() {
-      Some(self.consume_expect_token(TokenType::Identifier))
+      Some(self.parse_identifier_or_keyword_as_identifier()?)
     } else {
       None
     };
@@ -143,25 +311,61 @@ impl<'a> Parser<'a> {
     if let Some(alias) = &alias {
       range = range_from(&range, &alias.range);
     }
-    SelectExpression::new(expression, alias, range)
+    Ok(SelectExpression::new(expression, alias, range))
   }
 
-  fn parse_from_clause(&mut self) -> FromClause {
-    let from_range = self.consume_expect_token(TokenType::From).range;
-    let table_name = self.consume_expect_token(TokenType::Identifier);
+  fn parse_from_clause(&mut self) -> ParseResult<FromClause> {
+    let from_range = self.consume_expect_token(TokenType::From)?.range;
+    let table_name = self.consume_expect_token(TokenType::Identifier)?;
     let range = range_from(&from_range, &table_name.range);
-    FromClause::new(table_name, range)
+    let mut from = FromClause::new(table_name, range);
+    if let Some(alias) = self.parse_table_alias()? {
+      from.with_alias(alias);
+    }
+    if self.match_token(&TokenType::TableSample) {
+      from.with_sample(self.parse_table_sample_clause()?);
+    }
+    Ok(from)
   }
 
-  fn parse_join_clause(&mut self) -> JoinClause {
-    let join_range = self.consume_expect_token(TokenType::Join).range;
-    let table_name = self.consume_expect_token(TokenType::Identifier);
+  // TABLESAMPLE BERNOULLI(10), the size is a percentage of the rows
+  fn parse_table_sample_clause(&mut self) -> ParseResult<TableSampleClause> {
+    let sample_range = self.consume_expect_token(TokenType::TableSample)?.range;
+    let token = self.consume_token();
+    let method = match token.kind {
+      TokenType::Bernoulli => SampleMethod::Bernoulli,
+      TokenType::System => SampleMethod::System,
+      TokenType::Block => SampleMethod::Block,
+      _ if token.is_error() => return Err(self.lexer_error(&token)),
+      _ => {
+        let message = format!("expected 'BERNOULLI', 'SYSTEM' or 'BLOCK' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+    };
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let size = self.parse_expression()?;
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(TableSampleClause::new(method, size, range_from(&sample_range, &end)))
+  }
+
+  fn parse_table_alias(&mut self) -> ParseResult<Option<Token>> {
+    if self.match_token_and_consume(TokenType::As).is_some() {
+      Ok(Some(self.parse_identifier_or_keyword_as_identifier()?))
+    } else {
+      Ok(None)
+    }
+  }
 
-    self.consume_expect_token(TokenType::On);
+  fn parse_join_clause(&mut self) -> ParseResult<JoinClause> {
+    let join_range = self.consume_expect_token(TokenType::Join)?.range;
+    let table_name = self.consume_expect_token(TokenType::Identifier)?;
+    let alias = self.parse_table_alias()?;
 
-    let left = self.parse_column_expression();
-    let operator = self.parse_operator();
-    let right = self.parse_column_expression();
+    self.consume_expect_token(TokenType::On)?;
+
+    let left = self.parse_column_expression()?;
+    let operator = self.parse_operator()?;
+    let right = self.parse_column_expression()?;
 
     // todo: is correct
     let left_range = range_from(&join_range, &left.get_range());
@@ -170,43 +374,520 @@ impl<'a> Parser<'a> {
 
     let condition = ConditionExpression::new(left, operator, right);
 
-    JoinClause::new(table_name, condition, range)
+    let mut join = JoinClause::new(table_name, condition, range);
+    if let Some(alias) = alias {
+      join.with_alias(alias);
+    }
+    Ok(join)
   }
 
-  fn parse_where_clause(&mut self) -> WhereClause {
-    let where_range = self.consume_expect_token(TokenType::Where).range;
+  fn parse_where_clause(&mut self) -> ParseResult<WhereClause> {
+    let where_range = self.consume_expect_token(TokenType::Where)?.range;
 
-    let condition = self.parse_condition_expression();
+    let condition = self.parse_boolean_expression()?;
 
     let range = range_from(&where_range, &condition.get_range());
-    WhereClause::new(condition, range)
+    Ok(WhereClause::new(condition, range))
   }
 
-  fn parse_group_by_clause(&mut self) -> GroupByClause {
-    let group_range = self.consume_expect_token(TokenType::Group).range;
-    self.consume_expect_token(TokenType::By);
-    let mut columns = vec![];
-    while !self.match_any_token(&[TokenType::Order, TokenType::Limit]) && !self.is_end() {
-      columns.push(self.parse_column_expression());
+  fn parse_qualify_clause(&mut self) -> ParseResult<QualifyClause> {
+    let qualify_range = self.consume_expect_token(TokenType::Qualify)?.range;
+    let condition = self.parse_boolean_expression()?;
+    let range = range_from(&qualify_range, &condition.get_range());
+    Ok(QualifyClause::new(condition, range))
+  }
+
+  // COPY orders TO '/tmp/orders.csv' | COPY orders FROM STDIN, then an optional WITH (...)
+  fn parse_copy_statement(&mut self) -> ParseResult<CopyStatement> {
+    let copy_range = self.consume_expect_token(TokenType::Copy)?.range;
+    let table = self.consume_expect_token(TokenType::Identifier)?;
+    let token = self.consume_token();
+    let direction = match token.kind {
+      TokenType::To => CopyDirection::To,
+      TokenType::From => CopyDirection::From,
+      _ => {
+        let message = format!("expected 'TO' or 'FROM' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+    };
+    // rows go out to STDOUT and come in from STDIN, never the other way round
+    let token = self.peek_token();
+    let target = match (token.kind.clone(), direction) {
+      (TokenType::String, _) => CopyTarget::File(self.parse_string_literal()?),
+      (TokenType::Stdout, CopyDirection::To) => CopyTarget::Stdout,
+      (TokenType::Stdin, CopyDirection::From) => CopyTarget::Stdin,
+      (_, CopyDirection::To) => {
+        let message = format!("expected a file or 'STDOUT' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+      (_, CopyDirection::From) => {
+        let message = format!("expected a file or 'STDIN' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+    };
+    if !matches!(target, CopyTarget::File(_)) {
+      self.consume_token();
+    }
+    let mut copy = CopyStatement::new(table, direction, target, range_from(&copy_range, &token.range));
+    if self.match_token_and_consume(TokenType::With).is_some() {
+      let (options, end) = self.parse_copy_options()?;
+      copy.with_options(options, &end);
+    }
+    Ok(copy)
+  }
+
+  // (FORMAT csv, DELIMITER ',', HEADER [true | false]), returning the range of the `)`
+  fn parse_copy_options(&mut self) -> ParseResult<(CopyOptions, Range)> {
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut options = CopyOptions::default();
+    loop {
+      let name = self.consume_expect_token(TokenType::Identifier)?;
+      let option = name.lexeme.as_deref().unwrap_or_default().to_uppercase();
+      match option.as_str() {
+        "FORMAT" => options.format = self.consume_expect_token(TokenType::Identifier)?.lexeme,
+        "DELIMITER" => {
+          let literal = self.parse_string_literal()?;
+          let mut chars = literal.value.chars();
+          let (Some(delimiter), None) = (chars.next(), chars.next()) else {
+            let message = "a COPY delimiter is a single character".to_string();
+            return Err(Diagnostic::error("E0103", message, literal.range));
+          };
+          options.delimiter = Some(delimiter);
+        }
+        "HEADER" => {
+          let value = self.match_token_and_consume(TokenType::Boolean);
+          options.header = value.is_none_or(|value| value.lexeme.as_deref() == Some("true"));
+        }
+        _ => {
+          let message = format!("unknown COPY option '{}', expected FORMAT, DELIMITER or HEADER", option);
+          return Err(self.error("E0102", message, &name));
+        }
+      }
       if self.match_token_and_consume(TokenType::Comma).is_none() {
         break;
       }
     }
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok((options, end))
+  }
+
+  // INSERT INTO t (a, b) VALUES (1, 2), (3, 4) | INSERT INTO t (a, b) FROM s |> SELECT x, y
+  fn parse_insert_statement(&mut self) -> ParseResult<InsertStatement> {
+    let insert_range = self.consume_expect_token(TokenType::Insert)?.range;
+    self.consume_expect_token(TokenType::Into)?;
+    let table = self.consume_expect_token(TokenType::Identifier)?;
+    let mut columns = vec![];
+    if self.match_token_and_consume(TokenType::LeftParen).is_some() {
+      columns.push(self.consume_expect_token(TokenType::Identifier)?);
+      while self.match_token_and_consume(TokenType::Comma).is_some() {
+        columns.push(self.consume_expect_token(TokenType::Identifier)?);
+      }
+      self.consume_expect_token(TokenType::RightParen)?;
+    }
+    let token = self.peek_token();
+    let (source, end) = match token.kind {
+      TokenType::Values => self.parse_insert_values()?,
+      // the query takes the rest of the chain, so its `|>` stages stay inside the INSERT
+      TokenType::Select | TokenType::From => {
+        let query = self.parse_statement()?;
+        let end = query.get_range();
+        (InsertSource::Select(Box::new(query)), end)
+      }
+      _ => {
+        let message = format!("expected 'VALUES', 'SELECT' or 'FROM' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+    };
+    Ok(InsertStatement::new(table, columns, source, range_from(&insert_range, &end)))
+  }
+
+  // VALUES (1, 'a'), (2, 'b'), returning the range of the last `)`
+  fn parse_insert_values(&mut self) -> ParseResult<(InsertSource, Range)> {
+    self.consume_expect_token(TokenType::Values)?;
+    let mut rows = vec![];
+    loop {
+      self.consume_expect_token(TokenType::LeftParen)?;
+      let mut row = vec![self.parse_expression()?];
+      while self.match_token_and_consume(TokenType::Comma).is_some() {
+        row.push(self.parse_expression()?);
+      }
+      let end = self.consume_expect_token(TokenType::RightParen)?.range;
+      rows.push(row);
+      if self.match_token_and_consume(TokenType::Comma).is_none() {
+        return Ok((InsertSource::Values(rows), end));
+      }
+    }
+  }
+
+  // TRUNCATE TABLE orders, the TABLE is optional as in postgres and mysql
+  fn parse_truncate_statement(&mut self) -> ParseResult<TruncateStatement> {
+    let truncate_range = self.consume_expect_token(TokenType::Truncate)?.range;
+    self.match_token_and_consume(TokenType::Table);
+    let table = self.consume_expect_token(TokenType::Identifier)?;
+    let range = range_from(&truncate_range, &table.range);
+    Ok(TruncateStatement::new(table, range))
+  }
+
+  // ALTER TABLE orders ADD COLUMN note text, DROP COLUMN note, RENAME COLUMN a TO b or RENAME TO archive;
+  // COLUMN is optional after ADD and DROP
+  fn parse_alter_table_statement(&mut self) -> ParseResult<AlterTableStatement> {
+    let alter_range = self.consume_expect_token(TokenType::Alter)?.range;
+    self.consume_expect_token(TokenType::Table)?;
+    let table = self.consume_expect_token(TokenType::Identifier)?;
+    let token = self.peek_token();
+    let action = if self.match_keyword_and_consume("ADD").is_some() {
+      self.match_keyword_and_consume("COLUMN");
+      AlterAction::AddColumn(self.parse_column_definition()?)
+    } else if self.match_token_and_consume(TokenType::Drop).is_some() {
+      self.match_keyword_and_consume("COLUMN");
+      AlterAction::DropColumn(self.consume_expect_token(TokenType::Identifier)?)
+    } else if self.match_keyword_and_consume("RENAME").is_some() {
+      if self.match_token_and_consume(TokenType::To).is_some() {
+        AlterAction::RenameTable(self.consume_expect_token(TokenType::Identifier)?)
+      } else {
+        self.match_keyword_and_consume("COLUMN");
+        let from = self.consume_expect_token(TokenType::Identifier)?;
+        self.consume_expect_token(TokenType::To)?;
+        AlterAction::RenameColumn { from, to: self.consume_expect_token(TokenType::Identifier)? }
+      }
+    } else {
+      let message = format!("expected 'ADD', 'DROP' or 'RENAME' but found '{}'", token.kind);
+      return Err(self.error("E0102", message, &token));
+    };
+    let range = range_from(&alter_range, &self.current_range());
+    Ok(AlterTableStatement::new(table, action, range))
+  }
+
+  // CREATE [UNIQUE] INDEX orders_customer ON orders (customer_id, created_at)
+  fn parse_create_index_statement(&mut self) -> ParseResult<CreateIndexStatement> {
+    let create_range = self.consume_expect_token(TokenType::Create)?.range;
+    let unique = self.match_token_and_consume(TokenType::Unique).is_some();
+    self.consume_expect_token(TokenType::Index)?;
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    self.consume_expect_token(TokenType::On)?;
+    let table = self.consume_expect_token(TokenType::Identifier)?;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut columns = vec![self.consume_expect_token(TokenType::Identifier)?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      columns.push(self.consume_expect_token(TokenType::Identifier)?);
+    }
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(CreateIndexStatement::new(unique, name, table, columns, range_from(&create_range, &end)))
+  }
+
+  // DROP INDEX orders_customer [ON orders]
+  fn parse_drop_index_statement(&mut self) -> ParseResult<DropIndexStatement> {
+    let drop_range = self.consume_expect_token(TokenType::Drop)?.range;
+    self.consume_expect_token(TokenType::Index)?;
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    let table = match self.match_token_and_consume(TokenType::On) {
+      Some(_) => Some(self.consume_expect_token(TokenType::Identifier)?),
+      None => None,
+    };
+    let range = range_from(&drop_range, &self.current_range());
+    Ok(DropIndexStatement::new(name, table, range))
+  }
+
+  // COMMENT ON TABLE users IS 'user accounts' | COMMENT ON COLUMN users.email IS '...' | COMMENT ON INDEX ...
+  fn parse_comment_on_statement(&mut self) -> ParseResult<CommentOnStatement> {
+    let comment_range = self.consume_expect_token(TokenType::CommentOn)?.range;
+    self.consume_expect_token(TokenType::On)?;
+    let token = self.peek_token();
+    let object_type = if self.match_token_and_consume(TokenType::Table).is_some() {
+      CommentObjectType::Table
+    } else if self.match_token_and_consume(TokenType::Index).is_some() {
+      CommentObjectType::Index
+    } else if self.match_keyword_and_consume("COLUMN").is_some() {
+      CommentObjectType::Column
+    } else {
+      let message = format!("expected 'TABLE', 'COLUMN' or 'INDEX' but found '{}'", token.kind);
+      return Err(self.error("E0102", message, &token));
+    };
+    let first = self.consume_expect_token(TokenType::Identifier)?;
+    let (table, name) = match object_type {
+      CommentObjectType::Column => {
+        self.consume_expect_token(TokenType::Dot)?;
+        (Some(first), self.consume_expect_token(TokenType::Identifier)?)
+      }
+      _ => (None, first),
+    };
+    self.consume_expect_token(TokenType::Is)?;
+    let comment = self.parse_string_literal()?;
+    let range = range_from(&comment_range, &comment.range);
+    Ok(CommentOnStatement::new(object_type, table, name, comment, range))
+  }
+
+  // PRAGMA foreign_keys = ON | PRAGMA table_info(users) | PRAGMA journal_mode
+  fn parse_pragma_statement(&mut self) -> ParseResult<PragmaStatement> {
+    let pragma_range = self.consume_expect_token(TokenType::Pragma)?.range;
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    let argument = if self.match_token_and_consume(TokenType::Equal).is_some() {
+      // ON is a keyword here, kept as written like the bare words sqlite takes, e.g. `journal_mode = WAL`
+      match self.match_token_and_consume(TokenType::On) {
+        Some(on) => {
+          Some(PragmaArgument::Value(Expression::create_column(None, Token { lexeme: Some("ON".to_string()), ..on })))
+        }
+        None => Some(PragmaArgument::Value(self.parse_expression()?)),
+      }
+    } else if self.match_token_and_consume(TokenType::LeftParen).is_some() {
+      let table = self.consume_expect_token(TokenType::Identifier)?;
+      self.consume_expect_token(TokenType::RightParen)?;
+      Some(PragmaArgument::TableArg(table))
+    } else {
+      None
+    };
+    let range = range_from(&pragma_range, &self.current_range());
+    Ok(PragmaStatement::new(name, argument, range))
+  }
+
+  // GRANT SELECT, INSERT ON [TABLE] orders TO analyst, reporting [WITH GRANT OPTION]
+  fn parse_grant_statement(&mut self) -> ParseResult<GrantStatement> {
+    let grant_range = self.consume_expect_token(TokenType::Grant)?.range;
+    let (privileges, object) = self.parse_privileges_on()?;
+    self.consume_expect_token(TokenType::To)?;
+    let to = self.parse_grantees()?;
+    let with_grant_option = self.match_token_and_consume(TokenType::With).is_some();
+    if with_grant_option {
+      self.consume_expect_token(TokenType::Grant)?;
+      let token = self.peek_token();
+      if self.match_keyword_and_consume("OPTION").is_none() {
+        let message = format!("expected 'OPTION' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+    }
+    let range = range_from(&grant_range, &self.current_range());
+    Ok(GrantStatement::new(privileges, object, to, with_grant_option, range))
+  }
+
+  // REVOKE INSERT ON [TABLE] orders FROM analyst
+  fn parse_revoke_statement(&mut self) -> ParseResult<RevokeStatement> {
+    let revoke_range = self.consume_expect_token(TokenType::Revoke)?.range;
+    let (privileges, object) = self.parse_privileges_on()?;
+    self.consume_expect_token(TokenType::From)?;
+    let from = self.parse_grantees()?;
+    let range = range_from(&revoke_range, &self.current_range());
+    Ok(RevokeStatement::new(privileges, object, from, range))
+  }
+
+  // SELECT, INSERT ON [TABLE] orders | ALL [PRIVILEGES] ON orders
+  fn parse_privileges_on(&mut self) -> ParseResult<(Vec<Privilege>, Token)> {
+    let mut privileges = vec![self.parse_privilege()?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      privileges.push(self.parse_privilege()?);
+    }
+    self.consume_expect_token(TokenType::On)?;
+    self.match_token_and_consume(TokenType::Table);
+    Ok((privileges, self.consume_expect_token(TokenType::Identifier)?))
+  }
+
+  fn parse_privilege(&mut self) -> ParseResult<Privilege> {
+    if self.match_keyword_and_consume("ALL").is_some() {
+      self.match_keyword_and_consume("PRIVILEGES");
+      return Ok(Privilege::All);
+    }
+    let token = self.consume_token();
+    let privilege = match token.kind {
+      TokenType::Select => Privilege::Select,
+      TokenType::Insert => Privilege::Insert,
+      TokenType::Update => Privilege::Update,
+      TokenType::Delete => Privilege::Delete,
+      TokenType::Truncate => Privilege::Truncate,
+      _ => {
+        let message = format!("expected a privilege like 'SELECT' or 'ALL' but found '{}'", token.kind);
+        return Err(self.error("E0102", message, &token));
+      }
+    };
+    Ok(privilege)
+  }
+
+  // WITH [RECURSIVE] tree [(id, parent_id)] AS (base UNION ALL step), ... then the query reading them
+  fn parse_with_statement(&mut self) -> ParseResult<WithStatement> {
+    let with_range = self.consume_expect_token(TokenType::With)?.range;
+    let recursive = self.match_token_and_consume(TokenType::Recursive).is_some();
+    let mut ctes = vec![self.parse_common_table_expression()?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      ctes.push(self.parse_common_table_expression()?);
+    }
+    let body = self.parse_statement()?;
+    let range = range_from(&with_range, &body.get_range());
+    Ok(WithStatement::new(recursive, ctes, body, range))
+  }
+
+  fn parse_common_table_expression(&mut self) -> ParseResult<CommonTableExpression> {
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    let mut columns = vec![];
+    if self.match_token_and_consume(TokenType::LeftParen).is_some() {
+      columns.push(self.consume_expect_token(TokenType::Identifier)?);
+      while self.match_token_and_consume(TokenType::Comma).is_some() {
+        columns.push(self.consume_expect_token(TokenType::Identifier)?);
+      }
+      self.consume_expect_token(TokenType::RightParen)?;
+    }
+    self.consume_expect_token(TokenType::As)?;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let query = self.parse_statement()?;
+    let mut unions = vec![];
+    while self.match_token_and_consume(TokenType::Union).is_some() {
+      let all = self.match_keyword_and_consume("ALL").is_some();
+      unions.push(UnionBranch { all, query: self.parse_statement()? });
+    }
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    let range = range_from(&name.range, &end);
+    Ok(CommonTableExpression::new(name, columns, query, unions, range))
+  }
+
+  // BEGIN [TRANSACTION | WORK], COMMIT [WORK], ROLLBACK [WORK] [TO [SAVEPOINT] s],
+  // SAVEPOINT s, RELEASE [SAVEPOINT] s
+  fn parse_transaction_statement(&mut self) -> ParseResult<TransactionStatement> {
+    let token = self.consume_token();
+    let kind = match token.kind {
+      TokenType::Begin => {
+        if self.match_keyword_and_consume("TRANSACTION").is_none() {
+          self.match_keyword_and_consume("WORK");
+        }
+        TransactionKind::Begin
+      }
+      TokenType::Commit => {
+        self.match_keyword_and_consume("WORK");
+        TransactionKind::Commit
+      }
+      TokenType::Rollback => {
+        self.match_keyword_and_consume("WORK");
+        match self.match_token_and_consume(TokenType::To) {
+          Some(_) => TransactionKind::RollbackToSavepoint,
+          None => TransactionKind::Rollback,
+        }
+      }
+      TokenType::Savepoint => TransactionKind::Savepoint,
+      _ => TransactionKind::ReleaseSavepoint,
+    };
+    let savepoint = match kind {
+      TransactionKind::Savepoint => Some(self.consume_expect_token(TokenType::Identifier)?),
+      TransactionKind::ReleaseSavepoint | TransactionKind::RollbackToSavepoint => {
+        self.match_token_and_consume(TokenType::Savepoint);
+        Some(self.consume_expect_token(TokenType::Identifier)?)
+      }
+      _ => None,
+    };
+    let range = range_from(&token.range, &self.current_range());
+    Ok(TransactionStatement::new(kind, savepoint, range))
+  }
+
+  // the roles or users a privilege goes to or is taken from, PUBLIC for everyone
+  fn parse_grantees(&mut self) -> ParseResult<Vec<Token>> {
+    let mut grantees = vec![self.consume_expect_token(TokenType::Identifier)?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      grantees.push(self.consume_expect_token(TokenType::Identifier)?);
+    }
+    Ok(grantees)
+  }
+
+  fn parse_column_definition(&mut self) -> ParseResult<ColumnDefinition> {
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    let data_type = self.parse_data_type()?;
+    let not_null = self.match_token_and_consume(TokenType::Not).is_some();
+    if not_null {
+      self.consume_expect_token(TokenType::Null)?;
+    }
+    let range = range_from(&name.range, &self.current_range());
+    Ok(ColumnDefinition::new(name, data_type, not_null, range))
+  }
+
+  // WINDOW w AS (PARTITION BY a ORDER BY b), v AS (...)
+  fn parse_window_clause(&mut self) -> ParseResult<WindowClause> {
+    let window_range = self.consume_expect_token(TokenType::Window)?.range;
+    let mut definitions = vec![self.parse_window_definition()?];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      definitions.push(self.parse_window_definition()?);
+    }
+    let range = range_from(&window_range, &definitions.last().unwrap().range);
+    Ok(WindowClause::new(definitions, range))
+  }
+
+  fn parse_window_definition(&mut self) -> ParseResult<WindowDefinition> {
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    self.consume_expect_token(TokenType::As)?;
+    let spec = self.parse_window_spec(&name.range)?;
+    let range = range_from(&name.range, &spec.range);
+    Ok(WindowDefinition::new(name, spec, range))
+  }
+
+  fn parse_group_by_clause(&mut self) -> ParseResult<GroupByClause> {
+    let group_range = self.consume_expect_token(TokenType::Group)?.range;
+    self.consume_expect_token(TokenType::By)?;
+    if self.match_any_token(&[TokenType::Rollup, TokenType::Cube, TokenType::Grouping]) {
+      return self.parse_group_by_modifier(group_range);
+    }
+    let mut columns = vec![];
+    let ends = [TokenType::Order, TokenType::Limit, TokenType::Semicolon, TokenType::Pipe, TokenType::RightParen];
+    while !self.match_any_token(&ends) && !self.is_end() {
+      columns.push(self.parse_column_expression()?);
+      let Some(comma) = self.match_token_and_consume(TokenType::Comma) else { break };
+      self.check_trailing_comma(&comma, &ends);
+    }
     if !columns.is_empty() {
       let last_range = columns.last().unwrap().get_range();
       let range = range_from(&group_range, &last_range);
-      return GroupByClause::new(columns, range);
+      return Ok(GroupByClause::new(columns, range));
     }
-    GroupByClause::new(columns, group_range)
+    Ok(GroupByClause::new(columns, group_range))
   }
 
-  fn parse_order_clause(&mut self) -> OrderClause {
-    let order_range = self.consume_expect_token(TokenType::Order).range;
-    self.consume_expect_token(TokenType::By);
+  // ROLLUP(a, b), CUBE(a, b) or GROUPING SETS ((a, b), a, ())
+  fn parse_group_by_modifier(&mut self, group_range: Range) -> ParseResult<GroupByClause> {
+    let (columns, modifier, end) = match self.consume_token().kind {
+      TokenType::Rollup => {
+        let (columns, end) = self.parse_grouping_set()?;
+        (columns, GroupByModifier::Rollup, end)
+      }
+      TokenType::Cube => {
+        let (columns, end) = self.parse_grouping_set()?;
+        (columns, GroupByModifier::Cube, end)
+      }
+      _ => {
+        self.consume_expect_token(TokenType::Sets)?;
+        self.consume_expect_token(TokenType::LeftParen)?;
+        let mut sets = vec![];
+        loop {
+          if self.match_token(&TokenType::LeftParen) {
+            sets.push(self.parse_grouping_set()?.0);
+          } else {
+            sets.push(vec![self.parse_column_expression()?]);
+          }
+          if self.match_token_and_consume(TokenType::Comma).is_none() {
+            break;
+          }
+        }
+        let end = self.consume_expect_token(TokenType::RightParen)?.range;
+        (vec![], GroupByModifier::GroupingSets(sets), end)
+      }
+    };
+    let mut group_by = GroupByClause::new(columns, range_from(&group_range, &end));
+    group_by.with_modifier(modifier);
+    Ok(group_by)
+  }
+
+  // `(a, b)` or `()`, with the range of its closing paren
+  fn parse_grouping_set(&mut self) -> ParseResult<(Vec<Expression>, Range)> {
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut columns = vec![];
+    while !self.match_token(&TokenType::RightParen) && !self.is_end() {
+      columns.push(self.parse_column_expression()?);
+      if self.match_token_and_consume(TokenType::Comma).is_none() {
+        break;
+      }
+    }
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok((columns, end))
+  }
+
+  fn parse_order_clause(&mut self) -> ParseResult<OrderClause> {
+    let order_range = self.consume_expect_token(TokenType::Order)?.range;
+    self.consume_expect_token(TokenType::By)?;
     let mut columns = vec![];
 
     while !self.match_token(&TokenType::Limit) && !self.is_end() {
-      columns.push(self.parse_order_column());
+      columns.push(self.parse_order_column()?);
       if self.match_token_and_consume(TokenType::Comma).is_none() {
         break;
       }
@@ -215,16 +896,17 @@ impl<'a> Parser<'a> {
     if !columns.is_empty() {
       let last_range = columns.last().unwrap().get_range();
       let range = range_from(&order_range, &last_range);
-      return OrderClause::new(columns, range);
+      return Ok(OrderClause::new(columns, range));
     }
-    OrderClause::new(columns, self.current_range())
+    Ok(OrderClause::new(columns, range_from(&order_range, &self.current_range())))
   }
 
-  fn parse_limit_clause(&mut self) -> LimitClause {
-    let limit_range = self.consume_expect_token(TokenType::Limit).range;
-    let count = self.parse_number_literal();
-    let offset = if self.match_token_and_consume(TokenType::Comma).is_some() {
-      Some(self.parse_number_literal())
+  fn parse_limit_clause(&mut self) -> ParseResult<LimitClause> {
+    let limit_range = self.consume_expect_token(TokenType::Limit)?.range;
+    let count = self.parse_number_literal()?;
+    let comma_offset = self.match_token_and_consume(TokenType::Comma).is_some();
+    let offset = if comma_offset || self.match_token_and_consume(TokenType::Offset).is_some() {
+      Some(self.parse_number_literal()?)
     } else {
       None
     };
@@ -234,147 +916,458 @@ impl<'a> Parser<'a> {
       range = range_from(&range, &offset.range);
     }
 
-    LimitClause::new(count, offset, range)
+    let mut limit = LimitClause::new(count, offset, range);
+    if comma_offset {
+      limit.with_comma_offset();
+    }
+    Ok(limit)
+  }
+
+  fn parse_expression(&mut self) -> ParseResult<Expression> {
+    let mut expression = self.parse_term()?;
+    while self.match_any_token(&[TokenType::Plus, TokenType::Minus]) {
+      let operator = if self.consume_token().kind == TokenType::Plus { Operator::Plus } else { Operator::Minus };
+      let right = self.parse_term()?;
+      expression = Expression::create_condition(expression, operator, right);
+    }
+    self.parse_in_expression(expression)
+  }
+
+  // `price * quantity`, binding tighter than `+` and `-`
+  fn parse_term(&mut self) -> ParseResult<Expression> {
+    let mut expression = self.parse_cast_operand()?;
+    while self.match_any_token(&[TokenType::Asterisk, TokenType::Slash, TokenType::Percent]) {
+      let operator = match self.consume_token().kind {
+        TokenType::Asterisk => Operator::Multiply,
+        TokenType::Slash => Operator::Divide,
+        _ => Operator::Modulo,
+      };
+      let right = self.parse_cast_operand()?;
+      expression = Expression::create_condition(expression, operator, right);
+    }
+    Ok(expression)
+  }
+
+  // `x::date`, the cast binds tighter than any operator and can be chained
+  fn parse_cast_operand(&mut self) -> ParseResult<Expression> {
+    let mut expression = self.parse_primary_expression()?;
+    while self.match_token_and_consume(TokenType::DoubleColon).is_some() {
+      let data_type = self.parse_data_type()?;
+      let range = range_from(&expression.get_range(), &data_type.range);
+      expression = Expression::Cast(CastExpression::new(expression, data_type, CastStyle::DoubleColon, range));
+    }
+    Ok(expression)
+  }
+
+  // CAST(x AS date)
+  fn parse_cast_function(&mut self) -> ParseResult<Expression> {
+    let cast_range = self.consume_expect_token(TokenType::Cast)?.range;
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let expression = self.parse_expression()?;
+    self.consume_expect_token(TokenType::As)?;
+    let data_type = self.parse_data_type()?;
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    let range = range_from(&cast_range, &end);
+    Ok(Expression::Cast(CastExpression::new(expression, data_type, CastStyle::CastFunction, range)))
+  }
+
+  // a type name with optional parameters, `varchar(10)` is kept as a single token
+  fn parse_data_type(&mut self) -> ParseResult<Token> {
+    let name = self.consume_expect_token(TokenType::Identifier)?;
+    if !self.match_token(&TokenType::LeftParen) {
+      return Ok(name);
+    }
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut parameters = vec![self.parse_number_literal()?.raw];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      parameters.push(self.parse_number_literal()?.raw);
+    }
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    let text = format!("{}({})", name.lexeme.unwrap_or_default(), parameters.join(", "));
+    Ok(Token::new(TokenType::Identifier, Some(text), range_from(&name.range, &end)))
+  }
+
+  // `x IN (...)` or `x NOT IN (...)`, a NOT after an operand can only start a NOT IN
+  fn parse_in_expression(&mut self, expression: Expression) -> ParseResult<Expression> {
+    let negated = self.match_token_and_consume(TokenType::Not).is_some();
+    if !negated && !self.match_token(&TokenType::In) {
+      return Ok(expression);
+    }
+    self.consume_expect_token(TokenType::In)?;
+    let left_paren_range = self.consume_expect_token(TokenType::LeftParen)?.range;
+    let mut values = vec![];
+    let subquery = if self.match_any_token(&[TokenType::Select, TokenType::From]) {
+      Some(self.parse_statement()?)
+    } else {
+      while !self.match_token(&TokenType::RightParen) && !self.is_end() {
+        values.push(self.parse_expression()?);
+        if self.match_token_and_consume(TokenType::Comma).is_none() {
+          break;
+        }
+      }
+      None
+    };
+    let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
+    if let Some(statement) = subquery {
+      values.push(Expression::create_subquery(statement, range_from(&left_paren_range, &right_paren_range)));
+    }
+    let range = range_from(&expression.get_range(), &right_paren_range);
+    Ok(Expression::create_in(expression, negated, values, range))
   }
 
-  fn parse_expression(&mut self) -> Expression {
-    let token = self.lexer.peek_token();
+  // NOT (condition), the unary NOT in front of a whole condition
+  fn parse_not_expression(&mut self) -> ParseResult<Expression> {
+    let not_range = self.consume_expect_token(TokenType::Not)?.range;
+    if self.match_token_and_consume(TokenType::LeftParen).is_none() {
+      let mut expression = self.parse_expression()?;
+      // `NOT a = 1` negates the whole comparison
+      if self.match_comparison_operator() {
+        expression = self.parse_comparison(expression)?;
+      }
+      let range = range_from(&not_range, &expression.get_range());
+      return Ok(Expression::create_not(expression, range));
+    }
+    let condition = self.parse_boolean_expression()?;
+    let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(Expression::create_not(condition, range_from(&not_range, &right_paren_range)))
+  }
+
+  fn parse_primary_expression(&mut self) -> ParseResult<Expression> {
+    let token = self.peek_token();
     match token.kind {
       TokenType::Identifier => self.parse_column_or_function_call(),
       TokenType::Number => {
-        let literal = self.parse_number_literal();
-        Expression::create_literal(Literal::Number(literal))
+        let literal = self.parse_number_literal()?;
+        Ok(Expression::create_literal(Literal::Number(literal)))
       }
       TokenType::String => {
-        let literal = self.parse_string_literal();
-        Expression::create_literal(Literal::String(literal))
+        let literal = self.parse_string_literal()?;
+        Ok(Expression::create_literal(Literal::String(literal)))
       }
-      TokenType::LeftParen => self.parse_subquery_expression(),
-      _ => self.report_unexpected_token(token),
+      TokenType::CurrentTimestamp => self.parse_current_timestamp(TimestampStyle::CurrentTimestamp),
+      TokenType::CurrentDate => self.parse_current_timestamp(TimestampStyle::CurrentDate),
+      TokenType::CurrentTime => self.parse_current_timestamp(TimestampStyle::CurrentTime),
+      TokenType::Interval => {
+        let literal = self.parse_interval_literal()?;
+        Ok(Expression::create_literal(Literal::Interval(literal)))
+      }
+      TokenType::LeftParen => self.parse_parenthesized_expression(),
+      TokenType::Not => self.parse_not_expression(),
+      TokenType::Cast => self.parse_cast_function(),
+      _ if token.is_aggregate_function() => self.parse_aggregate_call(),
+      _ => Err(self.unexpected_token(token)),
     }
   }
 
-  fn parse_column_or_function_call(&mut self) -> Expression {
-    let identifier = self.consume_expect_token(TokenType::Identifier);
+  fn parse_column_or_function_call(&mut self) -> ParseResult<Expression> {
+    let identifier = self.consume_expect_token(TokenType::Identifier)?;
 
-    if self.match_token_and_consume(TokenType::LeftParen).is_some() {
-      let mut arguments = vec![];
-      while !self.match_token(&TokenType::RightParen) && !self.is_end() {
-        let argument = self.parse_expression();
-        arguments.push(argument);
-        self.match_token_and_consume(TokenType::Comma);
-      }
-      self.consume_expect_token(TokenType::RightParen);
-      Expression::create_function_call(identifier, arguments, self.current_range())
+    if self.match_token(&TokenType::LeftParen) {
+      self.parse_function_call(identifier)
+    } else if self.match_token_and_consume(TokenType::Dot).is_some() {
+      let column = self.consume_expect_token(TokenType::Identifier)?;
+      Ok(Expression::create_column(Some(identifier), column))
     } else {
-      let mut column = None;
-      if self.match_token_and_consume(TokenType::Dot).is_some() {
-        column = Some(self.consume_expect_token(TokenType::Identifier));
+      Ok(Expression::create_column(None, identifier))
+    }
+  }
+
+  // aggregate names are keywords, but inside expressions they are plain function calls
+  fn parse_aggregate_call(&mut self) -> ParseResult<Expression> {
+    let token = self.consume_token();
+    let name = Token::new(TokenType::Identifier, Some(token.kind.to_string()), token.range);
+    self.parse_function_call(name)
+  }
+
+  fn parse_function_call(&mut self, name: Token) -> ParseResult<Expression> {
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut arguments = vec![];
+    while !self.match_token(&TokenType::RightParen) && !self.is_end() {
+      let argument = self.parse_expression()?;
+      arguments.push(argument);
+      self.match_token_and_consume(TokenType::Comma);
+    }
+    let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
+    let range = range_from(&name.range, &right_paren_range);
+    if arguments.is_empty() && name.lexeme.as_deref().is_some_and(|name| name.eq_ignore_ascii_case("NOW")) {
+      return Ok(Expression::create_current_timestamp(TimestampStyle::Now, range));
+    }
+    let function = FunctionCallExpression::new(name, arguments, range);
+    if self.match_token(&TokenType::Over) {
+      return self.parse_window_function(function);
+    }
+    Ok(Expression::FunctionCall(function))
+  }
+
+  // OVER ([PARTITION BY a, b] [ORDER BY c DESC]) | OVER w
+  fn parse_window_function(&mut self, function: FunctionCallExpression) -> ParseResult<Expression> {
+    let over_range = self.consume_expect_token(TokenType::Over)?.range;
+    if let Some(name) = self.match_token_and_consume(TokenType::Identifier) {
+      let range = range_from(&function.get_range(), &name.range);
+      return Ok(Expression::Window(WindowFunctionExpression::named(function, name, range)));
+    }
+    let spec = self.parse_window_spec(&over_range)?;
+    let range = range_from(&function.get_range(), &spec.range);
+    Ok(Expression::Window(WindowFunctionExpression::new(function, spec, range)))
+  }
+
+  // ([PARTITION BY a, b] [ORDER BY c DESC]), ranging from `start` to the `)`
+  fn parse_window_spec(&mut self, start: &Range) -> ParseResult<WindowSpec> {
+    self.consume_expect_token(TokenType::LeftParen)?;
+    let mut partition_by = vec![];
+    if self.match_token_and_consume(TokenType::Partition).is_some() {
+      self.consume_expect_token(TokenType::By)?;
+      partition_by.push(self.parse_expression()?);
+      while self.match_token_and_consume(TokenType::Comma).is_some() {
+        partition_by.push(self.parse_expression()?);
       }
-      Expression::create_column(column, identifier)
     }
+    let mut order_by = vec![];
+    if self.match_token_and_consume(TokenType::Order).is_some() {
+      self.consume_expect_token(TokenType::By)?;
+      order_by.push(self.parse_order_column()?);
+      while self.match_token_and_consume(TokenType::Comma).is_some() {
+        order_by.push(self.parse_order_column()?);
+      }
+    }
+    let end = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(WindowSpec::new(partition_by, order_by, range_from(start, &end)))
+  }
+
+  // CURRENT_TIMESTAMP and friends take no parentheses
+  fn parse_current_timestamp(&mut self, style: TimestampStyle) -> ParseResult<Expression> {
+    let token = self.consume_token();
+    Ok(Expression::create_current_timestamp(style, token.range))
+  }
+
+  // `(SELECT ...)` is a subquery, `(a, b)` a row value, anything else in parentheses only groups
+  fn parse_parenthesized_expression(&mut self) -> ParseResult<Expression> {
+    let left_paren_range = self.consume_expect_token(TokenType::LeftParen)?.range;
+    if self.match_any_token(&[TokenType::Select, TokenType::From]) {
+      return self.parse_subquery_expression(left_paren_range);
+    }
+    let expression = self.parse_boolean_expression()?;
+    if !self.match_token(&TokenType::Comma) {
+      self.consume_expect_token(TokenType::RightParen)?;
+      return Ok(expression);
+    }
+    let mut items = vec![expression];
+    while self.match_token_and_consume(TokenType::Comma).is_some() {
+      items.push(self.parse_boolean_expression()?);
+    }
+    let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
+    Ok(Expression::create_tuple(items, range_from(&left_paren_range, &right_paren_range)))
   }
 
-  fn parse_subquery_expression(&mut self) -> Expression {
-    let left_paren_range = self.consume_expect_token(TokenType::LeftParen).range;
-    let statement = self.parse_statement();
+  fn parse_subquery_expression(&mut self, left_paren_range: Range) -> ParseResult<Expression> {
+    let statement = self.parse_statement()?;
     // if self.match_token(&TokenType::Select) {
-    //   let select = self.parse_select_statement();
-    //   let right_paren_range = self.consume_expect_token(TokenType::RightParen).range;
+    //   let select = self.parse_select_statement()?;
+    //   let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
     //   let range = range_from(&left_paren_range, &right_paren_range);
     //   Expression::create_subquery(select, range)
     // } else {
-    //   let expression = self.parse_expression();
-    //   let right_paren_range = self.consume_expect_token(TokenType::RightParen).range;
+    //   let expression = self.parse_expression()?;
+    //   let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
     //   let range = range_from(&left_paren_range, &right_paren_range);
     //   expression
     // }
-    let right_paren_range = self.consume_expect_token(TokenType::RightParen).range;
+    let right_paren_range = self.consume_expect_token(TokenType::RightParen)?.range;
     let range = range_from(&left_paren_range, &right_paren_range);
-    Expression::create_subquery(statement, range)
+    Ok(Expression::create_subquery(statement, range))
   }
 
-  fn parse_column_expression(&mut self) -> Expression {
-    let column_name = self.consume_expect_token(TokenType::Identifier);
-    let mut table_name = None;
+  fn parse_column_expression(&mut self) -> ParseResult<Expression> {
+    let name = self.consume_expect_token(TokenType::Identifier)?;
     if self.match_token_and_consume(TokenType::Dot).is_some() {
-      table_name = Some(self.consume_expect_token(TokenType::Identifier));
+      let column_name = self.consume_expect_token(TokenType::Identifier)?;
+      return Ok(Expression::create_column(Some(name), column_name));
     }
-    Expression::create_column(table_name, column_name)
+    Ok(Expression::create_column(None, name))
   }
 
-  fn parse_condition_expression(&mut self) -> Expression {
-    let left = self.parse_expression();
-    let operator = self.parse_operator();
-    let right = self.parse_expression();
-    Expression::create_condition(left, operator, right)
+  // OR binds looser than AND, which binds looser than NOT and the comparisons
+  fn parse_boolean_expression(&mut self) -> ParseResult<Expression> {
+    let mut expression = self.parse_and_expression()?;
+    while self.match_token_and_consume(TokenType::Or).is_some() {
+      let right = self.parse_and_expression()?;
+      expression = Expression::create_condition(expression, Operator::Or, right);
+    }
+    Ok(expression)
   }
 
-  fn parse_operator(&mut self) -> Operator {
+  fn parse_and_expression(&mut self) -> ParseResult<Expression> {
+    let mut expression = self.parse_condition_expression()?;
+    while self.match_token_and_consume(TokenType::And).is_some() {
+      let right = self.parse_condition_expression()?;
+      expression = Expression::create_condition(expression, Operator::And, right);
+    }
+    Ok(expression)
+  }
+
+  fn parse_condition_expression(&mut self) -> ParseResult<Expression> {
+    let left = self.parse_expression()?;
+    // a predicate, a parenthesized condition or a bare boolean operand like `active AND ...`
+    if left.is_predicate() || !self.match_comparison_operator() {
+      return Ok(left);
+    }
+    self.parse_comparison(left)
+  }
+
+  // comparisons do not associate, a second operator after one is reported rather than parsed
+  fn parse_comparison(&mut self, left: Expression) -> ParseResult<Expression> {
+    let operator = self.parse_operator()?;
+    let operator_range = self.current_range();
+    let right = self.parse_expression()?;
+    if self.match_comparison_operator() {
+      return Err(self.chained_comparison(&left, &operator_range, &right)?);
+    }
+    Ok(Expression::create_condition(left, operator, right))
+  }
+
+  // `a < b < c` is no range test in sql, it would compare the boolean `a < b` with `c`
+  fn chained_comparison(
+    &mut self,
+    left: &Expression,
+    operator: &Range,
+    middle: &Expression,
+  ) -> ParseResult<Diagnostic> {
+    self.parse_operator()?;
+    let second_operator = self.current_range();
+    let right = self.parse_expression()?;
+    let range = range_from(&left.get_range(), &right.get_range());
+    let raw = self.lexer.get_source().raw;
+    let text = |range: &Range| raw.get(range.start..range.end).unwrap_or_default().to_string();
+    let (middle, second_operator) = (text(&middle.get_range()), text(&second_operator));
+    let (left, operator, right) = (text(&left.get_range()), text(operator), text(&right.get_range()));
+    let split = format!("{} {} {} AND {} {} {}", left, operator, middle, middle, second_operator, right);
+    let mut error = Diagnostic::error("E0105", "comparisons cannot be chained".to_string(), range.clone());
+    error.hint = Some("compare each pair on its own and join them with AND".to_string());
+    Ok(error.with_suggestion(Replacement::new(range, split)))
+  }
+
+  fn match_comparison_operator(&mut self) -> bool {
+    self.match_any_token(&[
+      TokenType::Equal,
+      TokenType::NotEqual,
+      TokenType::LessThan,
+      TokenType::GreaterThan,
+      TokenType::LessThanOrEqual,
+      TokenType::GreaterThanOrEqual,
+    ])
+  }
+
+  fn parse_operator(&mut self) -> ParseResult<Operator> {
     let token = self.consume_token();
-    match token.kind {
+    let operator = match token.kind {
       TokenType::Equal => Operator::Equal,
       TokenType::NotEqual => Operator::NotEqual,
       TokenType::LessThan => Operator::LessThan,
       TokenType::GreaterThan => Operator::GreaterThan,
       TokenType::LessThanOrEqual => Operator::LessThanOrEqual,
       TokenType::GreaterThanOrEqual => Operator::GreaterThanOrEqual,
-      TokenType::And => Operator::And,
-      TokenType::Or => Operator::Or,
-      _ => self.report_unexpected_token(token),
-    }
+      _ => return Err(self.unexpected_token(token)),
+    };
+    Ok(operator)
   }
 
-  fn parse_order_column(&mut self) -> OrderColumn {
-    let column = self.parse_column_expression();
+  fn parse_order_column(&mut self) -> ParseResult<OrderColumn> {
+    // `ORDER BY 2` sorts by the second selected column
+    let column = if self.match_token(&TokenType::Number) {
+      Expression::create_literal(Literal::Number(self.parse_number_literal()?))
+    } else {
+      self.parse_column_expression()?
+    };
     let direction = if self.match_token_and_consume(TokenType::Desc).is_some() {
       OrderDirection::Desc
     } else {
+      // ASC is the default, an explicit one only needs consuming
+      self.match_token_and_consume(TokenType::Asc);
       OrderDirection::Asc
     };
-    OrderColumn::new(column, direction)
+    Ok(OrderColumn::new(column, direction))
   }
 
-  fn parse_number_literal(&mut self) -> NumberLiteral {
-    let token = self.consume_expect_token(TokenType::Number);
-    if token.lexeme.is_none() {
-      self.report_token_with_message("expected number literal".to_string(), token);
-    }
-    let value = token.lexeme.unwrap();
-    NumberLiteral::new(value, token.range)
+  fn parse_number_literal(&mut self) -> ParseResult<NumberLiteral> {
+    let token = self.consume_expect_token(TokenType::Number)?;
+    let Some(value) = token.lexeme else {
+      return Err(self.error("E0103", "expected number literal".to_string(), &token));
+    };
+    Ok(NumberLiteral::new(value, token.range))
   }
 
-  fn parse_string_literal(&mut self) -> StringLiteral {
-    let token = self.consume_expect_token(TokenType::String);
-    if token.lexeme.is_none() {
-      self.report_token_with_message("expected string literal".to_string(), token);
-    }
-    let value = token.lexeme.unwrap();
-    StringLiteral::new(value, token.range)
+  fn parse_string_literal(&mut self) -> ParseResult<StringLiteral> {
+    let token = self.consume_expect_token(TokenType::String)?;
+    let Some(value) = token.lexeme else {
+      return Err(self.error("E0103", "expected string literal".to_string(), &token));
+    };
+    Ok(StringLiteral::new(value, token.range))
   }
 
-  fn consume_expect_token(&mut self, kind: TokenType) -> Token {
+  // INTERVAL '7 days' | INTERVAL '7' DAY
+  fn parse_interval_literal(&mut self) -> ParseResult<IntervalLiteral> {
+    let interval_range = self.consume_expect_token(TokenType::Interval)?.range;
+    let literal = self.parse_string_literal()?;
+    let mut range = range_from(&interval_range, &literal.range);
+    let (value, unit) = match literal.value.trim().split_once(char::is_whitespace) {
+      Some((value, unit)) => (value.to_string(), IntervalUnit::from_name(unit.trim())),
+      None => {
+        let token = self.consume_expect_token(TokenType::Identifier)?;
+        range = range_from(&range, &token.range);
+        (literal.value.trim().to_string(), token.lexeme.as_deref().and_then(IntervalUnit::from_name))
+      }
+    };
+    let Some(unit) = unit else {
+      let message = "expected interval unit (SECOND, MINUTE, HOUR, DAY, WEEK, MONTH, YEAR)".to_string();
+      return Err(Diagnostic::error("E0104", message, range));
+    };
+    Ok(IntervalLiteral::new(value, unit, range))
+  }
+
+  fn consume_expect_token(&mut self, kind: TokenType) -> ParseResult<Token> {
     let token = self.consume_token();
     if token.kind != kind {
-      let message = format!("expected '{}' but found '{}'", kind.to_string(), token.kind.to_string());
-      self.report_error(message, token);
+      if token.is_error() {
+        return Err(self.lexer_error(&token));
+      }
+      let message = format!("expected '{}' but found '{}'", kind, token.kind);
+      return Err(self.error("E0102", message, &token));
     }
-    token
+    Ok(token)
   }
 
   fn consume_token(&mut self) -> Token {
-    self.lexer.next_token()
+    self.collect_comments();
+    let token = self.lexer.next_token();
+    self.last_range = token.range.clone();
+    token
+  }
+
+  fn peek_token(&mut self) -> Token {
+    self.collect_comments();
+    self.lexer.peek_token()
   }
 
   fn match_token(&mut self, kind: &TokenType) -> bool {
-    self.lexer.peek_token().kind == *kind
+    self.peek_token().kind == *kind
   }
 
   fn match_any_token(&mut self, kinds: &[TokenType]) -> bool {
-    let token = self.lexer.peek_token();
+    let token = self.peek_token();
     kinds.contains(&token.kind)
   }
 
+  // words such as ADD or RENAME are only keywords where a statement expects them, elsewhere they name columns
+  fn match_keyword_and_consume(&mut self, keyword: &str) -> Option<Token> {
+    let token = self.peek_token();
+    let is_keyword = token.lexeme.as_deref().is_some_and(|lexeme| lexeme.eq_ignore_ascii_case(keyword));
+    if token.kind == TokenType::Identifier && is_keyword {
+      Some(self.consume_token())
+    } else {
+      None
+    }
+  }
+
   fn match_token_and_consume(&mut self, kind: TokenType) -> Option<Token> {
     if self.match_token(&kind) {
       Some(self.consume_token())
@@ -387,30 +1380,103 @@ impl<'a> Parser<'a> {
     self.match_token(&TokenType::EOF)
   }
 
-  fn skip_comments(&mut self) {
+  fn collect_comments(&mut self) {
     while self.lexer.peek_token().is_comment() {
-      self.lexer.next_token();
+      let token = self.lexer.next_token();
+      let placement = if token.is_trailing_comment() { CommentPlacement::Trailing } else { CommentPlacement::Leading };
+      let text = token.lexeme.unwrap_or_default();
+      self.pending_comments.push(Comment::new(text, placement, token.range));
+    }
+  }
+
+  // records the error and skips to the next `;`, `|>` or clause keyword
+  fn recover(&mut self, error: Diagnostic) {
+    self.diagnostics.push(error);
+    while !self.is_end() && !self.match_any_token(&SYNC_TOKENS) {
+      let token = self.consume_token();
+      // bad text inside the skipped tokens is still reported, once
+      if token.is_error() && !self.diagnostics.iter().any(|error| error.range == token.range) {
+        let error = self.lexer_error(&token);
+        self.diagnostics.push(error);
+      }
+    }
+  }
+
+  // `a, b,` right before the end of a list, the comma is dropped with a warning instead of failing
+  // after AS only a name can follow, so a keyword there, e.g. `AS COUNT`, is read as one with a warning;
+  // keywords are uppercase only, so the lowercase spelling is a plain name
+  fn parse_identifier_or_keyword_as_identifier(&mut self) -> ParseResult<Token> {
+    let token = self.peek_token();
+    let raw = self.lexer.get_source().raw;
+    let text = raw.get(token.range.start..token.range.end).unwrap_or_default().to_string();
+    let word = text.starts_with(|c: char| c.is_ascii_alphabetic())
+      && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if token.kind == TokenType::Identifier || token.is_error() || !word {
+      return self.consume_expect_token(TokenType::Identifier);
     }
+    self.consume_token();
+    let message = format!("`{}` is a keyword, it is read as a name here", text);
+    let mut warning = Diagnostic::warning("W0102", message, token.range.clone());
+    warning.hint = Some(format!("write it as `{}` to make it a plain name", text.to_lowercase()));
+    self.diagnostics.push(warning.with_suggestion(Replacement::new(token.range.clone(), text.to_lowercase())));
+    Ok(Token::new(TokenType::Identifier, Some(text), token.range))
+  }
+
+  fn check_trailing_comma(&mut self, comma: &Token, ends: &[TokenType]) {
+    if !self.match_any_token(ends) && !self.is_end() {
+      return;
+    }
+    let message = "trailing comma at the end of the list".to_string();
+    let warning = Diagnostic::warning("W0101", message, comma.range.clone());
+    self.diagnostics.push(warning.with_suggestion(Replacement::new(comma.range.clone(), String::new())));
   }
 
   fn current_range(&self) -> Range {
-    Range::default()
+    self.last_range.clone()
   }
 
-  fn report_unexpected_token(&self, token: Token) -> ! {
-    let message = format!("unexpected token '{}'", token.kind.to_string());
-    self.report_error(message, token)
+  fn unexpected_token(&self, token: Token) -> Diagnostic {
+    if token.is_error() {
+      return self.lexer_error(&token);
+    }
+    let message = format!("unexpected token '{}'", token.kind);
+    self.error("E0101", message, &token)
   }
 
-  fn report_token_with_message(&self, message: String, token: Token) -> ! {
-    self.report_error(message, token)
+  fn error(&self, code: &'static str, message: String, token: &Token) -> Diagnostic {
+    Diagnostic::error(code, message, token.range.clone())
   }
 
-  fn report_error(&self, message: String, token: Token) -> ! {
-    report_and_exit(&message, &token.range, &self.lexer.get_source())
+  // the lexer already described the text behind an `Error` token
+  fn lexer_error(&self, token: &Token) -> Diagnostic {
+    let errors = self.lexer.get_errors().iter();
+    let error = errors.rev().find(|error| error.range == token.range);
+    error.cloned().unwrap_or_else(|| self.error("E0001", "unexpected character".to_string(), token))
   }
 
   pub fn get_source(&self) -> &Source<'a> {
     self.lexer.get_source()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // three queries with a typo each, the parser goes on after each one
+  #[test]
+  fn reports_every_syntax_error() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/playground/syntax_errors.sql");
+    let raw = std::fs::read_to_string(path).unwrap();
+    let source = Source::new("syntax_errors.sql", &raw);
+    let (program, errors) = Parser::new(&mut Lexer::new(&source)).parse();
+    let found = errors.iter().map(|error| (error.code, error.range.start..error.range.end)).collect::<Vec<_>>();
+    assert_eq!(found, [("E0101", 26..27), ("E0102", 75..77), ("E0102", 137..140)]);
+    assert_eq!(&raw[26..27], ">");
+    assert_eq!(&raw[75..77], "ON");
+    assert_eq!(&raw[137..140], "ten");
+    // what comes before each typo is kept, and each query still ends at its own `;`
+    assert_eq!(program.statements.len(), 3);
+    assert_eq!(program.terminated, [true, true, false]);
+  }
+}
@@ -0,0 +1,103 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// An indented outline of a node, built from its JSON so every node the serde derives know is covered:
+// Where 97..121
+//   condition: Condition
+//     left: Column 103..115
+//       column: Identifier `total_amount` 103..115
+//     operator: GreaterThan
+//     right: Number `100` 118..121
+pub fn to_tree(node: &impl Serialize) -> String {
+  let value = serde_json::to_value(node).expect("nodes always serialize");
+  let mut text = String::new();
+  match &value {
+    // the program itself has no kind, its statements and comments are the top of the outline
+    Value::Object(fields) if !fields.contains_key("kind") => write_fields(&mut text, fields, 0),
+    _ => {
+      text.push_str(&format!("{}\n", headline(&value)));
+      if let Value::Object(fields) = &value {
+        write_fields(&mut text, fields, 1);
+      }
+    }
+  }
+  text
+}
+
+// the headline goes on the line already started, after a space unless there is nothing to show
+fn write_node(text: &mut String, value: &Value, depth: usize) {
+  let headline = headline(value);
+  if !headline.is_empty() {
+    text.push(' ');
+    text.push_str(&headline);
+  }
+  text.push('\n');
+  if let Value::Object(fields) = value {
+    write_fields(text, fields, depth + 1);
+  }
+}
+
+fn write_fields(text: &mut String, fields: &Map<String, Value>, depth: usize) {
+  for (name, value) in fields {
+    if is_inline(name) || is_empty(value) {
+      continue;
+    }
+    text.push_str(&format!("{}{}:", "  ".repeat(depth), name));
+    match value {
+      Value::Array(items) => {
+        text.push('\n');
+        for item in items {
+          text.push_str(&format!("{}-", "  ".repeat(depth + 1)));
+          write_node(text, item, depth + 1);
+        }
+      }
+      _ => write_node(text, value, depth),
+    }
+  }
+}
+
+// the kind of a node with what identifies it on the same line: its text and where it is
+fn headline(value: &Value) -> String {
+  let fields = match value {
+    Value::Object(fields) => fields,
+    Value::String(text) => return text.clone(),
+    _ => return value.to_string(),
+  };
+  if let Some(range) = range(value) {
+    return range;
+  }
+  let mut parts = vec![];
+  if let Some(Value::String(kind)) = fields.get("kind") {
+    parts.push(kind.clone());
+  }
+  for key in ["lexeme", "raw", "text"] {
+    if let Some(Value::String(written)) = fields.get(key) {
+      parts.push(format!("`{}`", written.trim()));
+    }
+  }
+  if let Some(range) = fields.get("range").and_then(range) {
+    parts.push(range);
+  }
+  parts.join(" ")
+}
+
+// `{"start":1,"end":4}` reads as `1..4`
+fn range(value: &Value) -> Option<String> {
+  let fields = value.as_object().filter(|fields| fields.len() == 2)?;
+  let (start, end) = (fields.get("start")?.as_u64()?, fields.get("end")?.as_u64()?);
+  Some(format!("{}..{}", start, end))
+}
+
+// what the headline already shows
+fn is_inline(name: &str) -> bool {
+  matches!(name, "kind" | "lexeme" | "raw" | "text" | "range")
+}
+
+// absent options and empty lists would only add noise
+fn is_empty(value: &Value) -> bool {
+  match value {
+    Value::Null => true,
+    Value::Array(items) => items.is_empty(),
+    _ => false,
+  }
+}
@@ -0,0 +1,63 @@
+use std::io::{self, BufRead, Write};
+
+use crate::compiler::compile;
+use crate::config::Config;
+use crate::diagnostics::maneger::ReportFilter;
+use crate::emiter::{Dialect, EmitOptions, ToSql};
+use crate::utils::source::Source;
+use crate::utils::ColorMode;
+
+// Reads queries from `input`, compiling each one when a blank line ends it, until EOF.
+// Every query is independent of the ones before it.
+pub fn run_repl(input: impl BufRead, mut output: impl Write, color: ColorMode) -> io::Result<()> {
+  let mut query = String::new();
+  for line in input.lines() {
+    let line = line?;
+    if !line.trim().is_empty() {
+      query.push_str(&line);
+      query.push('\n');
+      continue;
+    }
+    if !query.trim().is_empty() {
+      compile_query(&query, &mut output, color)?;
+    }
+    query.clear();
+  }
+  if !query.trim().is_empty() {
+    compile_query(&query, &mut output, color)?;
+  }
+  Ok(())
+}
+
+fn compile_query(query: &str, output: &mut impl Write, color: ColorMode) -> io::Result<()> {
+  let source = Source::new("<repl>", query);
+  let (program, diagnostics) = compile(&source, None, &[], &Config::default());
+  diagnostics.report(&source, color, &ReportFilter::default());
+  if let Some(program) = program.filter(|_| !diagnostics.contains_error()) {
+    writeln!(output, "{}", program.to_sql(Dialect::default(), &EmitOptions::default()))?;
+  }
+  output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn repl(input: &str) -> String {
+    let mut output = vec![];
+    run_repl(input.as_bytes(), &mut output, ColorMode::Never).unwrap();
+    String::from_utf8(output).unwrap()
+  }
+
+  #[test]
+  fn compiles_each_query_a_blank_line_ends() {
+    let output = repl("FROM users\n|> WHERE age > 30\n|> SELECT id;\n\nFROM orders |> SELECT total\n");
+    assert_eq!(output, "SELECT id FROM users WHERE age > 30\nSELECT total FROM orders\n");
+  }
+
+  #[test]
+  fn skips_queries_that_do_not_compile() {
+    let output = repl("FROM users |> SELECT\n\n\n\nFROM users |> SELECT name\n");
+    assert_eq!(output, "SELECT name FROM users\n");
+  }
+}
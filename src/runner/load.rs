@@ -0,0 +1,28 @@
+use crate::csv_table::{ColumnKind, CsvTable};
+
+// the statements that create a `--table` and insert its rows, one per row
+pub fn statements(table: &CsvTable) -> impl Iterator<Item = String> + '_ {
+  let columns = table.columns.iter().map(|(column, kind)| format!("{} {}", quote_name(column), kind.emit()));
+  let create = format!("CREATE TABLE {} ({})", quote_name(&table.name), columns.collect::<Vec<_>>().join(", "));
+  let inserts = table.rows.iter().map(|row| {
+    let values = row.iter().zip(&table.columns).map(|(value, (_, kind))| literal(value, *kind));
+    format!("INSERT INTO {} VALUES ({})", quote_name(&table.name), values.collect::<Vec<_>>().join(", "))
+  });
+  std::iter::once(create).chain(inserts)
+}
+
+// values past the sampled rows may not fit the column, those are kept as text
+fn literal(value: &str, kind: ColumnKind) -> String {
+  if value.is_empty() {
+    return "NULL".to_string();
+  }
+  match (kind, ColumnKind::of(value)) {
+    (ColumnKind::Boolean, ColumnKind::Boolean) => (value.eq_ignore_ascii_case("true") as u8).to_string(),
+    (ColumnKind::Integer | ColumnKind::Real, found) if kind.merge(found).is_some() => value.to_string(),
+    _ => format!("'{}'", value.replace('\'', "''")),
+  }
+}
+
+fn quote_name(name: &str) -> String {
+  format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use crate::cli::RunOptions;
+use crate::csv_table::CsvTable;
+use crate::diagnostics::maneger::Diagnostic;
+use crate::emiter::Dialect;
+use crate::utils::range::{range_from, Range};
+
+mod load;
+#[cfg(feature = "mysql")]
+mod mysql;
+mod output;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+use output::Output;
+
+// what a query returned, the column names and each row
+pub struct Rows {
+  pub names: Vec<String>,
+  pub values: Vec<Vec<Value>>,
+}
+
+// one value as every output format needs it, numbers keep the database's text so no digit is lost
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+  Null,
+  Bool(bool),
+  Number(String),
+  Text(String),
+}
+
+// a connection `hoshi run` sends the emitted sql through, one query at a time
+pub trait Database {
+  // the rows of a query that returns any, nothing for the others
+  fn query(&mut self, sql: &str) -> Result<Option<Rows>, String>;
+}
+
+pub struct Runner {
+  database: Box<dyn Database>,
+  output: Output,
+}
+
+impl Runner {
+  // the sqlite file of `--db`, or the server of `--url`
+  pub fn open(options: &RunOptions) -> Result<Self, String> {
+    let target = options.url.as_deref().unwrap_or(&options.db);
+    let timeout = options.timeout.map(Duration::from_secs);
+    let opened = match options.dialect() {
+      #[cfg(feature = "sqlite")]
+      Dialect::Sqlite => sqlite::open(target, timeout),
+      #[cfg(feature = "postgres")]
+      Dialect::Postgres => postgres::open(target, timeout),
+      #[cfg(feature = "mysql")]
+      Dialect::MySql => mysql::open(target, timeout),
+      dialect => {
+        let name = dialect.name();
+        return Err(format!(
+          "this hoshi was built without {0}, build it with `--features {0}` to run against it",
+          name
+        ));
+      }
+    };
+    let database = opened.map_err(|err| format!("cannot open '{}': {}", target, err))?;
+    Ok(Runner { database, output: Output::open(options)? })
+  }
+
+  // creates the `--table` tables and fills them in one transaction, before any query runs
+  pub fn load(&mut self, tables: &[CsvTable]) -> Result<(), String> {
+    if tables.is_empty() {
+      return Ok(());
+    }
+    self.database.query("BEGIN")?;
+    for table in tables {
+      if let Err(err) = load::statements(table).try_for_each(|sql| self.database.query(&sql).map(|_| ())) {
+        let _ = self.database.query("ROLLBACK");
+        return Err(format!("cannot load the table `{}`: {}", table.name, err));
+      }
+    }
+    self.database.query("COMMIT").map(|_| ())
+  }
+
+  // prints the rows of each query in order, stopping at the first one the database rejects
+  pub fn run(&mut self, queries: &[(String, Range)], transaction: bool) -> Result<(), Diagnostic> {
+    if !transaction {
+      return queries.iter().try_for_each(|(sql, range)| self.execute(sql, range));
+    }
+    // the transaction has no statement of its own, so its errors point at all of them
+    let first = queries.first().map(|(_, range)| range.clone()).unwrap_or_default();
+    let last = queries.last().map(|(_, range)| range.clone()).unwrap_or_default();
+    let whole = range_from(&first, &last);
+    self.execute("BEGIN", &whole)?;
+    if let Err(error) = queries.iter().try_for_each(|(sql, range)| self.execute(sql, range)) {
+      // the rejected statement is what gets reported, not a rollback failing after it
+      let _ = self.database.query("ROLLBACK");
+      return Err(error);
+    }
+    self.execute("COMMIT", &whole)
+  }
+
+  fn execute(&mut self, sql: &str, range: &Range) -> Result<(), Diagnostic> {
+    let rows = self.database.query(sql).map_err(|err| Diagnostic::error("E0301", err, range.clone()))?;
+    if let Some(rows) = rows {
+      self.output.write(&rows).unwrap_or_else(|err| panic!("ERROR: cannot write the rows: {}", err));
+    }
+    Ok(())
+  }
+}
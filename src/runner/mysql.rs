@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use mysql_async::consts::ColumnType;
+use mysql_async::prelude::Queryable;
+use mysql_async::{Column, Conn, Row};
+use tokio::runtime::Runtime;
+
+use super::{Database, Rows, Value};
+
+// the driver is async, a runtime on this thread waits on it one query at a time
+pub fn open(url: &str, timeout: Option<Duration>) -> Result<Box<dyn Database>, String> {
+  let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().map_err(|err| err.to_string())?;
+  let connection = runtime.block_on(async {
+    let connecting = Conn::from_url(url);
+    match timeout {
+      Some(timeout) => tokio::time::timeout(timeout, connecting).await.map_err(|_| "timed out connecting".to_string()),
+      None => Ok(connecting.await),
+    }
+  })?;
+  let connection = connection.map_err(message)?;
+  Ok(Box::new(MySql { runtime, connection }))
+}
+
+struct MySql {
+  runtime: Runtime,
+  connection: Conn,
+}
+
+impl Database for MySql {
+  fn query(&mut self, sql: &str) -> Result<Option<Rows>, String> {
+    self.runtime.block_on(rows(&mut self.connection, sql)).map_err(message)
+  }
+}
+
+async fn rows(connection: &mut Conn, sql: &str) -> mysql_async::Result<Option<Rows>> {
+  let mut result = connection.query_iter(sql).await?;
+  // no columns at all means the statement was never going to return rows
+  let columns = result.columns_ref().to_vec();
+  let rows = result.collect::<Row>().await?;
+  result.drop_result().await?;
+  if columns.is_empty() {
+    return Ok(None);
+  }
+  let names = columns.iter().map(|column| column.name_str().into_owned()).collect();
+  let values = rows.into_iter().map(|row| row.unwrap().into_iter().zip(&columns).map(format_value).collect());
+  Ok(Some(Rows { names, values: values.collect() }))
+}
+
+// the server's own message, without the driver's wrapping around it
+fn message(err: mysql_async::Error) -> String {
+  match err {
+    mysql_async::Error::Server(error) => error.message,
+    err => err.to_string(),
+  }
+}
+
+// queries come back over the text protocol, so numbers are told apart by their column's type
+fn format_value((value, column): (mysql_async::Value, &Column)) -> Value {
+  use mysql_async::Value as MySqlValue;
+  match value {
+    MySqlValue::NULL => Value::Null,
+    MySqlValue::Bytes(bytes) if is_boolean(column) => Value::Bool(bytes != b"0"),
+    MySqlValue::Bytes(bytes) if is_numeric(column.column_type()) => {
+      Value::Number(String::from_utf8_lossy(&bytes).into())
+    }
+    MySqlValue::Bytes(bytes) => Value::Text(String::from_utf8_lossy(&bytes).into_owned()),
+    MySqlValue::Int(integer) => Value::Number(integer.to_string()),
+    MySqlValue::UInt(integer) => Value::Number(integer.to_string()),
+    MySqlValue::Float(float) => Value::Number(float.to_string()),
+    MySqlValue::Double(double) => Value::Number(double.to_string()),
+    MySqlValue::Date(year, month, day, hour, minute, second, micros) => {
+      let date = format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, hour, minute, second);
+      Value::Text(with_micros(date, micros))
+    }
+    MySqlValue::Time(negative, days, hours, minutes, seconds, micros) => {
+      let sign = if negative { "-" } else { "" };
+      let time = format!("{}{:02}:{:02}:{:02}", sign, days * 24 + u32::from(hours), minutes, seconds);
+      Value::Text(with_micros(time, micros))
+    }
+  }
+}
+
+fn with_micros(text: String, micros: u32) -> String {
+  match micros {
+    0 => text,
+    micros => format!("{}.{:06}", text, micros),
+  }
+}
+
+// BOOL is a TINYINT(1) and comes back as 0 or 1
+fn is_boolean(column: &Column) -> bool {
+  column.column_type() == ColumnType::MYSQL_TYPE_TINY && column.column_length() == 1
+}
+
+fn is_numeric(column_type: ColumnType) -> bool {
+  matches!(
+    column_type,
+    ColumnType::MYSQL_TYPE_TINY
+      | ColumnType::MYSQL_TYPE_SHORT
+      | ColumnType::MYSQL_TYPE_INT24
+      | ColumnType::MYSQL_TYPE_LONG
+      | ColumnType::MYSQL_TYPE_LONGLONG
+      | ColumnType::MYSQL_TYPE_FLOAT
+      | ColumnType::MYSQL_TYPE_DOUBLE
+      | ColumnType::MYSQL_TYPE_DECIMAL
+      | ColumnType::MYSQL_TYPE_NEWDECIMAL
+  )
+}
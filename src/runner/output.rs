@@ -0,0 +1,137 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use super::{Rows, Value};
+use crate::cli::{ResultFormat, RunOptions};
+
+// where and how `hoshi run` prints the rows of each query
+pub struct Output {
+  format: ResultFormat,
+  max_col_width: Option<usize>,
+  limit: Option<usize>,
+  writer: Box<dyn Write>,
+}
+
+impl Output {
+  pub fn open(options: &RunOptions) -> Result<Self, String> {
+    let writer: Box<dyn Write> = match &options.out {
+      Some(path) => {
+        let file = File::create(path).map_err(|err| format!("cannot write '{}': {}", path, err))?;
+        Box::new(BufWriter::new(file))
+      }
+      None => Box::new(io::stdout()),
+    };
+    let (format, max_col_width, limit) = (options.output, options.max_col_width, options.limit_display);
+    Ok(Output { format, max_col_width, limit, writer })
+  }
+
+  pub fn write(&mut self, rows: &Rows) -> io::Result<()> {
+    let shown = self.limit.map_or(rows.values.len(), |limit| limit.min(rows.values.len()));
+    let text = match self.format {
+      ResultFormat::Table => table(rows, shown, self.max_col_width),
+      ResultFormat::Csv => csv(rows, shown),
+      ResultFormat::Json => json(rows, shown),
+    };
+    // the table notes the cut in its footer, csv and json have no room for it
+    if shown < rows.values.len() && self.format != ResultFormat::Table {
+      eprintln!("showing {} of {} rows", shown, rows.values.len());
+    }
+    self.writer.write_all(text.as_bytes())?;
+    self.writer.flush()
+  }
+}
+
+// the psql layout, columns padded to their widest value and the row count at the end
+fn table(rows: &Rows, shown: usize, max_col_width: Option<usize>) -> String {
+  let cell = |text: String| {
+    // a line break would split the row, so it is shown escaped
+    let text = text.replace('\n', "\\n");
+    match max_col_width {
+      Some(width) if text.chars().count() > width => {
+        let kept = text.chars().take(width.saturating_sub(1)).collect::<String>();
+        format!("{}…", kept)
+      }
+      _ => text,
+    }
+  };
+  let names = rows.names.iter().map(|name| cell(name.clone())).collect::<Vec<_>>();
+  let values = rows.values[..shown].iter().map(|row| row.iter().map(|value| cell(plain(value))).collect());
+  let values = values.collect::<Vec<Vec<_>>>();
+  let widths = names.iter().enumerate().map(|(index, name)| {
+    let widths = values.iter().map(|row| row[index].chars().count());
+    widths.chain(std::iter::once(name.chars().count())).max().unwrap_or_default()
+  });
+  let widths = widths.collect::<Vec<_>>();
+  let line = |values: &[String]| {
+    let cells = values.iter().zip(&widths).map(|(value, width)| format!("{:<width$}", value, width = width));
+    format!("{}\n", cells.collect::<Vec<_>>().join(" | ").trim_end())
+  };
+  let mut table = line(&names);
+  table.push_str(&widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-+-"));
+  table.push('\n');
+  values.iter().for_each(|row| table.push_str(&line(row)));
+  let total = rows.values.len();
+  let count = match (shown, total) {
+    (_, 1) => "(1 row)".to_string(),
+    (shown, total) if shown < total => format!("(showing {} of {} rows)", shown, total),
+    (_, total) => format!("({} rows)", total),
+  };
+  table.push_str(&count);
+  table.push_str("\n\n");
+  table
+}
+
+// a header line and one line per row, each ended by CRLF as RFC 4180 asks, NULL as an empty field
+fn csv(rows: &Rows, shown: usize) -> String {
+  let field = |text: &str| {
+    if text.contains([',', '"', '\r', '\n']) {
+      format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+      text.to_string()
+    }
+  };
+  let mut csv = rows.names.iter().map(|name| field(name)).collect::<Vec<_>>().join(",");
+  csv.push_str("\r\n");
+  for row in &rows.values[..shown] {
+    let fields = row.iter().map(|value| match value {
+      Value::Null => String::new(),
+      value => field(&plain(value)),
+    });
+    csv.push_str(&fields.collect::<Vec<_>>().join(","));
+    csv.push_str("\r\n");
+  }
+  csv
+}
+
+// one object per line, keyed by column name in the order the query selected them
+fn json(rows: &Rows, shown: usize) -> String {
+  let key = |name: &String| serde_json::to_string(name).expect("strings always serialize");
+  let objects = rows.values[..shown].iter().map(|row| {
+    let fields = rows.names.iter().zip(row).map(|(name, value)| format!("{}: {}", key(name), json_value(value)));
+    format!("  {{{}}}", fields.collect::<Vec<_>>().join(", "))
+  });
+  let objects = objects.collect::<Vec<_>>();
+  if objects.is_empty() {
+    return "[]\n".to_string();
+  }
+  format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
+fn json_value(value: &Value) -> String {
+  match value {
+    Value::Null => "null".to_string(),
+    Value::Bool(boolean) => boolean.to_string(),
+    // NaN and Infinity are numbers to the database but not to JSON
+    Value::Number(number) if serde_json::from_str::<serde_json::Number>(number).is_ok() => number.clone(),
+    Value::Number(text) | Value::Text(text) => serde_json::to_string(text).expect("strings always serialize"),
+  }
+}
+
+// how the table and csv show a value
+fn plain(value: &Value) -> String {
+  match value {
+    Value::Null => "NULL".to_string(),
+    Value::Bool(boolean) => boolean.to_string(),
+    Value::Number(text) | Value::Text(text) => text.clone(),
+  }
+}
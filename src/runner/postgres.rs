@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::time::Duration;
+
+use postgres::types::Type;
+use postgres::{Client, Config, NoTls, SimpleQueryMessage};
+
+use super::{Database, Rows, Value};
+
+pub fn open(url: &str, timeout: Option<Duration>) -> Result<Box<dyn Database>, String> {
+  let mut config = url.parse::<Config>().map_err(|err| err.to_string())?;
+  if let Some(timeout) = timeout {
+    config.connect_timeout(timeout);
+  }
+  let client = config.connect(NoTls).map_err(message)?;
+  Ok(Box::new(Postgres { client }))
+}
+
+struct Postgres {
+  client: Client,
+}
+
+impl Database for Postgres {
+  // the simple protocol hands every value back as text, whatever its type
+  fn query(&mut self, sql: &str) -> Result<Option<Rows>, String> {
+    let messages = self.client.simple_query(sql).map_err(message)?;
+    let mut names = None;
+    let mut texts = vec![];
+    for message in messages {
+      match message {
+        SimpleQueryMessage::RowDescription(columns) => {
+          names = Some(columns.iter().map(|column| column.name().to_string()).collect());
+        }
+        SimpleQueryMessage::Row(row) => {
+          texts.push((0..row.len()).map(|index| row.get(index).map(str::to_string)).collect::<Vec<_>>());
+        }
+        _ => {}
+      }
+    }
+    let Some(names) = names else {
+      return Ok(None);
+    };
+    // preparing the query again only describes it, the types tell numbers and booleans from text
+    let statement = self.client.prepare(sql).map_err(message)?;
+    let types = statement.columns().iter().map(|column| column.type_().clone()).collect::<Vec<_>>();
+    let values =
+      texts.into_iter().map(|row| row.into_iter().zip(&types).map(|(text, kind)| format_value(text, kind)).collect());
+    Ok(Some(Rows { names, values: values.collect() }))
+  }
+}
+
+fn format_value(text: Option<String>, kind: &Type) -> Value {
+  let numeric = [Type::INT2, Type::INT4, Type::INT8, Type::FLOAT4, Type::FLOAT8, Type::NUMERIC, Type::OID];
+  match text {
+    None => Value::Null,
+    Some(text) if *kind == Type::BOOL => Value::Bool(text == "t"),
+    Some(text) if numeric.contains(kind) => Value::Number(text),
+    Some(text) => Value::Text(text),
+  }
+}
+
+// the server's own message, or what kept the client from reaching it
+fn message(err: postgres::Error) -> String {
+  if let Some(error) = err.as_db_error() {
+    return error.message().to_string();
+  }
+  match err.source() {
+    Some(source) => format!("{}: {}", err, source),
+    None => err.to_string(),
+  }
+}
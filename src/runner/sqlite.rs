@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+
+use super::{Database, Rows, Value};
+
+// `:memory:` gives a database that only lives for the run
+pub fn open(path: &str, timeout: Option<Duration>) -> Result<Box<dyn Database>, String> {
+  let connection = Connection::open(path).map_err(|err| err.to_string())?;
+  // how long to wait on a file another process holds locked
+  if let Some(timeout) = timeout {
+    connection.busy_timeout(timeout).map_err(|err| err.to_string())?;
+  }
+  Ok(Box::new(Sqlite { connection }))
+}
+
+struct Sqlite {
+  connection: Connection,
+}
+
+impl Database for Sqlite {
+  fn query(&mut self, sql: &str) -> Result<Option<Rows>, String> {
+    self.rows(sql).map_err(|err| err.to_string())
+  }
+}
+
+impl Sqlite {
+  fn rows(&self, sql: &str) -> rusqlite::Result<Option<Rows>> {
+    let mut statement = self.connection.prepare(sql)?;
+    if statement.column_count() == 0 {
+      statement.execute([])?;
+      return Ok(None);
+    }
+    let columns = statement.columns();
+    let names = columns.iter().map(|column| column.name().to_string()).collect::<Vec<_>>();
+    // the declared type is all that tells a BOOLEAN column from an integer one
+    let booleans = columns.iter().map(|column| column.decl_type().is_some_and(is_boolean)).collect::<Vec<_>>();
+    let mut rows = statement.query([])?;
+    let mut values = vec![];
+    while let Some(row) = rows.next()? {
+      let row = booleans
+        .iter()
+        .enumerate()
+        .map(|(index, boolean)| row.get_ref(index).map(|value| format_value(value, *boolean)));
+      values.push(row.collect::<rusqlite::Result<Vec<_>>>()?);
+    }
+    Ok(Some(Rows { names, values }))
+  }
+}
+
+// sqlite keeps booleans as the integers 0 and 1
+fn format_value(value: ValueRef, boolean: bool) -> Value {
+  match value {
+    ValueRef::Null => Value::Null,
+    ValueRef::Integer(integer) if boolean => Value::Bool(integer != 0),
+    ValueRef::Integer(integer) => Value::Number(integer.to_string()),
+    ValueRef::Real(real) => Value::Number(real.to_string()),
+    ValueRef::Text(text) => Value::Text(String::from_utf8_lossy(text).into_owned()),
+    ValueRef::Blob(blob) => Value::Text(format!("<{} bytes>", blob.len())),
+  }
+}
+
+fn is_boolean(decl_type: &str) -> bool {
+  decl_type.eq_ignore_ascii_case("BOOLEAN") || decl_type.eq_ignore_ascii_case("BOOL")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::compiler::compile;
+  use crate::config::Config;
+  use crate::csv_table::CsvTable;
+  use crate::emiter::{Dialect, EmitOptions};
+  use crate::runner::load;
+  use crate::utils::source::Source;
+
+  // the rows of each query of `raw`, compiled for sqlite and run in order
+  fn run(database: &mut Box<dyn Database>, raw: &str) -> Vec<Rows> {
+    let source = Source::new("test.sql", raw);
+    let config = Config { dialect: Dialect::Sqlite, ..Config::default() };
+    let (program, diagnostics) = compile(&source, None, &[], &config);
+    assert!(!diagnostics.contains_error(), "{} does not compile", raw);
+    let queries = program.unwrap().queries_sql(Dialect::Sqlite, &EmitOptions::default());
+    queries.iter().filter_map(|(sql, _)| database.query(sql).unwrap_or_else(|err| panic!("{}: {}", sql, err))).collect()
+  }
+
+  fn texts(rows: &Rows) -> Vec<Vec<String>> {
+    let text = |value: &Value| match value {
+      Value::Null => "NULL".to_string(),
+      Value::Bool(boolean) => boolean.to_string(),
+      Value::Number(number) | Value::Text(number) => number.clone(),
+    };
+    rows.values.iter().map(|row| row.iter().map(text).collect()).collect()
+  }
+
+  #[test]
+  fn runs_pipe_chains_in_memory() {
+    let mut database = open(":memory:", None).unwrap();
+    database.query("CREATE TABLE users (id INTEGER, name TEXT, age INTEGER)").unwrap();
+    database.query("INSERT INTO users VALUES (1, 'ana', 31), (2, 'rui', 25), (3, 'eva', 42)").unwrap();
+    let raw = "FROM users |> WHERE age > 30 |> SELECT id, name;\nFROM users |> WHERE age < 30 |> ORDER BY id LIMIT 1;";
+    let results = run(&mut database, raw);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].names, ["id", "name"]);
+    assert_eq!(texts(&results[0]), [["1", "ana"], ["3", "eva"]]);
+    assert_eq!(results[1].names, ["id", "name", "age"]);
+    assert_eq!(texts(&results[1]), [["2", "rui", "25"]]);
+  }
+
+  #[test]
+  fn runs_over_a_csv_table() {
+    let path = std::env::temp_dir().join(format!("hoshi-users-{}.csv", std::process::id()));
+    std::fs::write(&path, "id,name,age\n1,ana,31\n2,rui,25\n3,eva,42\n4,leo,9\n").unwrap();
+    let (table, notices) = CsvTable::read("users", path.to_str().unwrap(), true).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(notices.is_empty());
+    let mut database = open(":memory:", None).unwrap();
+    load::statements(&table).for_each(|sql| assert!(database.query(&sql).is_ok(), "{}", sql));
+    // the ages are compared as the integers they were loaded as, not as text
+    let results = run(&mut database, "FROM users |> WHERE age > 30 |> SELECT name |> ORDER BY name;");
+    assert_eq!(results[0].names, ["name"]);
+    assert_eq!(texts(&results[0]), [["ana"], ["eva"]]);
+  }
+
+  #[test]
+  fn shifts_dates_by_intervals() {
+    let mut database = open(":memory:", None).unwrap();
+    database.query("CREATE TABLE events (id INTEGER, at TEXT)").unwrap();
+    database
+      .query("INSERT INTO events VALUES (1, datetime('now', '-3 days')), (2, datetime('now', '-10 days'))")
+      .unwrap();
+    let raw = "FROM events |> WHERE at > CURRENT_TIMESTAMP - INTERVAL '7' DAY |> SELECT id;\n\
+               FROM events |> WHERE at + INTERVAL '1' WEEK < NOW() |> SELECT id;\n\
+               FROM events |> WHERE INTERVAL '2' WEEK + at > CURRENT_TIMESTAMP |> SELECT id |> ORDER BY id;";
+    let results = run(&mut database, raw);
+    assert_eq!(texts(&results[0]), [["1"]]);
+    assert_eq!(texts(&results[1]), [["2"]]);
+    assert_eq!(texts(&results[2]), [["1"], ["2"]]);
+  }
+}
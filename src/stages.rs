@@ -0,0 +1,90 @@
+use crate::emiter::{Dialect, EmitOptions, ToSql};
+use crate::parser::ast::*;
+use crate::utils::range::Range;
+
+// The sql a query adds up to after each of its stages, for seeing how a pipe chain desugars:
+// FROM orders                    -- query 1, stage 1: FROM orders
+// |> WHERE status = 'paid'  =>   FROM orders
+//                                -- query 1, stage 2: |> WHERE status = 'paid'
+//                                SELECT * FROM orders WHERE status = 'paid'
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Stage {
+  pub stage: String, // the stage as written
+  pub sql: String,   // the query up to and including it
+}
+
+// a chain is a left-leaning tree of pipes, so each `left` is the chain one stage shorter,
+// and every stage past the first is a query of its own, with the SELECT in front
+fn chain_stages(stmt: &Statement, raw: &str, dialect: Dialect) -> Vec<Stage> {
+  let Statement::Pipe(pipe) = stmt else {
+    let sql = stmt.to_sql(dialect, &EmitOptions::default());
+    return vec![Stage { stage: written(raw, &stmt.get_range()), sql }];
+  };
+  let sql = std::slice::from_ref(stmt).to_sql(dialect, &EmitOptions::default());
+  let mut stages = chain_stages(&pipe.left, raw, dialect);
+  stages.push(Stage { stage: format!("|> {}", written(raw, &pipe.right.get_range())), sql });
+  stages
+}
+
+// a query may go on after its chain, e.g. the GROUP BY closing an AGGREGATE, so those stages follow the whole chain
+pub fn stages(query: &[Statement], raw: &str, dialect: Dialect) -> Vec<Stage> {
+  let mut stages = vec![];
+  for (index, stmt) in query.iter().enumerate() {
+    let before = query[..index].to_sql(dialect, &EmitOptions::default());
+    for mut stage in chain_stages(stmt, raw, dialect) {
+      if !before.is_empty() {
+        stage.sql = format!("{} {}", before, stage.sql);
+      }
+      stages.push(stage);
+    }
+  }
+  stages
+}
+
+pub fn explain_pipes(program: &Program, raw: &str, dialect: Dialect) -> String {
+  let mut text = String::new();
+  for (index, query) in program.queries().into_iter().enumerate() {
+    for (position, stage) in stages(query, raw, dialect).into_iter().enumerate() {
+      text.push_str(&format!("-- query {}, stage {}: {}\n{}\n", index + 1, position + 1, stage.stage, stage.sql));
+    }
+  }
+  text
+}
+
+// the source of a stage on one line
+fn written(raw: &str, range: &Range) -> String {
+  let text = raw.get(range.start..range.end).unwrap_or_default();
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::Lexer;
+  use crate::parser::Parser;
+  use crate::utils::source::Source;
+
+  fn stage_sql(raw: &str) -> Vec<String> {
+    let source = Source::new("test.sql", raw);
+    let mut lexer = Lexer::new(&source);
+    let (program, errors) = Parser::new(&mut lexer).parse();
+    assert!(errors.is_empty(), "{} does not parse", raw);
+    let query = program.queries()[0];
+    stages(query, raw, Dialect::Ansi).into_iter().map(|stage| stage.sql).collect()
+  }
+
+  #[test]
+  fn stages_add_up_to_valid_queries() {
+    let sql = stage_sql("FROM t |> WHERE x = 1 |> SELECT id");
+    assert_eq!(sql, ["FROM t", "SELECT * FROM t WHERE x = 1", "SELECT id FROM t WHERE x = 1"]);
+  }
+
+  #[test]
+  fn stages_after_the_select_keep_it_in_front() {
+    let sql = stage_sql("FROM t |> SELECT id |> ORDER BY id |> LIMIT 3");
+    assert_eq!(
+      sql,
+      ["FROM t", "SELECT id FROM t", "SELECT id FROM t ORDER BY id ASC", "SELECT id FROM t ORDER BY id ASC LIMIT 3"]
+    );
+  }
+}
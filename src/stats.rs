@@ -0,0 +1,136 @@
+use serde::Serialize;
+
+use crate::lexer::Token;
+use crate::parser::ast::*;
+
+// what a program is made of, for auditing query files without compiling them
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+  pub statements: usize, // `;`-separated queries
+  pub joins: usize,
+  pub subqueries: usize,
+  pub aggregates: usize,   // AGGREGATE and PIVOT stages and aggregate calls in expressions
+  pub tables: Vec<String>, // every table read, once each in order of first use
+}
+
+impl Stats {
+  pub fn of(program: &Program) -> Self {
+    let mut stats = Stats { statements: program.queries().len(), ..Stats::default() };
+    program.statements.iter().for_each(|stmt| stats.count_statement(stmt));
+    stats
+  }
+
+  pub fn to_text(&self) -> String {
+    let mut text = format!("statements: {}\n", self.statements);
+    text.push_str(&format!("joins:      {}\n", self.joins));
+    text.push_str(&format!("subqueries: {}\n", self.subqueries));
+    text.push_str(&format!("aggregates: {}\n", self.aggregates));
+    text.push_str(&format!("tables:     {} ({})\n", self.tables.len(), self.tables.join(", ")));
+    text
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("stats always serialize")
+  }
+
+  fn count_statement(&mut self, stmt: &Statement) {
+    match stmt {
+      Statement::Select(select) => {
+        if let Some(from) = &select.from {
+          self.use_table(&from.table);
+        }
+        select.expressions.iter().for_each(|expr| self.count_expression(&expr.expression));
+      }
+      Statement::From(from) => self.use_table(&from.table),
+      Statement::Copy(copy) => self.use_table(&copy.table),
+      Statement::Truncate(truncate) => self.use_table(&truncate.table),
+      Statement::AlterTable(alter) => self.use_table(&alter.table),
+      Statement::CreateIndex(index) => self.use_table(&index.table),
+      Statement::DropIndex(index) => index.table.iter().for_each(|table| self.use_table(table)),
+      Statement::Grant(grant) => self.use_table(&grant.object),
+      Statement::Pragma(pragma) => pragma.table().iter().for_each(|table| self.use_table(table)),
+      Statement::Revoke(revoke) => self.use_table(&revoke.object),
+      Statement::CommentOn(comment) => match (comment.object_type, &comment.table) {
+        (CommentObjectType::Table, _) => self.use_table(&comment.name),
+        (_, table) => table.iter().for_each(|table| self.use_table(table)),
+      },
+      Statement::Insert(insert) => {
+        self.use_table(&insert.table);
+        match &insert.source {
+          InsertSource::Values(rows) => rows.iter().flatten().for_each(|value| self.count_expression(value)),
+          InsertSource::Select(query) => self.count_statement(query),
+        }
+      }
+      Statement::Join(join) => {
+        self.joins += 1;
+        self.use_table(&join.table);
+        self.count_expression(&join.on.left);
+        self.count_expression(&join.on.right);
+      }
+      Statement::Where(where_clause) => self.count_expression(&where_clause.condition),
+      Statement::Qualify(qualify) => self.count_expression(&qualify.condition),
+      Statement::GroupBy(group_by) => group_by.expressions().into_iter().for_each(|expr| self.count_expression(expr)),
+      Statement::Order(order) => order.columns.iter().for_each(|order| self.count_expression(&order.column)),
+      Statement::Aggregate(agg) => {
+        self.aggregates += 1;
+        self.count_expression(&agg.argument);
+        if let Some(filter) = &agg.filter {
+          self.count_expression(filter);
+        }
+        if let Some(within_group) = &agg.within_group {
+          within_group.order.columns.iter().for_each(|key| self.count_expression(&key.column));
+        }
+      }
+      Statement::Pivot(pivot) => {
+        self.aggregates += 1;
+        self.count_expression(&pivot.aggregate.argument);
+      }
+      Statement::Pipe(pipe) => {
+        self.count_statement(&pipe.left);
+        self.count_statement(&pipe.right);
+      }
+      Statement::Expression(expression) => self.count_expression(expression),
+      Statement::With(with) => with.queries().for_each(|query| self.count_statement(query)),
+      Statement::Limit(_) | Statement::Unpivot(_) | Statement::Window(_) | Statement::Transaction(_) => {}
+    }
+  }
+
+  fn count_expression(&mut self, expression: &Expression) {
+    match expression {
+      Expression::FunctionCall(call) => {
+        if call.is_aggregate() {
+          self.aggregates += 1;
+        }
+        call.arguments.iter().for_each(|argument| self.count_expression(argument));
+      }
+      Expression::Subquery(subquery) => {
+        self.subqueries += 1;
+        self.count_statement(&subquery.stmt);
+      }
+      Expression::Condition(condition) => {
+        self.count_expression(&condition.left);
+        self.count_expression(&condition.right);
+      }
+      Expression::In(in_) => {
+        self.count_expression(&in_.expression);
+        in_.values.iter().for_each(|value| self.count_expression(value));
+      }
+      Expression::Not(not) => self.count_expression(&not.expression),
+      Expression::Cast(cast) => self.count_expression(&cast.expression),
+      Expression::Tuple(tuple) => tuple.items.iter().for_each(|item| self.count_expression(item)),
+      Expression::Window(window) => {
+        window.function.arguments.iter().for_each(|argument| self.count_expression(argument));
+        window.spec.partition_by.iter().for_each(|expr| self.count_expression(expr));
+        window.spec.order_by.iter().for_each(|order| self.count_expression(&order.column));
+      }
+      Expression::Column(_) | Expression::Literal(_) | Expression::CurrentTimestamp(_) => {}
+    }
+  }
+
+  fn use_table(&mut self, table: &Token) {
+    let name = table.lexeme.clone().unwrap_or_default();
+    if !self.tables.contains(&name) {
+      self.tables.push(name);
+    }
+  }
+}
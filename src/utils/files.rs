@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+// what a directory is searched for when no `--extension` is given
+pub const SOURCE_EXTENSIONS: [&str; 2] = ["spipe", "sp"];
+
+// a file to compile or check, with where it sits under the directory it was found in
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+  pub path: String,
+  pub relative: PathBuf, // just the file name for a file named on the command line
+}
+
+// the files named on the command line, each directory replaced by the source files under it, in name order
+pub fn expand_paths(paths: &[&str], extensions: &[String]) -> Result<Vec<SourceFile>, String> {
+  let mut files = vec![];
+  for path in paths {
+    let root = Path::new(path);
+    if !root.is_dir() {
+      let relative = root.file_name().map_or_else(|| PathBuf::from(path), PathBuf::from);
+      files.push(SourceFile { path: path.to_string(), relative });
+      continue;
+    }
+    let mut found = vec![];
+    walk(root, extensions, &mut found).map_err(|err| format!("cannot read directory '{}': {}", path, err))?;
+    if found.is_empty() {
+      let patterns = extensions.iter().map(|extension| format!("*.{}", extension)).collect::<Vec<_>>();
+      return Err(format!("no {} files in '{}'", patterns.join(" or "), path));
+    }
+    found.sort();
+    for file in found {
+      let relative = file.strip_prefix(root).unwrap_or(&file).to_path_buf();
+      files.push(SourceFile { path: file.display().to_string(), relative });
+    }
+  }
+  Ok(files)
+}
+
+fn walk(dir: &Path, extensions: &[String], found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      walk(&path, extensions, found)?;
+    } else if path.extension().is_some_and(|extension| extensions.iter().any(|wanted| extension == wanted.as_str())) {
+      found.push(path);
+    }
+  }
+  Ok(())
+}
@@ -1,24 +1,62 @@
+use std::io::IsTerminal;
+
+pub mod files;
 pub mod range;
 pub mod source;
 
 pub fn match_number(character: char) -> bool {
-  "1234567890.".contains(character)
+  character.is_ascii_digit() || character == '.'
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ColorMode {
+  #[default]
+  Auto, // color when stdout is a terminal and NO_COLOR is unset
+  Always,
+  Never,
+}
+
+impl ColorMode {
+  // NO_COLOR only changes the automatic choice, an explicit `--color=always` still wins;
+  // diagnostics go to stderr, so that is the terminal asked
+  pub fn is_colored(&self) -> bool {
+    match self {
+      ColorMode::Always => true,
+      ColorMode::Never => false,
+      ColorMode::Auto => {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        !no_color && std::io::stderr().is_terminal()
+      }
+    }
+  }
 }
-pub fn highlight_text_with_red(text: &str) -> String {
-  format!("\x1b[31m{}\x1b[0m", text)
+
+fn paint(code: &str, text: &str, colored: bool) -> String {
+  if !colored {
+    return text.to_string();
+  }
+  format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+pub fn highlight_text_with_red(text: &str, colored: bool) -> String {
+  paint("31", text, colored)
 }
 
-pub fn highlight_text_with_yellow(text: &str) -> String {
-  format!("\x1b[33m{}\x1b[0m", text)
+pub fn highlight_text_with_yellow(text: &str, colored: bool) -> String {
+  paint("33", text, colored)
 }
-pub fn highlight_text_with_cyan(text: &str) -> String {
-  format!("\x1b[36m{}\x1b[0m", text)
+pub fn highlight_text_with_blue(text: &str, colored: bool) -> String {
+  paint("34", text, colored)
+}
+
+pub fn highlight_text_with_cyan(text: &str, colored: bool) -> String {
+  paint("36", text, colored)
 }
 
-pub fn highlight_text_with_white(text: &str) -> String {
-  format!("\x1b[97m{}\x1b[0m", text)
+pub fn highlight_text_with_white(text: &str, colored: bool) -> String {
+  paint("97", text, colored)
 }
 
-pub fn highlight_text_with_green(text: &str) -> String {
-  format!("\x1b[32m{}\x1b[0m", text)
+pub fn highlight_text_with_green(text: &str, colored: bool) -> String {
+  paint("32", text, colored)
 }
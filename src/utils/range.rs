@@ -2,24 +2,21 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Range {
   pub start: usize,
   pub end: usize,
 }
 
 impl Range {
+  // a backwards range means the operands of a `range_from` were swapped, caught in debug builds
   pub fn new(start: usize, end: usize) -> Range {
+    debug_assert!(start <= end, "backwards range {}..{}", start, end);
     Range { start, end }
   }
 }
 
-impl Default for Range {
-  fn default() -> Self {
-    Range { start: 0, end: 0 }
-  }
-}
-
+// `left` is the first part of the source the range covers, `right` the last
 pub fn range_from(left: &Range, right: &Range) -> Range {
   Range::new(left.start, right.end)
 }
@@ -1,10 +1,60 @@
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
 pub struct Source<'a> {
   pub raw: &'a str,
   pub path: &'a str,
+  pub tab_width: usize, // the columns a tab takes in a snippet, up to the next multiple of it
+  line_starts: Vec<usize>,
 }
 
 impl<'a> Source<'a> {
   pub fn new(path: &'a str, raw: &'a str) -> Source<'a> {
-    Source { path, raw }
+    let newlines = raw.match_indices('\n').map(|(offset, _)| offset + 1);
+    let line_starts = std::iter::once(0).chain(newlines).collect();
+    Source { path, raw, tab_width: DEFAULT_TAB_WIDTH, line_starts }
+  }
+
+  pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+    self.tab_width = tab_width.max(1);
+    self
+  }
+
+  // 1-based line and column, the column counts characters rather than bytes
+  pub fn offset_to_line_col(&self, offset: usize) -> (usize, usize) {
+    let (line, line_start, offset) = self.line_of(offset);
+    let column = self.raw.get(line_start..offset).map_or(offset - line_start, |text| text.chars().count());
+    (line + 1, column + 1)
+  }
+
+  // 0-based line and column, the column counts utf-16 code units like the language server protocol
+  pub fn offset_to_utf16_position(&self, offset: usize) -> (usize, usize) {
+    let (line, line_start, offset) = self.line_of(offset);
+    let column = self.raw.get(line_start..offset).map_or(offset - line_start, |text| text.encode_utf16().count());
+    (line, column)
+  }
+
+  // the 0-based line holding `offset`
+  pub fn line_index(&self, offset: usize) -> usize {
+    self.line_of(offset).0
+  }
+
+  // where the 0-based `line` starts, and its text without the line break
+  pub fn line(&self, line: usize) -> (usize, &'a str) {
+    let start = self.line_starts[line];
+    let end = self.line_starts.get(line + 1).map_or(self.raw.len(), |next| next - 1);
+    let text = &self.raw[start..end];
+    (start, text.strip_suffix('\r').unwrap_or(text))
+  }
+
+  // the 0-based line holding `offset`, where it starts, and `offset` clamped to the source
+  fn line_of(&self, offset: usize) -> (usize, usize, usize) {
+    let offset = offset.min(self.raw.len());
+    let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+    (line, self.line_starts[line], offset)
+  }
+
+  pub fn location(&self, offset: usize) -> String {
+    let (line, column) = self.offset_to_line_col(offset);
+    format!("{}:{}:{}", self.path, line, column)
   }
 }
@@ -0,0 +1,10 @@
+-- the whole product is the one argument of SUM
+FROM orders
+|> AGGREGATE SUM(price * quantity) AS revenue GROUP BY customer_id;
+
+-- `*`, `/` and `%` bind tighter than `+` and `-`, parentheses are kept only where they matter
+FROM orders
+|> AGGREGATE AVG((price - discount) * quantity / 100 + tax % 10) AS average GROUP BY customer_id;
+
+FROM orders
+|> SELECT id, price * (1 + tax) AS gross, price * quantity - discount AS net;
@@ -0,0 +1,4 @@
+-- postgres keeps FILTER, other dialects get COUNT(CASE WHEN status = 'active' THEN id END)
+FROM users
+|> AGGREGATE COUNT(id) FILTER (WHERE status = 'active') AS active_users
+GROUP BY country;
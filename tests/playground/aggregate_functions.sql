@@ -0,0 +1,7 @@
+FROM orders
+|> AGGREGATE ARRAY_AGG(id) AS order_ids
+|> AGGREGATE JSON_AGG(total_amount) AS totals
+|> AGGREGATE MEDIAN(total_amount)
+|> AGGREGATE MODE(status)
+|> AGGREGATE FIRST(created_at)
+|> AGGREGATE LAST(created_at)
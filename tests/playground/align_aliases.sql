@@ -0,0 +1,7 @@
+-- `fmt --align-aliases --stdout` puts each column on its own line with their `AS` lined up:
+-- FROM orders
+-- |> SELECT id                 AS order_id,
+--           (total_amount * 2) AS doubled,
+--           customer_id        AS customer;
+-- without the flag the select stays on one line
+FROM orders |> SELECT id AS order_id, (total_amount*2) AS doubled, customer_id AS customer;
@@ -0,0 +1,3 @@
+-- `--allow unused-alias` drops the warning on `c`, `--allow duplicate-alias` the error on the second `o`
+FROM orders AS o JOIN customers AS c ON o.customer_id = customers.id SELECT o.id;
+FROM orders AS o JOIN payments AS o ON o.id = o.order_id SELECT o.id;
@@ -0,0 +1,10 @@
+-- schema migrations, one change per ALTER TABLE
+ALTER TABLE orders ADD COLUMN note varchar(200) NOT NULL;
+ALTER TABLE orders ADD discount numeric(5, 2);
+ALTER TABLE orders DROP COLUMN note;
+ALTER TABLE orders RENAME COLUMN total TO total_amount;
+ALTER TABLE orders RENAME TO archived_orders;
+
+-- add and column still name columns outside ALTER TABLE
+FROM changes
+|> SELECT add, column;
@@ -0,0 +1,16 @@
+-- `hoshi ast ast.sql --statement 2` prints the second statement, `--format json` gives it as JSON
+-- Pipe
+--   left: From 443..453
+--     table: Identifier `users` 448..453
+--   right: Select 457..475
+--     distinct: None
+--     expressions:
+--       - 464..475
+--         expression: Column 464..468
+--           column: Identifier `name` 464..468
+--         alias: Identifier `who` 472..475
+FROM orders
+|> WHERE total > 100
+|> SELECT id;
+
+FROM users |> SELECT name AS who;
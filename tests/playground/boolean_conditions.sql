@@ -0,0 +1,22 @@
+-- AND binds tighter than OR, parentheses regroup them
+-- `check` warns about each WHERE with an OR (W0222, the `or-in-where` rule)
+FROM orders
+|> WHERE status = 'paid' AND total_amount > 100 OR status = 'refunded'
+|> SELECT id;
+
+FROM orders
+|> WHERE status = 'paid' AND (total_amount > 100 OR priority = 1)
+|> SELECT id;
+
+FROM orders
+|> WHERE NOT status = 'cancelled' AND (total_amount - (discount + 5)) > 0
+|> SELECT id;
+
+FROM orders
+|> WHERE NOT (status = 'cancelled' OR status = 'refunded') AND id IN (1, 2, 3)
+|> SELECT id;
+
+-- a leading group, and a subquery still parsed as one
+FROM orders
+|> WHERE (status = 'paid' OR status = 'shipped') AND total_amount > (SELECT AVG(total_amount) FROM orders)
+|> SELECT id;
@@ -0,0 +1,4 @@
+-- `::` is postgres only, the other dialects get CAST
+FROM orders
+|> WHERE created_at::date = '2024-01-01'::date
+|> SELECT id::text AS code, CAST(total_amount AS numeric(10, 2)) AS total, CAST(total_amount + 1 AS int) AS next;
@@ -0,0 +1,8 @@
+-- comparisons do not chain, each of these is an error with an AND to split it
+FROM orders
+|> WHERE 10 < total_amount < 100
+|> SELECT id;
+
+FROM orders
+|> WHERE status = 'paid' AND '2024-01-01' <= created_at < '2025-01-01'
+|> SELECT id;
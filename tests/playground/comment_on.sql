@@ -0,0 +1,7 @@
+-- documents the schema, postgres keeps these and the other dialects leave them out
+COMMENT ON TABLE users IS 'user accounts';
+COMMENT ON COLUMN users.email IS 'unique per tenant';
+COMMENT ON INDEX users_email IS 'backs the login lookup';
+
+FROM users
+|> SELECT id, email;
@@ -0,0 +1,4 @@
+-- bulk export and import, postgres only
+COPY orders TO '/tmp/orders.csv' WITH (FORMAT csv, DELIMITER ';', HEADER);
+
+COPY orders FROM STDIN;
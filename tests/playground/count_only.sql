@@ -0,0 +1,12 @@
+-- `hoshi compile --count-only` gives 2 statements, 2 joins, 1 subquery, 3 aggregates
+-- and 3 tables: orders, customers, products
+FROM orders
+|> JOIN customers ON orders.customer_id = customers.customer_id
+|> WHERE orders.total_amount > (SELECT AVG(total_amount) FROM orders)
+|> AGGREGATE COUNT(order_id) AS num_orders
+GROUP BY customers.customer_name;
+
+FROM orders
+|> JOIN products ON orders.product_id = products.product_id
+|> AGGREGATE SUM(orders.total_amount) AS revenue
+GROUP BY products.category;
@@ -0,0 +1,4 @@
+FROM sessions
+|> WHERE expires_at > CURRENT_TIMESTAMP
+|> WHERE started_at > NOW() - INTERVAL '1 day'
+|> SELECT id, CURRENT_DATE AS today
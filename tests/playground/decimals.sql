@@ -0,0 +1,6 @@
+-- integers, decimals and a trailing-dot decimal all lex as one number
+FROM orders
+|> WHERE total_amount > 1.
+|> WHERE discount < 0.25
+|> SELECT id, total_amount
+|> LIMIT 100;
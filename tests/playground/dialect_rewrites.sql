@@ -0,0 +1,4 @@
+-- outside ansi the QUALIFY becomes a subquery, an info that neither fails the run nor counts as a warning
+FROM employees
+|> QUALIFY ROW_NUMBER() OVER (PARTITION BY dept ORDER BY salary DESC) = 1
+|> SELECT name, dept;
@@ -0,0 +1,3 @@
+-- DISTINCT is a keyword, so each customer is listed once
+FROM orders
+|> SELECT DISTINCT customer_id;
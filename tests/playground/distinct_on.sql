@@ -0,0 +1,8 @@
+-- the latest order of each customer, postgres only
+FROM orders
+|> ORDER BY customer_id, created_at DESC
+|> SELECT DISTINCT ON (customer_id) customer_id, id, total_amount;
+
+-- plain DISTINCT runs everywhere
+FROM orders
+|> SELECT DISTINCT customer_id;
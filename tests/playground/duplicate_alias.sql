@@ -0,0 +1,3 @@
+FROM users AS u
+|> JOIN orders AS u ON u.id = u.user_id
+|> SELECT u.name;
@@ -0,0 +1,11 @@
+-- each select has its own projection, picking `id` in both is not a duplicate
+FROM users
+|> SELECT id, name;
+
+FROM orders
+|> SELECT id, total_amount
+|> SELECT id;
+
+-- within one projection it still is
+FROM users
+|> SELECT id, id;
@@ -0,0 +1,7 @@
+-- the warnings point back at the first `id` and the first read of `users`
+FROM users
+|> SELECT id, name, id;
+
+FROM users
+|> FROM users
+|> SELECT email
@@ -0,0 +1,4 @@
+-- `hoshi compile emit_ast.sql --emit ast --pretty` tags every node with its kind
+FROM orders
+|> WHERE total_amount > 100
+|> SELECT id, 'big' AS size;
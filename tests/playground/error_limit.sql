@@ -0,0 +1,23 @@
+-- one error per query, past the default limit of 20 the rest are counted in a single line
+FROM users |> WHERE age > 18 # 1 |> SELECT name;
+FROM users |> WHERE age > 18 # 2 |> SELECT name;
+FROM users |> WHERE age > 18 # 3 |> SELECT name;
+FROM users |> WHERE age > 18 # 4 |> SELECT name;
+FROM users |> WHERE age > 18 # 5 |> SELECT name;
+FROM users |> WHERE age > 18 # 6 |> SELECT name;
+FROM users |> WHERE age > 18 # 7 |> SELECT name;
+FROM users |> WHERE age > 18 # 8 |> SELECT name;
+FROM users |> WHERE age > 18 # 9 |> SELECT name;
+FROM users |> WHERE age > 18 # 10 |> SELECT name;
+FROM users |> WHERE age > 18 # 11 |> SELECT name;
+FROM users |> WHERE age > 18 # 12 |> SELECT name;
+FROM users |> WHERE age > 18 # 13 |> SELECT name;
+FROM users |> WHERE age > 18 # 14 |> SELECT name;
+FROM users |> WHERE age > 18 # 15 |> SELECT name;
+FROM users |> WHERE age > 18 # 16 |> SELECT name;
+FROM users |> WHERE age > 18 # 17 |> SELECT name;
+FROM users |> WHERE age > 18 # 18 |> SELECT name;
+FROM users |> WHERE age > 18 # 19 |> SELECT name;
+FROM users |> WHERE age > 18 # 20 |> SELECT name;
+FROM users |> WHERE age > 18 # 21 |> SELECT name;
+FROM users |> WHERE age > 18 # 22 |> SELECT name;
@@ -0,0 +1,9 @@
+-- `compile --explain-pipe` prints the sql after each stage:
+-- FROM orders
+-- SELECT * FROM orders WHERE status = 'paid'
+-- SELECT SUM(total_amount) AS spent FROM orders WHERE status = 'paid' GROUP BY customer_id
+FROM orders
+|> WHERE status = 'paid'
+|> AGGREGATE SUM(total_amount) AS spent GROUP BY customer_id;
+
+FROM users |> WHERE active = TRUE |> SELECT id, name |> ORDER BY name;
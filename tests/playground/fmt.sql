@@ -0,0 +1,7 @@
+-- `fmt` rewrites this file with one stage per line, as `fmt --stdout` shows:
+-- FROM orders
+-- |> WHERE total_amount > 10 AND status IN ('paid', 'sent') -- kept at the end of its line
+-- |> AGGREGATE SUM(total_amount * 2) AS spent
+-- GROUP BY customer_id;
+FROM   orders |>WHERE total_amount>10 AND status IN ( 'paid','sent' ) -- kept at the end of its line
+    |> AGGREGATE SUM( total_amount*2 )AS spent GROUP BY customer_id ;
@@ -0,0 +1,6 @@
+-- spipe:allow(access-control)
+GRANT SELECT, INSERT ON orders TO analyst, reporting WITH GRANT OPTION;
+-- spipe:allow(access-control)
+GRANT ALL PRIVILEGES ON TABLE customers TO admin;
+-- spipe:allow(access-control)
+REVOKE INSERT, DELETE ON orders FROM reporting;
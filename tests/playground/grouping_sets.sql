@@ -0,0 +1,13 @@
+-- subtotals per region and product, then per region, then the grand total
+-- mysql only has ROLLUP and sqlite none of the three, `check` reports the others there (E0221)
+FROM sales
+|> AGGREGATE SUM(amount)
+GROUP BY ROLLUP(region, product);
+
+FROM sales
+|> AGGREGATE SUM(amount)
+GROUP BY CUBE(region, product);
+
+FROM sales
+|> AGGREGATE SUM(amount)
+GROUP BY GROUPING SETS ((region, product), region, ());
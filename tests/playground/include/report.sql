@@ -0,0 +1,6 @@
+-- `hoshi check --schema schema/schema.json -I include/shared.sql include/report.sql` passes,
+-- without `-I` `big_spenders` is an unknown table; only this file's sql is emitted:
+-- FROM big_spenders JOIN users ON big_spenders.user_id = users.id SELECT name, total
+FROM big_spenders
+|> JOIN users ON big_spenders.user_id = users.id
+|> SELECT name, total;
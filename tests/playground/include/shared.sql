@@ -0,0 +1,3 @@
+-- the named queries report.sql reads, parsed first with `-I include/shared.sql`
+WITH big_spenders AS (FROM orders |> WHERE total > 100 |> SELECT user_id, total)
+FROM big_spenders |> SELECT user_id;
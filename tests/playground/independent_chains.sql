@@ -0,0 +1,7 @@
+-- the second chain reuses `id` without a `;`, that is not a duplicate column
+FROM users
+|> SELECT id, name
+|> LIMIT 10
+
+FROM orders
+|> SELECT id, total_amount
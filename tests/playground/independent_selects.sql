@@ -0,0 +1,3 @@
+-- Exemplo 9: Duas consultas independentes no mesmo arquivo
+SELECT customer_id, customer_name FROM customers;
+SELECT customer_id, total_amount FROM orders;
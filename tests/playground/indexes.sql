@@ -0,0 +1,8 @@
+-- indexes for the join and filter columns of the other queries
+CREATE INDEX orders_customer ON orders (customer_id);
+CREATE UNIQUE INDEX customers_email ON customers (email, tenant_id);
+DROP INDEX orders_customer ON orders;
+
+-- index and unique still name columns in lowercase
+FROM changes
+|> SELECT index, unique;
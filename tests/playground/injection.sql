@@ -0,0 +1,12 @@
+-- W0218: a quote closing the string, then a tautology
+FROM users
+|> WHERE name = "x' OR '1'='1"
+|> SELECT id;
+
+-- W0218: a second statement and a comment hidden in inserted values
+INSERT INTO users (id, name) VALUES (1, "a'; DROP TABLE users"), (2, 'admin --');
+
+-- plain strings are left alone
+FROM users
+|> WHERE name = 'O Brien' AND role = 'or else'
+|> SELECT id;
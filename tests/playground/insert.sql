@@ -0,0 +1,15 @@
+-- rows written out
+INSERT INTO customers (id, name, active) VALUES (1, 'alice', 1), (2, 'bob', 0);
+
+-- every column of the table, in order
+INSERT INTO customers VALUES (3, 'carol', 1);
+
+-- the rows of a plain query
+INSERT INTO summary (dept, headcount) SELECT dept, COUNT(id) FROM employees GROUP BY dept;
+
+-- the rows of a pipe, its stages stay inside the INSERT
+INSERT INTO totals (total)
+FROM orders
+|> WHERE status = 'paid'
+|> AGGREGATE SUM(total_amount) AS total
+GROUP BY customer_id;
@@ -0,0 +1,3 @@
+FROM orders
+|> WHERE created_at > NOW() - INTERVAL '7 days'
+|> SELECT id, created_at + INTERVAL '2' WEEK AS due
@@ -0,0 +1,6 @@
+-- `check --format json` writes one object per diagnostic, diagnostics.jsonl is what it prints for this file
+FROM orders AS o
+|> SELECT id, id;
+
+FROM orders
+|> SELECT customer_id, SUM(total_amount) AS spent;
@@ -0,0 +1,6 @@
+-- W0102: a keyword after AS is read as a name, `check --fix` lowercases it
+FROM orders
+|> AGGREGATE COUNT(id) AS COUNT GROUP BY customer_id;
+
+FROM orders AS o
+|> SELECT o.total AS SUM, o.id AS count;
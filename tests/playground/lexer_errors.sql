@@ -0,0 +1,21 @@
+FROM users
+|> WHERE age > 18 # 1
+|> SELECT name;
+
+FROM users
+|> WHERE age ! 18
+|> SELECT name;
+
+FROM users | SELECT name;
+
+FROM users
+|> WHERE name = 'unterminated
+|> SELECT name;
+
+FROM users
+|> WHERE age > 18 # @ ~
+|> SELECT name, email
+
+FROM orders
+|> WHERE total_amount > 1.2.3
+|> SELECT id;
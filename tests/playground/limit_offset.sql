@@ -0,0 +1,8 @@
+-- both spellings compile to the same LIMIT, the comma one also warns with a fix
+FROM orders
+|> SELECT id, total_amount
+|> LIMIT 10 OFFSET 20;
+
+FROM orders
+|> SELECT id, total_amount
+|> LIMIT 10, 20;
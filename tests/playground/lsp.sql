@@ -0,0 +1,3 @@
+-- for `hoshi check --format lsp`, the 🎉 is two utf-16 code units so the repeated `id` starts at character 29
+FROM orders
+|> SELECT '🎉' AS party, id, id;
@@ -0,0 +1,10 @@
+-- `fmt --max-width 40 --stdout` fills each line with as many columns or conditions as fit:
+-- FROM orders
+-- |> WHERE total_amount > 100
+--          AND status = 'paid'
+-- |> SELECT id, customer_id, total_amount,
+--           status, created_at;
+-- with `--max-width 200` both stages stay on one line, as they are written below
+FROM orders
+|> WHERE total_amount > 100 AND status = 'paid'
+|> SELECT id, customer_id, total_amount, status, created_at;
@@ -0,0 +1,3 @@
+FROM clientes
+|> WHERE nome = 'ação'
+|> SELECT nome, cidade
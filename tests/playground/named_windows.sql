@@ -0,0 +1,4 @@
+-- one named window shared by two rankings, hoisted to the end of the query
+FROM employees
+|> WINDOW w AS (PARTITION BY dept ORDER BY salary DESC)
+|> SELECT name, RANK() OVER w AS salary_rank, ROW_NUMBER() OVER w AS position;
@@ -0,0 +1,15 @@
+FROM orders
+|> WHERE customer_id NOT IN (1, 2, 3)
+|> SELECT id, customer_id;
+
+FROM orders
+|> WHERE customer_id NOT IN (FROM customers |> WHERE status = 'inactive' |> SELECT id)
+|> SELECT id;
+
+FROM orders
+|> WHERE NOT (customer_id IN (1))
+|> SELECT id;
+
+FROM orders
+|> WHERE status IN ('paid', 'shipped')
+|> SELECT id;
@@ -0,0 +1,3 @@
+FROM employees
+|> ORDER BY last_name ASC, hired_at DESC, first_name
+|> SELECT first_name, last_name, hired_at
@@ -0,0 +1,12 @@
+-- ORDER BY keys that resolve: positions, aliases, qualified and grouped columns
+FROM employees AS e
+|> SELECT e.name, e.salary AS pay
+|> ORDER BY 2 DESC, pay, e.name;
+
+FROM employees
+|> SELECT employees.name, employees.dept
+|> ORDER BY employees.dept, 1;
+
+FROM employees
+|> AGGREGATE SUM(salary) AS total GROUP BY dept
+|> ORDER BY total DESC, dept, 1;
@@ -0,0 +1,13 @@
+-- postgres keeps the ordered-set aggregates:
+-- SELECT PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY salary ASC) AS median FROM employees
+-- mysql and sqlite rank the values in a subquery and take the first one at the percentile:
+-- SELECT (SELECT value FROM (SELECT salary AS value, ROW_NUMBER() OVER (ORDER BY salary IS NULL, salary DESC) AS position,
+--   COUNT(salary) OVER () AS total FROM employees WHERE active = 1) AS ranked WHERE position >= 0.9 * total
+--   ORDER BY position LIMIT 1) AS top_decile
+-- which is exact for PERCENTILE_DISC, PERCENTILE_CONT warns with W0220 and a GROUP BY is E0219 there
+FROM employees
+|> AGGREGATE PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY salary) AS median;
+
+FROM employees
+|> WHERE active = 1
+|> AGGREGATE PERCENTILE_DISC(0.9) WITHIN GROUP (ORDER BY salary DESC) AS top_decile;
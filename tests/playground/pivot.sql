@@ -0,0 +1,7 @@
+-- one column per quarter, then the quarters back into rows
+FROM sales
+|> PIVOT (SUM(amount) FOR quarter IN ('Q1', 'Q2', 'Q3', 'Q4'))
+|> GROUP BY region;
+
+FROM quarterly_sales
+|> UNPIVOT (amount FOR quarter IN (q1, q2));
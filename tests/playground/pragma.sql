@@ -0,0 +1,9 @@
+-- sqlite settings, left out with a warning for the other dialects
+PRAGMA foreign_keys = ON;
+PRAGMA journal_mode = WAL;
+PRAGMA busy_timeout = 5000;
+
+-- introspection, the table is looked up in the schema
+PRAGMA table_info(users);
+PRAGMA index_info(users_email);
+PRAGMA integrity_check;
@@ -0,0 +1,20 @@
+-- `hoshi ast pretty_print.sql --format spipe` writes the program back as pipes, keeping the stages sql would
+-- rewrite and only the parentheses the parser needs; reading that output back gives the same program:
+-- FROM orders
+-- |> WHERE (status = 'paid' OR status = 'sent') AND total_amount > 10
+-- |> SELECT id, (total_amount > 100) AS large, (total_amount + 1)::int AS rounded;
+--
+-- FROM orders
+-- |> AGGREGATE SUM(total_amount) FILTER (WHERE status = 'paid') AS spent
+-- GROUP BY customer_id;
+--
+-- FROM orders
+-- |> WHERE customer_id IN (FROM customers |> WHERE active = TRUE |> SELECT id)
+-- |> PIVOT (SUM(total_amount) FOR status IN ('paid', 'sent'));
+FROM orders |> WHERE ((status = 'paid' OR status = 'sent')) AND total_amount > 10
+  |> SELECT id, (total_amount > 100) AS large, (total_amount + 1)::int AS rounded;
+
+FROM orders |> AGGREGATE SUM(total_amount) FILTER (WHERE status = 'paid') AS spent GROUP BY customer_id;
+
+FROM orders |> WHERE customer_id IN (FROM customers |> WHERE active = TRUE |> SELECT id)
+  |> PIVOT (SUM(total_amount) FOR status IN ('paid', 'sent'));
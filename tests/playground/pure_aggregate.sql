@@ -0,0 +1,2 @@
+-- Exemplo 11: Agregação sem GROUP BY
+SELECT COUNT(order_id), SUM(total_amount) FROM orders;
@@ -0,0 +1,4 @@
+-- `o.id` and `o.total_amount` are two columns of the same table, not a duplicate
+FROM orders AS o
+|> JOIN customers AS c ON c.id = o.customer_id
+|> SELECT o.id, o.total_amount, c.name;
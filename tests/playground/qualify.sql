@@ -0,0 +1,4 @@
+-- the latest order of each customer
+FROM orders
+|> WHERE status = 'paid'
+|> QUALIFY ROW_NUMBER() OVER (PARTITION BY customer_id ORDER BY created_at DESC) = 1;
@@ -0,0 +1,8 @@
+-- a range test is two comparisons joined with AND
+FROM orders
+|> WHERE 10 < total_amount AND total_amount < 100
+|> SELECT id;
+
+FROM orders
+|> WHERE NOT total_amount > 100 AND discount <= total_amount
+|> SELECT id;
@@ -0,0 +1,16 @@
+WITH RECURSIVE tree (id, parent_id, depth) AS (
+  FROM categories
+  |> WHERE parent_id = 0
+  |> SELECT id, parent_id, 1 AS depth
+  UNION ALL
+  FROM categories
+  |> JOIN tree ON categories.parent_id = tree.id
+  |> WHERE tree.depth < 10
+  |> SELECT categories.id, categories.parent_id, tree.depth + 1
+)
+FROM tree
+|> ORDER BY depth;
+
+WITH recent AS (FROM orders |> WHERE total > 100)
+FROM recent
+|> SELECT customer_id;
@@ -0,0 +1,5 @@
+-- several diagnostics for `hoshi check --format sarif` and `--format github`
+FROM orders AS o
+|> JOIN customers AS o ON o.id = customer_id
+|> SELECT id, total_amount
+|> LIMIT 10, 20;
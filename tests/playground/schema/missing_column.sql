@@ -0,0 +1,4 @@
+FROM users AS u
+|> JOIN orders AS o ON u.id = o.user_id
+|> WHERE o.amount > 10
+|> SELECT u.name, nickname, o.total AS spent
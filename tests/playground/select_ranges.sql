@@ -0,0 +1,4 @@
+-- each aliased expression spans from its first token to its alias, debug builds assert ranges never run backwards
+FROM orders
+|> SELECT id AS order_id, price * quantity AS revenue, CAST(total AS int) AS whole, total::text AS label,
+   COUNT(id) OVER (PARTITION BY customer_id) AS per_customer, CURRENT_DATE AS today;
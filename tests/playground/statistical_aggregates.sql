@@ -0,0 +1,11 @@
+FROM measurements
+|> AGGREGATE STDDEV_SAMP(reading);
+
+FROM measurements
+|> AGGREGATE VAR_POP(reading);
+
+FROM flags
+|> AGGREGATE BIT_AND(mask);
+
+FROM flags
+|> AGGREGATE BOOL_OR(enabled)
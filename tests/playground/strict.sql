@@ -0,0 +1,3 @@
+-- only a warning, so `hoshi check` passes while `hoshi check --strict` (or `-W error`) exits 1
+FROM orders
+|> SELECT id, customer_id,;
@@ -0,0 +1,14 @@
+-- a piped subquery is emitted SELECT first, the way the outer query reads it
+FROM orders
+|> WHERE customer_id IN (FROM customers |> WHERE active = TRUE |> SELECT id)
+|> SELECT id;
+
+-- ORDER BY and LIMIT stay after the moved SELECT
+FROM orders
+|> WHERE customer_id IN (FROM customers |> WHERE region = 'EU' |> SELECT id |> ORDER BY id |> LIMIT 10)
+|> SELECT id;
+
+-- an aggregating subquery was already SELECT first
+FROM orders
+|> WHERE total > (FROM orders |> AGGREGATE AVG(total) AS average)
+|> SELECT id;
@@ -0,0 +1,9 @@
+-- spipe:allow-file(missing-join-index)
+
+-- spipe:allow(unused-alias)
+FROM orders AS o
+|> JOIN customers AS c ON id = customer_id
+|> SELECT id, id;
+
+FROM orders AS o -- spipe:allow(duplicate-column, no-such-rule)
+|> SELECT id, id;
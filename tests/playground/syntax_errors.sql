@@ -0,0 +1,10 @@
+FROM users
+|> WHERE age > > 18
+|> SELECT name, email;
+
+FROM orders
+|> JOIN ON orders.id = users.id
+|> SELECT id;
+
+FROM products
+|> LIMIT ten
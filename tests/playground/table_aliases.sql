@@ -0,0 +1,5 @@
+-- Exemplo 10: Aliases de tabela
+FROM orders AS o
+|> JOIN customers AS c ON o.customer_id = c.customer_id
+|> WHERE o.total_amount > 100
+|> ORDER BY o.total_amount DESC;
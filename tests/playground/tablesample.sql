@@ -0,0 +1,7 @@
+-- reads about a tenth of the orders, natively where the dialect has TABLESAMPLE
+FROM orders TABLESAMPLE BERNOULLI(10)
+|> WHERE status = 'paid'
+|> SELECT id, total_amount;
+
+FROM orders TABLESAMPLE SYSTEM(25)
+|> SELECT id;
@@ -0,0 +1,14 @@
+-- each WHERE below compares a value with itself, `check` warns with W0221 three times:
+-- `price = price`, `1 = 1` and `o.status = o.status`; `price = o.price` may be two tables and is left alone
+FROM products
+|> WHERE price = price AND stock > 0
+|> SELECT id;
+
+FROM products
+|> WHERE 1 = 1 OR name = 'x'
+|> SELECT id;
+
+FROM orders AS o
+|> JOIN products ON products.id = o.product_id
+|> WHERE o.status = o.status AND price = o.price
+|> SELECT o.id;
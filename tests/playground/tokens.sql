@@ -0,0 +1,16 @@
+-- `hoshi tokens tokens.sql` lists every token the lexer produces, one per line, ending with
+-- 1172..1173  Error `unexpected character '#'`
+-- 1174..1174  EOF
+-- `--format json` gives them as an array; the header lines are Comment tokens too
+-- tokens.txt holds the whole listing, a test of the lexer compares it with `dump_tokens`
+SELECT FROM WHERE ORDER BY ASC DESC LIMIT OFFSET JOIN ON GROUP HAVING AS UNION WITH CASE END WITHIN
+AND OR NOT IN IS LIKE BETWEEN EXISTS NULL DISTINCT INSERT INTO VALUES UPDATE SET DELETE
+CREATE TABLE ALTER DROP TRUNCATE INDEX UNIQUE COMMENT GRANT REVOKE BEGIN COMMIT ROLLBACK SAVEPOINT RELEASE
+RECURSIVE PRAGMA AGGREGATE INTERVAL FILTER ROLLUP CUBE GROUPING SETS PIVOT UNPIVOT FOR OVER PARTITION
+QUALIFY WINDOW CAST COPY TO STDIN STDOUT TABLESAMPLE BERNOULLI SYSTEM BLOCK
+CURRENT_TIMESTAMP CURRENT_DATE CURRENT_TIME
+COUNT SUM AVG MIN MAX STDDEV STDDEV_POP STDDEV_SAMP VAR_POP VAR_SAMP VARIANCE FIRST LAST GROUP_CONCAT
+STRING_AGG MEDIAN MODE ARRAY_AGG JSON_AGG JSON_OBJECT_AGG BIT_AND BIT_OR BOOL_AND BOOL_OR PERCENTILE_CONT PERCENTILE_DISC
+orders 'paid' "sent" true false 42 1.5
++ - * / % = != < > <= >= , ; ( ) [ ] . : :: |> -- trailing
+#
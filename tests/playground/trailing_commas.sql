@@ -0,0 +1,6 @@
+-- a dangling comma at the end of a list is dropped with a warning
+FROM orders
+|> SELECT id, customer_id,
+|> GROUP BY id, customer_id,;
+
+SELECT id, total_amount, FROM orders;
@@ -0,0 +1,6 @@
+-- Exemplo 8: Comentários no fim da linha
+FROM orders -- only paid orders are stored here
+|> WHERE orders.total_amount > 100 -- big spenders
+|> SELECT id, total_amount -- what the report shows
+|> ORDER BY orders.total_amount DESC
+LIMIT 10;
@@ -0,0 +1,7 @@
+BEGIN;
+INSERT INTO orders (id, total) VALUES (1, 20);
+SAVEPOINT before_refund;
+INSERT INTO orders (id, total) VALUES (2, 0);
+ROLLBACK TO SAVEPOINT before_refund;
+RELEASE before_refund;
+COMMIT;
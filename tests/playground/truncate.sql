@@ -0,0 +1,6 @@
+-- empties the table, warned about
+TRUNCATE TABLE orders;
+
+-- TABLE is optional
+-- spipe:allow(destructive-operation)
+TRUNCATE staging_orders;
@@ -0,0 +1,13 @@
+-- a row value on the left of IN compares whole rows, kept as written in ansi, postgres and mysql;
+-- sqlite compares row values only with a subquery, so there the lists become equalities (I0201):
+-- WHERE (customer_id = 1 AND status = 'paid' OR customer_id = 2 AND status = 'sent')
+-- WHERE NOT (customer_id = 3 AND status = 'refunded')
+-- a row of another width, e.g. `IN ((1, 'paid'), (2))`, is E0218
+FROM orders
+|> WHERE (customer_id, status) IN ((1, 'paid'), (2, 'sent'))
+|> WHERE (customer_id, status) NOT IN ((3, 'refunded'))
+|> SELECT id;
+
+FROM orders
+|> WHERE (customer_id, status) IN (FROM refunds |> SELECT customer_id, status)
+|> SELECT id;
@@ -0,0 +1,4 @@
+-- multi-byte characters and tabs before an error keep the caret under the token
+FROM cafes -- ☕ olá, açúcar 🎉
+|> WHERE name = 'café 🎉' AND total > > 10
+|>	SELECT	name, 'ñ 名前' AS label, @;
@@ -0,0 +1,8 @@
+-- ORDER BY keys that do not resolve
+FROM employees AS e
+|> SELECT e.name, e.salary
+|> ORDER BY 3, x.name;
+
+FROM employees
+|> AGGREGATE SUM(salary) AS total GROUP BY dept
+|> ORDER BY salary DESC, 0;
@@ -0,0 +1,27 @@
+-- W0217: the eleventh distinct table joined
+FROM t1
+|> JOIN t2 ON t1.id = t2.id
+|> JOIN t3 ON t2.id = t3.id
+|> JOIN t4 ON t3.id = t4.id
+|> JOIN t5 ON t4.id = t5.id
+|> JOIN t6 ON t5.id = t6.id
+|> JOIN t7 ON t6.id = t7.id
+|> JOIN t8 ON t7.id = t8.id
+|> JOIN t9 ON t8.id = t9.id
+|> JOIN t10 ON t9.id = t10.id
+|> JOIN t11 ON t10.id = t11.id
+|> SELECT t1.id;
+
+-- joining a table again does not count it twice
+FROM t1
+|> JOIN t2 ON t1.id = t2.id
+|> JOIN t3 ON t2.id = t3.id
+|> JOIN t4 ON t3.id = t4.id
+|> JOIN t5 ON t4.id = t5.id
+|> JOIN t6 ON t5.id = t6.id
+|> JOIN t7 ON t6.id = t7.id
+|> JOIN t8 ON t7.id = t8.id
+|> JOIN t9 ON t8.id = t9.id
+|> JOIN t10 ON t9.id = t10.id
+|> JOIN t2 ON t10.id = t2.id
+|> SELECT t1.id;